@@ -1,18 +1,28 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
 use rust_extensions::{AppStates, ApplicationStates};
 use tokio::sync::RwLock;
 
 use crate::{
-    grpc_client::{MessagesPagesRepo, TopicsAndQueuesSnapshotRepo},
+    auth::{AdminAccess, Authenticator, TopicAcl},
+    cluster::ClusterTopology,
+    grpc_client::{
+        MessagesPagesRepo, MessagesWal, PersistenceCircuitBreaker, TopicsAndQueuesSnapshotRepo,
+    },
     queue_subscribers::SubscriberIdGenerator,
+    replication::ReplicationState,
     sessions::SessionsList,
     settings::SettingsModel,
+    telemetry::Telemetry,
     topics::TopicsList,
     utils::MultiThreadedShortString,
+    webhooks::WebhookPublisher,
 };
 
-use super::{prometheus_metrics::PrometheusMetrics, ImmediatelyPersistEventLoop};
+use super::{
+    logs::Logs, prometheus_metrics::PrometheusMetrics, ImmediatelyPersistEventLoop, ListenersList,
+    PageLoadsList, PublishRateLimiter, RuntimeSettings,
+};
 
 pub const APP_VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
@@ -27,13 +37,31 @@ pub struct AppContext {
     pub topic_list: TopicsList,
     pub topics_and_queues_repo: Arc<TopicsAndQueuesSnapshotRepo>,
     pub messages_pages_repo: Arc<MessagesPagesRepo>,
+    pub persistence_circuit_breaker: PersistenceCircuitBreaker,
+    pub messages_wal: Option<MessagesWal>,
+    pub replication: ReplicationState,
+    pub cluster: Option<ClusterTopology>,
+    pub auth: Option<Authenticator>,
+    pub topic_acl: Option<TopicAcl>,
+    pub admin_access: AdminAccess,
     pub sessions: SessionsList,
+    pub listeners: ListenersList,
     pub process_id: String,
     pub subscriber_id_generator: SubscriberIdGenerator,
 
     pub prometheus: PrometheusMetrics,
 
-    pub delivery_timeout: Duration,
+    pub runtime_settings: RuntimeSettings,
+
+    pub publish_rate_limiter: PublishRateLimiter,
+
+    pub page_loads: PageLoadsList,
+
+    pub telemetry: Telemetry,
+
+    pub logs: Arc<Logs>,
+
+    pub webhooks: WebhookPublisher,
 
     pub debug_topic_and_queue: RwLock<Option<DebugTopicAndQueue>>,
 
@@ -48,22 +76,50 @@ impl AppContext {
     pub async fn new(settings: SettingsModel) -> Self {
         let topics_and_queues_repo = settings.create_topics_and_queues_snapshot_repo().await;
         let messages_pages_repo = settings.create_messages_pages_repo().await;
+
+        let messages_wal = match &settings.wal_path {
+            Some(wal_path) => Some(MessagesWal::new(wal_path.as_str()).await),
+            None => None,
+        };
+
+        let cluster = match &settings.cluster {
+            Some(cluster_settings) => Some(ClusterTopology::new(cluster_settings).await),
+            None => None,
+        };
+
+        let auth = match &settings.auth {
+            Some(auth_settings) => match Authenticator::new(auth_settings).await {
+                Ok(auth) => Some(auth),
+                Err(err) => panic!("{}", err),
+            },
+            None => None,
+        };
+
         Self {
             states: Arc::new(AppStates::create_un_initialized()),
             topic_list: TopicsList::new(),
             topics_and_queues_repo: Arc::new(topics_and_queues_repo),
             messages_pages_repo: Arc::new(messages_pages_repo),
+            persistence_circuit_breaker: PersistenceCircuitBreaker::new(),
+            messages_wal,
+            replication: ReplicationState::new(settings.replication_role),
+            cluster,
+            auth,
+            topic_acl: settings.topic_acl.clone().map(TopicAcl::new),
+            admin_access: AdminAccess::new(&settings),
             sessions: SessionsList::new(),
+            listeners: ListenersList::new(&settings),
             process_id: uuid::Uuid::new_v4().to_string(),
 
             subscriber_id_generator: SubscriberIdGenerator::new(),
             prometheus: PrometheusMetrics::new(),
 
-            delivery_timeout: if let Some(delivery_timeout) = settings.delivery_timeout {
-                delivery_timeout
-            } else {
-                Duration::from_secs(30)
-            },
+            runtime_settings: RuntimeSettings::new(&settings),
+            publish_rate_limiter: PublishRateLimiter::new(&settings),
+            page_loads: PageLoadsList::new(&settings),
+            telemetry: Telemetry::new(settings.open_telemetry.as_ref()),
+            logs: Arc::new(Logs::new(settings.logs_buffer_size)),
+            webhooks: WebhookPublisher::new(&settings),
             debug_topic_and_queue: RwLock::new(None),
             immediately_persist_event_loop: ImmediatelyPersistEventLoop::new(),
             persistence_version: MultiThreadedShortString::new(),
@@ -87,7 +143,7 @@ impl AppContext {
     }
 
     pub fn get_max_delivery_size(&self) -> usize {
-        self.settings.max_delivery_size
+        self.runtime_settings.get_max_delivery_size()
     }
 }
 
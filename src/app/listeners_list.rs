@@ -0,0 +1,128 @@
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use crate::settings::{ReplicationRole, SettingsModel};
+
+pub const TCP_PROTOCOL: &str = "tcp";
+pub const HTTP_PROTOCOL: &str = "http";
+pub const WS_PROTOCOL: &str = "ws";
+pub const GRPC_PROTOCOL: &str = "grpc";
+pub const MQTT_PROTOCOL: &str = "mqtt";
+pub const REPLICATION_PROTOCOL: &str = "replication";
+
+pub struct ListenerStatus {
+    pub protocol: &'static str,
+    pub name: String,
+    pub address: SocketAddr,
+    // Sessions are created only for authenticated applications
+    pub require_auth: bool,
+    connections: AtomicUsize,
+}
+
+impl ListenerStatus {
+    pub fn new(
+        protocol: &'static str,
+        name: String,
+        address: SocketAddr,
+        require_auth: bool,
+    ) -> Self {
+        Self {
+            protocol,
+            name,
+            address,
+            require_auth,
+            connections: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn connected(&self) {
+        self.connections.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn disconnected(&self) {
+        self.connections.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    pub fn set_connections(&self, value: usize) {
+        self.connections.store(value, Ordering::SeqCst);
+    }
+
+    pub fn get_connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+}
+
+pub struct ListenersList {
+    items: Vec<Arc<ListenerStatus>>,
+}
+
+impl ListenersList {
+    pub fn new(settings: &SettingsModel) -> Self {
+        let mut items: Vec<Arc<ListenerStatus>> = settings
+            .tcp_listeners
+            .iter()
+            .map(|itm| {
+                Arc::new(ListenerStatus::new(
+                    TCP_PROTOCOL,
+                    itm.name.to_string(),
+                    itm.address,
+                    settings.auth.is_some() && itm.require_auth.unwrap_or(true),
+                ))
+            })
+            .collect();
+
+        items.push(Arc::new(ListenerStatus::new(
+            HTTP_PROTOCOL,
+            HTTP_PROTOCOL.to_string(),
+            settings.http_address,
+            settings.auth.is_some(),
+        )));
+
+        let transports = [
+            (WS_PROTOCOL, settings.web_socket_address),
+            (GRPC_PROTOCOL, settings.grpc_server_address),
+            (MQTT_PROTOCOL, settings.mqtt_address),
+        ];
+
+        for (protocol, address) in transports {
+            if let Some(address) = address {
+                items.push(Arc::new(ListenerStatus::new(
+                    protocol,
+                    protocol.to_string(),
+                    address,
+                    settings.auth.is_some(),
+                )));
+            }
+        }
+
+        // Passive nodes are authenticated with replication secret
+        if let Some(ReplicationRole::Active) = settings.replication_role {
+            if let Some(address) = settings.replication_address {
+                items.push(Arc::new(ListenerStatus::new(
+                    REPLICATION_PROTOCOL,
+                    REPLICATION_PROTOCOL.to_string(),
+                    address,
+                    true,
+                )));
+            }
+        }
+
+        Self { items }
+    }
+
+    pub fn get(&self, protocol: &str, name: &str) -> Option<Arc<ListenerStatus>> {
+        self.items
+            .iter()
+            .find(|itm| itm.protocol == protocol && itm.name == name)
+            .cloned()
+    }
+
+    pub fn get_all(&self) -> &[Arc<ListenerStatus>] {
+        self.items.as_slice()
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+
+use my_logger::MyLogEvent;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+const TOPIC_ID_CTX_KEY: &str = "topicId";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Info,
+    Warning,
+    Error,
+    FatalError,
+    Debug,
+}
+
+impl LogLevel {
+    pub fn parse(src: &str) -> Option<Self> {
+        Self::iterate()
+            .into_iter()
+            .find(|level| format!("{:?}", level).eq_ignore_ascii_case(src))
+    }
+
+    pub fn iterate() -> [Self; 5] {
+        [
+            Self::Info,
+            Self::Warning,
+            Self::Error,
+            Self::FatalError,
+            Self::Debug,
+        ]
+    }
+}
+
+impl From<&my_logger::LogLevel> for LogLevel {
+    fn from(src: &my_logger::LogLevel) -> Self {
+        match src {
+            my_logger::LogLevel::Info => Self::Info,
+            my_logger::LogLevel::Warning => Self::Warning,
+            my_logger::LogLevel::Error => Self::Error,
+            my_logger::LogLevel::FatalError => Self::FatalError,
+            my_logger::LogLevel::Debug => Self::Debug,
+        }
+    }
+}
+
+pub struct LogItem {
+    pub date: DateTimeAsMicroseconds,
+    pub level: LogLevel,
+    pub topic: Option<String>,
+    pub process: String,
+    pub message: String,
+    pub ctx: Option<HashMap<String, String>>,
+}
+
+impl From<&MyLogEvent> for LogItem {
+    fn from(src: &MyLogEvent) -> Self {
+        let topic = src
+            .context
+            .as_ref()
+            .and_then(|ctx| ctx.get(TOPIC_ID_CTX_KEY))
+            .cloned();
+
+        Self {
+            date: src.dt,
+            level: (&src.level).into(),
+            topic,
+            process: src.process.to_string(),
+            message: src.message.to_string(),
+            ctx: src.context.clone(),
+        }
+    }
+}
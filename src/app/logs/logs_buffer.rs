@@ -0,0 +1,108 @@
+use std::{
+    collections::{BTreeSet, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use my_logger::{MyLogEvent, MyLoggerReader};
+
+use super::{LogItem, LogsFilter};
+
+// Ring buffer of the latest log events. Oldest events are dropped when capacity is reached
+pub struct Logs {
+    items: Mutex<VecDeque<Arc<LogItem>>>,
+    capacity: usize,
+}
+
+impl Logs {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            items: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn add(&self, item: LogItem) {
+        let mut items = self.items.lock().unwrap();
+
+        while items.len() >= self.capacity {
+            items.pop_front();
+        }
+
+        items.push_back(Arc::new(item));
+    }
+
+    // Newest first
+    pub fn get(&self, filter: &LogsFilter) -> Vec<Arc<LogItem>> {
+        let items = self.items.lock().unwrap();
+
+        items
+            .iter()
+            .rev()
+            .filter(|item| filter.matches(item))
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_processes(&self) -> Vec<String> {
+        let items = self.items.lock().unwrap();
+
+        let processes: BTreeSet<&str> = items.iter().map(|item| item.process.as_str()).collect();
+
+        processes.into_iter().map(|itm| itm.to_string()).collect()
+    }
+}
+
+impl MyLoggerReader for Logs {
+    fn write_log(&self, log_event: &MyLogEvent) {
+        self.add(log_event.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+    use crate::app::logs::LogLevel;
+
+    fn create_item(unix_microseconds: i64, level: LogLevel, topic: Option<&str>) -> LogItem {
+        LogItem {
+            date: DateTimeAsMicroseconds::new(unix_microseconds),
+            level,
+            topic: topic.map(|itm| itm.to_string()),
+            process: "test".to_string(),
+            message: "test".to_string(),
+            ctx: None,
+        }
+    }
+
+    #[test]
+    fn test_oldest_items_are_dropped_and_filter_is_applied() {
+        let logs = Logs::new(3);
+
+        logs.add(create_item(1, LogLevel::Error, Some("topic-1")));
+        logs.add(create_item(2, LogLevel::Info, Some("topic-1")));
+        logs.add(create_item(3, LogLevel::Error, Some("topic-2")));
+        logs.add(create_item(4, LogLevel::Error, Some("topic-1")));
+
+        let all = logs.get(&LogsFilter::default());
+        let dates: Vec<i64> = all.iter().map(|itm| itm.date.unix_microseconds).collect();
+        assert_eq!(dates, vec![4, 3, 2]);
+
+        let filter = LogsFilter {
+            topic_id: Some("topic-1".to_string()),
+            level: Some(LogLevel::Error),
+            ..Default::default()
+        };
+        let result = logs.get(&filter);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].date.unix_microseconds, 4);
+
+        let filter = LogsFilter {
+            from: Some(DateTimeAsMicroseconds::new(2)),
+            to: Some(DateTimeAsMicroseconds::new(3)),
+            ..Default::default()
+        };
+        assert_eq!(logs.get(&filter).len(), 2);
+    }
+}
@@ -0,0 +1,48 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::{LogItem, LogLevel};
+
+#[derive(Default)]
+pub struct LogsFilter {
+    pub topic_id: Option<String>,
+    pub process: Option<String>,
+    pub level: Option<LogLevel>,
+    pub from: Option<DateTimeAsMicroseconds>,
+    pub to: Option<DateTimeAsMicroseconds>,
+}
+
+impl LogsFilter {
+    pub fn matches(&self, item: &LogItem) -> bool {
+        if let Some(topic_id) = &self.topic_id {
+            if item.topic.as_deref() != Some(topic_id.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(process) = &self.process {
+            if item.process != *process {
+                return false;
+            }
+        }
+
+        if let Some(level) = self.level {
+            if item.level != level {
+                return false;
+            }
+        }
+
+        if let Some(from) = self.from {
+            if item.date.unix_microseconds < from.unix_microseconds {
+                return false;
+            }
+        }
+
+        if let Some(to) = self.to {
+            if item.date.unix_microseconds > to.unix_microseconds {
+                return false;
+            }
+        }
+
+        true
+    }
+}
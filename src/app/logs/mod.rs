@@ -0,0 +1,7 @@
+mod log_item;
+mod logs_buffer;
+mod logs_filter;
+
+pub use log_item::*;
+pub use logs_buffer::*;
+pub use logs_filter::*;
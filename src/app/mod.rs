@@ -1,8 +1,17 @@
 mod app_ctx;
+mod listeners_list;
+pub mod logs;
+mod page_loads_list;
 pub mod prometheus_metrics;
+mod publish_rate_limiter;
+mod runtime_settings;
 pub mod shutdown;
 
 pub use app_ctx::AppContext;
 pub use app_ctx::APP_VERSION;
+pub use listeners_list::*;
+pub use page_loads_list::*;
+pub use publish_rate_limiter::*;
+pub use runtime_settings::*;
 mod immediately_persist_event_loop;
 pub use immediately_persist_event_loop::*;
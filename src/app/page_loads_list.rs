@@ -0,0 +1,89 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use my_service_bus::shared::sub_page::SubPageId;
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::settings::SettingsModel;
+
+// Sub pages which are being loaded from persistence. Requests for the sub page which is already
+// being loaded are coalesced, and amount of parallel loads from persistence is limited
+pub struct PageLoadsList {
+    // Value is true if sub page has to be deleted and loaded again after the load in flight
+    in_flight: Mutex<HashMap<(String, i64), bool>>,
+    permits: Semaphore,
+}
+
+impl PageLoadsList {
+    pub fn new(settings: &SettingsModel) -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+            permits: Semaphore::new(settings.max_concurrent_page_loads),
+        }
+    }
+
+    // Returns false if the sub page is already being loaded. Delete request is recorded on the load in flight
+    pub fn try_start(&self, topic_id: &str, sub_page_id: SubPageId, delete_page: bool) -> bool {
+        let mut in_flight = self.in_flight.lock().unwrap();
+
+        match in_flight.get_mut(&(topic_id.to_string(), sub_page_id.get_value())) {
+            Some(reload) => {
+                *reload |= delete_page;
+                false
+            }
+            None => {
+                in_flight.insert((topic_id.to_string(), sub_page_id.get_value()), false);
+                true
+            }
+        }
+    }
+
+    // Returns true if sub page was requested to be deleted and loaded again while it was being loaded
+    pub fn finish(&self, topic_id: &str, sub_page_id: SubPageId) -> bool {
+        let mut in_flight = self.in_flight.lock().unwrap();
+        in_flight
+            .remove(&(topic_id.to_string(), sub_page_id.get_value()))
+            .unwrap_or_default()
+    }
+
+    pub fn get_in_flight_amount(&self) -> usize {
+        self.in_flight.lock().unwrap().len()
+    }
+
+    pub async fn acquire_permit(&self) -> SemaphorePermit {
+        // Semaphore is never closed
+        self.permits.acquire().await.unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_sub_page_is_loaded_once() {
+        let page_loads = PageLoadsList::new(&SettingsModel::create_test_settings(16));
+
+        assert!(page_loads.try_start("test-topic", SubPageId::new(1), false));
+        assert!(!page_loads.try_start("test-topic", SubPageId::new(1), false));
+        assert!(page_loads.try_start("test-topic", SubPageId::new(2), false));
+        assert!(page_loads.try_start("other-topic", SubPageId::new(1), false));
+
+        assert!(!page_loads.finish("test-topic", SubPageId::new(1)));
+
+        assert!(page_loads.try_start("test-topic", SubPageId::new(1), false));
+        assert_eq!(page_loads.get_in_flight_amount(), 3);
+    }
+
+    #[test]
+    fn test_delete_request_is_recorded_on_load_in_flight() {
+        let page_loads = PageLoadsList::new(&SettingsModel::create_test_settings(16));
+
+        assert!(page_loads.try_start("test-topic", SubPageId::new(1), true));
+        assert!(!page_loads.finish("test-topic", SubPageId::new(1)));
+
+        assert!(page_loads.try_start("test-topic", SubPageId::new(1), false));
+        assert!(!page_loads.try_start("test-topic", SubPageId::new(1), true));
+        assert!(!page_loads.try_start("test-topic", SubPageId::new(1), false));
+        assert!(page_loads.finish("test-topic", SubPageId::new(1)));
+    }
+}
@@ -1,7 +1,14 @@
+use std::sync::Arc;
+
 use my_tcp_sockets::ThreadsStatistics;
-use prometheus::{Encoder, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder};
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry, TextEncoder,
+};
 
-use crate::messages_page::SizeMetrics;
+use crate::{grpc_client::PersistenceCircuitBreaker, messages_page::SizeMetrics};
 
 const TCP_METRIC: &str = "tcp_metric";
 
@@ -16,6 +23,20 @@ pub struct PrometheusMetrics {
     topic_messages_amount: IntGaugeVec,
     http_connections_amount: IntGauge,
     tcp_connections: IntGaugeVec,
+    persistence_state: IntGaugeVec,
+    acl_denied: IntCounterVec,
+    allocated_memory: IntGauge,
+    evicted_sub_pages: IntCounter,
+    sub_page_prefetch: IntCounterVec,
+    page_load_duration: HistogramVec,
+    page_load_failures: IntCounterVec,
+    page_loads_in_flight: IntGauge,
+    queue_oldest_message_age: GaugeVec,
+    queue_delivered_messages: IntCounterVec,
+    queue_redelivered_messages: IntCounterVec,
+    queue_failed_messages: IntCounterVec,
+    confirmation_duration: HistogramVec,
+    delivery_compilation_duration: HistogramVec,
 }
 
 impl PrometheusMetrics {
@@ -40,6 +61,112 @@ impl PrometheusMetrics {
 
         let tcp_connections = create_tcp_connections();
 
+        let persistence_state = create_persistence_state();
+
+        let acl_denied = create_acl_denied();
+
+        let allocated_memory = create_allocated_memory();
+
+        let evicted_sub_pages = create_evicted_sub_pages();
+
+        let sub_page_prefetch = create_sub_page_prefetch();
+
+        let page_load_duration = create_page_load_duration();
+
+        let page_load_failures = create_page_load_failures();
+
+        let page_loads_in_flight = create_page_loads_in_flight();
+
+        let queue_oldest_message_age = create_queue_oldest_message_age();
+
+        let queue_delivered_messages = create_queue_messages_counter(
+            "queue_delivered_messages",
+            "Messages which are confirmed as delivered",
+        );
+
+        let queue_redelivered_messages = create_queue_messages_counter(
+            "queue_redelivered_messages",
+            "Messages which are sent to subscriber again after failed delivery",
+        );
+
+        let queue_failed_messages = create_queue_messages_counter(
+            "queue_failed_messages",
+            "Messages which are confirmed as not delivered",
+        );
+
+        let confirmation_duration = create_queue_histogram(
+            "queue_confirmation_duration_seconds",
+            "Duration from the start of delivery till the confirmation",
+        );
+
+        let delivery_compilation_duration = create_queue_histogram(
+            "queue_delivery_compilation_duration_seconds",
+            "Duration of delivery package compilation",
+        );
+
+        for state in [
+            "degraded",
+            "circuit_open",
+            "consecutive_failures",
+            "total_failures",
+        ] {
+            persistence_state.with_label_values(&[state]).set(0);
+        }
+
+        registry
+            .register(Box::new(persistence_state.clone()))
+            .unwrap();
+
+        registry.register(Box::new(acl_denied.clone())).unwrap();
+
+        registry
+            .register(Box::new(allocated_memory.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(evicted_sub_pages.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(sub_page_prefetch.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(page_load_duration.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(page_load_failures.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(page_loads_in_flight.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(queue_oldest_message_age.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(queue_delivered_messages.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(queue_redelivered_messages.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(queue_failed_messages.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(confirmation_duration.clone()))
+            .unwrap();
+
+        registry
+            .register(Box::new(delivery_compilation_duration.clone()))
+            .unwrap();
+
         registry
             .register(Box::new(tcp_connections.clone()))
             .unwrap();
@@ -87,6 +214,20 @@ impl PrometheusMetrics {
             http_connections_amount,
             topic_mean_message_size,
             tcp_connections,
+            persistence_state,
+            acl_denied,
+            allocated_memory,
+            evicted_sub_pages,
+            sub_page_prefetch,
+            page_load_duration,
+            page_load_failures,
+            page_loads_in_flight,
+            queue_oldest_message_age,
+            queue_delivered_messages,
+            queue_redelivered_messages,
+            queue_failed_messages,
+            confirmation_duration,
+            delivery_compilation_duration,
         };
     }
 
@@ -132,6 +273,25 @@ impl PrometheusMetrics {
     }
 
     pub fn queue_is_deleted(&self, topic_id: &str, queue_id: &str) {
+        let _ = self
+            .queue_oldest_message_age
+            .remove_label_values(&[topic_id, queue_id]);
+        let _ = self
+            .queue_delivered_messages
+            .remove_label_values(&[topic_id, queue_id]);
+        let _ = self
+            .queue_redelivered_messages
+            .remove_label_values(&[topic_id, queue_id]);
+        let _ = self
+            .queue_failed_messages
+            .remove_label_values(&[topic_id, queue_id]);
+        let _ = self
+            .confirmation_duration
+            .remove_label_values(&[topic_id, queue_id]);
+        let _ = self
+            .delivery_compilation_duration
+            .remove_label_values(&[topic_id, queue_id]);
+
         let result = self
             .topic_queue_size
             .remove_label_values(&[topic_id, queue_id]);
@@ -154,22 +314,169 @@ impl PrometheusMetrics {
         self.tcp_connections.with_label_values(&["count"]).dec();
     }
 
-    pub fn update_tcp_threads(&self, threads_statistics: &ThreadsStatistics) {
+    pub fn update_persistence_state(&self, circuit_breaker: &PersistenceCircuitBreaker) {
+        self.persistence_state
+            .with_label_values(&["degraded"])
+            .set(circuit_breaker.is_degraded() as i64);
+
+        self.persistence_state
+            .with_label_values(&["circuit_open"])
+            .set(circuit_breaker.is_open() as i64);
+
+        self.persistence_state
+            .with_label_values(&["consecutive_failures"])
+            .set(circuit_breaker.get_consecutive_failures() as i64);
+
+        self.persistence_state
+            .with_label_values(&["total_failures"])
+            .set(circuit_breaker.get_total_failures() as i64);
+    }
+
+    pub fn mark_acl_denied(&self, identity: &str, permission: &str) {
+        self.acl_denied
+            .with_label_values(&[identity, permission])
+            .inc();
+    }
+
+    pub fn update_allocated_memory(&self, value: usize) {
+        self.allocated_memory.set(value as i64);
+    }
+
+    pub fn mark_evicted_sub_pages(&self, amount: usize) {
+        self.evicted_sub_pages.inc_by(amount as u64);
+    }
+
+    pub fn mark_sub_page_prefetch_hit(&self, topic_id: &str) {
+        self.sub_page_prefetch
+            .with_label_values(&[topic_id, "hit"])
+            .inc();
+    }
+
+    pub fn mark_sub_page_prefetch_miss(&self, topic_id: &str) {
+        self.sub_page_prefetch
+            .with_label_values(&[topic_id, "miss"])
+            .inc();
+    }
+
+    pub fn observe_page_load_duration(&self, topic_id: &str, duration: Duration) {
+        self.page_load_duration
+            .with_label_values(&[topic_id])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn mark_page_load_failure(&self, topic_id: &str) {
+        self.page_load_failures.with_label_values(&[topic_id]).inc();
+    }
+
+    pub fn update_page_loads_in_flight(&self, value: usize) {
+        self.page_loads_in_flight.set(value as i64);
+    }
+
+    // None is exported as NaN when the age is unknown, so the last known age does not go stale
+    pub fn update_queue_oldest_message_age(
+        &self,
+        topic_id: &str,
+        queue_id: &str,
+        age: Option<Duration>,
+    ) {
+        self.queue_oldest_message_age
+            .with_label_values(&[topic_id, queue_id])
+            .set(age.map(|age| age.as_secs_f64()).unwrap_or(f64::NAN));
+    }
+
+    pub fn mark_queue_delivered_messages(&self, topic_id: &str, queue_id: &str, amount: usize) {
+        self.queue_delivered_messages
+            .with_label_values(&[topic_id, queue_id])
+            .inc_by(amount as u64);
+    }
+
+    #[cfg(test)]
+    pub fn get_queue_delivered_and_failed_messages(
+        &self,
+        topic_id: &str,
+        queue_id: &str,
+    ) -> (u64, u64) {
+        (
+            self.queue_delivered_messages
+                .with_label_values(&[topic_id, queue_id])
+                .get(),
+            self.queue_failed_messages
+                .with_label_values(&[topic_id, queue_id])
+                .get(),
+        )
+    }
+
+    pub fn mark_queue_redelivered_message(&self, topic_id: &str, queue_id: &str) {
+        self.queue_redelivered_messages
+            .with_label_values(&[topic_id, queue_id])
+            .inc();
+    }
+
+    pub fn mark_queue_failed_messages(&self, topic_id: &str, queue_id: &str, amount: usize) {
+        self.queue_failed_messages
+            .with_label_values(&[topic_id, queue_id])
+            .inc_by(amount as u64);
+    }
+
+    pub fn observe_confirmation_duration(
+        &self,
+        topic_id: &str,
+        queue_id: &str,
+        duration: Duration,
+    ) {
+        self.confirmation_duration
+            .with_label_values(&[topic_id, queue_id])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_delivery_compilation_duration(
+        &self,
+        topic_id: &str,
+        queue_id: &str,
+        duration: Duration,
+    ) {
+        self.delivery_compilation_duration
+            .with_label_values(&[topic_id, queue_id])
+            .observe(duration.as_secs_f64());
+    }
+
+    // Sum of all tcp listeners
+    pub fn update_tcp_threads(&self, threads_statistics: &[Arc<ThreadsStatistics>]) {
         self.tcp_connections
             .with_label_values(&["ping_threads"])
-            .set(threads_statistics.ping_threads.get());
+            .set(
+                threads_statistics
+                    .iter()
+                    .map(|itm| itm.ping_threads.get())
+                    .sum(),
+            );
 
         self.tcp_connections
             .with_label_values(&["read_threads"])
-            .set(threads_statistics.read_threads.get());
+            .set(
+                threads_statistics
+                    .iter()
+                    .map(|itm| itm.read_threads.get())
+                    .sum(),
+            );
 
         self.tcp_connections
             .with_label_values(&["write_threads"])
-            .set(threads_statistics.write_threads.get());
+            .set(
+                threads_statistics
+                    .iter()
+                    .map(|itm| itm.write_threads.get())
+                    .sum(),
+            );
 
         self.tcp_connections
             .with_label_values(&["connection_objects"])
-            .set(threads_statistics.connections_objects.get());
+            .set(
+                threads_statistics
+                    .iter()
+                    .map(|itm| itm.connections_objects.get())
+                    .sum(),
+            );
     }
 }
 
@@ -235,3 +542,86 @@ fn create_tcp_connections() -> IntGaugeVec {
     let labels = &[TCP_METRIC];
     IntGaugeVec::new(gauge_opts, labels).unwrap()
 }
+
+fn create_persistence_state() -> IntGaugeVec {
+    let gauge_opts = Opts::new(
+        "persistence_state",
+        "Messages persistence state. Degraded and circuit_open are 0 or 1",
+    );
+    let labels = &["state"];
+    IntGaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_acl_denied() -> IntCounterVec {
+    let counter_opts = Opts::new("acl_denied", "Operations which are denied by topic ACL");
+    let labels = &["identity", "permission"];
+    IntCounterVec::new(counter_opts, labels).unwrap()
+}
+
+fn create_allocated_memory() -> IntGauge {
+    IntGauge::new("allocated_memory", "Memory allocated by the process").unwrap()
+}
+
+fn create_evicted_sub_pages() -> IntCounter {
+    IntCounter::new(
+        "evicted_sub_pages",
+        "Persisted sub pages which are evicted from cache because memory budget is exceeded",
+    )
+    .unwrap()
+}
+
+fn create_sub_page_prefetch() -> IntCounterVec {
+    let counter_opts = Opts::new(
+        "sub_page_prefetch",
+        "Deliveries from prefetched sub page (hit) and deliveries which waited for sub page to be loaded (miss)",
+    );
+    let labels = &["topic", "result"];
+    IntCounterVec::new(counter_opts, labels).unwrap()
+}
+
+fn create_page_load_duration() -> HistogramVec {
+    let histogram_opts = HistogramOpts::new(
+        "page_load_duration_seconds",
+        "Duration of sub page load from persistence",
+    );
+    let labels = &["topic"];
+    HistogramVec::new(histogram_opts, labels).unwrap()
+}
+
+fn create_page_load_failures() -> IntCounterVec {
+    let counter_opts = Opts::new(
+        "page_load_failures",
+        "Failed attempts to load sub page from persistence",
+    );
+    let labels = &["topic"];
+    IntCounterVec::new(counter_opts, labels).unwrap()
+}
+
+fn create_page_loads_in_flight() -> IntGauge {
+    IntGauge::new(
+        "page_loads_in_flight",
+        "Sub pages which are being loaded from persistence",
+    )
+    .unwrap()
+}
+
+fn create_queue_oldest_message_age() -> GaugeVec {
+    let gauge_opts = Opts::new(
+        "queue_oldest_message_age_seconds",
+        "Age of the oldest message which is not delivered to the queue yet. NaN if sub page of the message is not loaded",
+    );
+    let labels = &["topic", "queue"];
+    GaugeVec::new(gauge_opts, labels).unwrap()
+}
+
+fn create_queue_messages_counter(name: &str, help: &str) -> IntCounterVec {
+    let counter_opts = Opts::new(name, help);
+    let labels = &["topic", "queue"];
+    IntCounterVec::new(counter_opts, labels).unwrap()
+}
+
+fn create_queue_histogram(name: &str, help: &str) -> HistogramVec {
+    let histogram_opts = HistogramOpts::new(name, help);
+    let labels = &["topic", "queue"];
+    HistogramVec::new(histogram_opts, labels).unwrap()
+}
@@ -0,0 +1,412 @@
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    auth::matches_pattern,
+    sessions::SessionId,
+    settings::{PublishRateLimit, PublishRateLimitScope, SettingsModel},
+};
+
+const MICROS_IN_SECOND: f64 = 1_000_000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishRateLimitResult {
+    Allowed,
+    // Publish is accepted. Response has to be delayed to slow down publisher
+    Delayed(Duration),
+    // Publish exceeds the limit more than max delay allows
+    Rejected(Duration),
+}
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+enum BucketOwner {
+    Session(i64),
+    Application(String),
+}
+
+#[derive(Clone, Hash, PartialEq, Eq)]
+struct BucketKey {
+    rule_no: usize,
+    owner: BucketOwner,
+    topic_id: String,
+}
+
+// Tokens are refilled continuously with the rate. Burst is one second of the rate.
+// Tokens go negative while publishers are delayed
+struct TokenBucket {
+    messages: Option<(f64, f64)>,
+    bytes: Option<(f64, f64)>,
+    last_refill: i64,
+}
+
+impl TokenBucket {
+    fn new(rule: &PublishRateLimit, now: i64) -> Self {
+        Self {
+            messages: rule
+                .messages_per_second
+                .map(|rate| (rate as f64, rate as f64)),
+            bytes: rule.bytes_per_second.map(|rate| (rate as f64, rate as f64)),
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: i64) {
+        let elapsed_secs = (now - self.last_refill).max(0) as f64 / MICROS_IN_SECOND;
+        self.last_refill = now;
+
+        for (rate, tokens) in [&mut self.messages, &mut self.bytes].into_iter().flatten() {
+            *tokens = (*tokens + *rate * elapsed_secs).min(*rate);
+        }
+    }
+
+    fn get_wait(&self, messages: usize, bytes: usize) -> Duration {
+        let mut result: f64 = 0.0;
+
+        if let Some((rate, tokens)) = self.messages {
+            result = result.max((messages as f64 - tokens) / rate);
+        }
+
+        if let Some((rate, tokens)) = self.bytes {
+            result = result.max((bytes as f64 - tokens) / rate);
+        }
+
+        Duration::from_secs_f64(result.max(0.0))
+    }
+
+    fn take(&mut self, messages: usize, bytes: usize) {
+        if let Some((_, tokens)) = &mut self.messages {
+            *tokens -= messages as f64;
+        }
+
+        if let Some((_, tokens)) = &mut self.bytes {
+            *tokens -= bytes as f64;
+        }
+    }
+
+    fn give_back(&mut self, messages: usize, bytes: usize) {
+        if let Some((rate, tokens)) = &mut self.messages {
+            *tokens = (*tokens + messages as f64).min(*rate);
+        }
+
+        if let Some((rate, tokens)) = &mut self.bytes {
+            *tokens = (*tokens + bytes as f64).min(*rate);
+        }
+    }
+}
+
+// Amount of the last completed second
+#[derive(Default)]
+struct RateCounter {
+    second: i64,
+    current: usize,
+    last: usize,
+}
+
+impl RateCounter {
+    fn add(&mut self, now_second: i64, amount: usize) {
+        if now_second != self.second {
+            self.last = if now_second == self.second + 1 {
+                self.current
+            } else {
+                0
+            };
+            self.second = now_second;
+            self.current = 0;
+        }
+
+        self.current += amount;
+    }
+
+    fn get(&self, now_second: i64) -> usize {
+        if now_second == self.second {
+            self.last
+        } else if now_second == self.second + 1 {
+            self.current
+        } else {
+            0
+        }
+    }
+}
+
+#[derive(Default)]
+struct SessionPublishStatistics {
+    messages: RateCounter,
+    bytes: RateCounter,
+    throttled: u64,
+    rejected: u64,
+}
+
+pub struct SessionPublishRate {
+    pub messages_per_second: usize,
+    pub bytes_per_second: usize,
+    pub throttled: u64,
+    pub rejected: u64,
+}
+
+#[derive(Default)]
+struct PublishRateLimiterInner {
+    buckets: HashMap<BucketKey, TokenBucket>,
+    sessions: HashMap<i64, SessionPublishStatistics>,
+}
+
+pub struct PublishRateLimiter {
+    rules: Vec<PublishRateLimit>,
+    max_delay: Duration,
+    inner: Mutex<PublishRateLimiterInner>,
+}
+
+impl PublishRateLimiter {
+    pub fn new(settings: &SettingsModel) -> Self {
+        Self {
+            rules: settings.publish_rate_limits.clone(),
+            max_delay: settings.publish_rate_limit_max_delay,
+            inner: Mutex::new(PublishRateLimiterInner::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.rules.is_empty()
+    }
+
+    pub fn acquire(
+        &self,
+        session_id: SessionId,
+        app_name: &str,
+        topic_id: &str,
+        messages: usize,
+        bytes: usize,
+        now: DateTimeAsMicroseconds,
+    ) -> PublishRateLimitResult {
+        if !self.is_enabled() {
+            return PublishRateLimitResult::Allowed;
+        }
+
+        let now_micros = now.unix_microseconds;
+
+        let mut inner = self.inner.lock().unwrap();
+
+        let keys = self.get_bucket_keys(session_id, app_name, topic_id);
+        let mut wait = Duration::ZERO;
+
+        for key in &keys {
+            let rule = &self.rules[key.rule_no];
+
+            let bucket = inner
+                .buckets
+                .entry(key.clone())
+                .or_insert_with(|| TokenBucket::new(rule, now_micros));
+
+            bucket.refill(now_micros);
+            wait = wait.max(bucket.get_wait(messages, bytes));
+        }
+
+        let now_second = now_micros / 1_000_000;
+
+        let statistics = inner.sessions.entry(session_id.get_value()).or_default();
+
+        if wait > self.max_delay {
+            statistics.rejected += 1;
+            return PublishRateLimitResult::Rejected(wait);
+        }
+
+        statistics.messages.add(now_second, messages);
+        statistics.bytes.add(now_second, bytes);
+
+        if !wait.is_zero() {
+            statistics.throttled += 1;
+        }
+
+        for key in keys {
+            if let Some(bucket) = inner.buckets.get_mut(&key) {
+                bucket.take(messages, bytes);
+            }
+        }
+
+        if wait.is_zero() {
+            PublishRateLimitResult::Allowed
+        } else {
+            PublishRateLimitResult::Delayed(wait)
+        }
+    }
+
+    // Publish which is failed after tokens are acquired does not count against the limit
+    pub fn refund(
+        &self,
+        session_id: SessionId,
+        app_name: &str,
+        topic_id: &str,
+        messages: usize,
+        bytes: usize,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let keys = self.get_bucket_keys(session_id, app_name, topic_id);
+
+        let mut inner = self.inner.lock().unwrap();
+
+        for key in keys {
+            if let Some(bucket) = inner.buckets.get_mut(&key) {
+                bucket.give_back(messages, bytes);
+            }
+        }
+    }
+
+    fn get_bucket_keys(
+        &self,
+        session_id: SessionId,
+        app_name: &str,
+        topic_id: &str,
+    ) -> Vec<BucketKey> {
+        let mut result = Vec::new();
+
+        for (rule_no, rule) in self.rules.iter().enumerate() {
+            if !matches_pattern(rule.app_name.as_str(), app_name)
+                || !matches_pattern(rule.topic_id.as_str(), topic_id)
+            {
+                continue;
+            }
+
+            result.push(BucketKey {
+                rule_no,
+                owner: match rule.scope {
+                    PublishRateLimitScope::Session => BucketOwner::Session(session_id.get_value()),
+                    PublishRateLimitScope::Application => {
+                        BucketOwner::Application(app_name.to_string())
+                    }
+                },
+                topic_id: topic_id.to_string(),
+            });
+        }
+
+        result
+    }
+
+    pub fn get_session_rate(
+        &self,
+        session_id: SessionId,
+        now: DateTimeAsMicroseconds,
+    ) -> Option<SessionPublishRate> {
+        let inner = self.inner.lock().unwrap();
+        let statistics = inner.sessions.get(&session_id.get_value())?;
+
+        let now_second = now.unix_microseconds / 1_000_000;
+
+        Some(SessionPublishRate {
+            messages_per_second: statistics.messages.get(now_second),
+            bytes_per_second: statistics.bytes.get(now_second),
+            throttled: statistics.throttled,
+            rejected: statistics.rejected,
+        })
+    }
+
+    pub fn remove_session(&self, session_id: SessionId) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.sessions.remove(&session_id.get_value());
+
+        inner.buckets.retain(|key, _| match key.owner {
+            BucketOwner::Session(owner) => owner != session_id.get_value(),
+            BucketOwner::Application(_) => true,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_limiter(scope: PublishRateLimitScope) -> PublishRateLimiter {
+        let mut settings = SettingsModel::create_test_settings(16);
+
+        settings.publish_rate_limits = vec![PublishRateLimit {
+            app_name: "*".to_string(),
+            topic_id: "orders".to_string(),
+            scope,
+            messages_per_second: Some(10),
+            bytes_per_second: None,
+        }];
+
+        settings.publish_rate_limit_max_delay = Duration::from_secs(1);
+
+        PublishRateLimiter::new(&settings)
+    }
+
+    #[test]
+    fn test_delay_and_reject() {
+        let limiter = create_limiter(PublishRateLimitScope::Session);
+        let now = DateTimeAsMicroseconds::new(1_000_000_000);
+        let session_id = SessionId::new(1);
+
+        assert_eq!(
+            PublishRateLimitResult::Allowed,
+            limiter.acquire(session_id, "app", "orders", 10, 100, now)
+        );
+
+        assert_eq!(
+            PublishRateLimitResult::Delayed(Duration::from_millis(500)),
+            limiter.acquire(session_id, "app", "orders", 5, 100, now)
+        );
+
+        assert!(matches!(
+            limiter.acquire(session_id, "app", "orders", 10, 100, now),
+            PublishRateLimitResult::Rejected(_)
+        ));
+
+        // Other topics are not limited
+        assert_eq!(
+            PublishRateLimitResult::Allowed,
+            limiter.acquire(session_id, "app", "payments", 100, 100, now)
+        );
+
+        let rate = limiter.get_session_rate(session_id, now).unwrap();
+        assert_eq!(1, rate.throttled);
+        assert_eq!(1, rate.rejected);
+    }
+
+    #[test]
+    fn test_refund() {
+        let limiter = create_limiter(PublishRateLimitScope::Session);
+        let now = DateTimeAsMicroseconds::new(1_000_000_000);
+        let session_id = SessionId::new(1);
+
+        assert_eq!(
+            PublishRateLimitResult::Allowed,
+            limiter.acquire(session_id, "app", "orders", 10, 100, now)
+        );
+
+        limiter.refund(session_id, "app", "orders", 10, 100);
+
+        assert_eq!(
+            PublishRateLimitResult::Allowed,
+            limiter.acquire(session_id, "app", "orders", 10, 100, now)
+        );
+    }
+
+    #[test]
+    fn test_application_scope_is_shared() {
+        let limiter = create_limiter(PublishRateLimitScope::Application);
+        let now = DateTimeAsMicroseconds::new(1_000_000_000);
+
+        assert_eq!(
+            PublishRateLimitResult::Allowed,
+            limiter.acquire(SessionId::new(1), "app", "orders", 10, 100, now)
+        );
+
+        assert!(matches!(
+            limiter.acquire(SessionId::new(2), "app", "orders", 1, 100, now),
+            PublishRateLimitResult::Delayed(_)
+        ));
+
+        assert_eq!(
+            PublishRateLimitResult::Allowed,
+            limiter.acquire(SessionId::new(3), "other-app", "orders", 10, 100, now)
+        );
+    }
+}
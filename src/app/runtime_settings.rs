@@ -0,0 +1,187 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use crate::settings::{SettingsModel, SettingsModelYaml};
+
+const DEFAULT_DELIVERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Settings file names of the settings which are applied without restart
+pub const HOT_RELOADABLE_SETTINGS: [&str; 4] = [
+    "DeliveryTimeout",
+    "MaxDeliverySize",
+    "AutoCreateTopicOnPublish",
+    "AutoCreateTopicOnSubscribe",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeSettingsValues {
+    pub delivery_timeout: Duration,
+    pub max_delivery_size: usize,
+    pub auto_create_topic_on_publish: bool,
+    pub auto_create_topic_on_subscribe: bool,
+}
+
+impl RuntimeSettingsValues {
+    pub fn from_yaml(src: &SettingsModelYaml) -> Result<Self, String> {
+        let delivery_timeout = match &src.delivery_timeout {
+            Some(value) => rust_extensions::duration_utils::parse_duration(value.as_str())
+                .map_err(|err| {
+                    format!(
+                        "Can not parse DeliveryTimeout value '{}'. Reason: {:?}",
+                        value, err
+                    )
+                })?,
+            None => DEFAULT_DELIVERY_TIMEOUT,
+        };
+
+        if delivery_timeout.is_zero() {
+            return Err("DeliveryTimeout must be greater than zero".to_string());
+        }
+
+        if src.max_delivery_size == 0 {
+            return Err("MaxDeliverySize must be greater than zero".to_string());
+        }
+
+        Ok(Self {
+            delivery_timeout,
+            max_delivery_size: src.max_delivery_size,
+            auto_create_topic_on_publish: src.auto_create_topic_on_publish.unwrap_or(false),
+            auto_create_topic_on_subscribe: src.auto_create_topic_on_subscribe.unwrap_or(false),
+        })
+    }
+
+    // Returns (SettingName, OldValue, NewValue) for every changed setting
+    pub fn get_changes(&self, new_values: &Self) -> Vec<(&'static str, String, String)> {
+        let mut result = Vec::new();
+
+        if self.delivery_timeout != new_values.delivery_timeout {
+            result.push((
+                "DeliveryTimeout",
+                format!("{:?}", self.delivery_timeout),
+                format!("{:?}", new_values.delivery_timeout),
+            ));
+        }
+
+        if self.max_delivery_size != new_values.max_delivery_size {
+            result.push((
+                "MaxDeliverySize",
+                self.max_delivery_size.to_string(),
+                new_values.max_delivery_size.to_string(),
+            ));
+        }
+
+        if self.auto_create_topic_on_publish != new_values.auto_create_topic_on_publish {
+            result.push((
+                "AutoCreateTopicOnPublish",
+                self.auto_create_topic_on_publish.to_string(),
+                new_values.auto_create_topic_on_publish.to_string(),
+            ));
+        }
+
+        if self.auto_create_topic_on_subscribe != new_values.auto_create_topic_on_subscribe {
+            result.push((
+                "AutoCreateTopicOnSubscribe",
+                self.auto_create_topic_on_subscribe.to_string(),
+                new_values.auto_create_topic_on_subscribe.to_string(),
+            ));
+        }
+
+        result
+    }
+}
+
+// Settings which are read on every operation, so they can be changed without restart
+pub struct RuntimeSettings {
+    delivery_timeout_micros: AtomicU64,
+    max_delivery_size: AtomicUsize,
+    auto_create_topic_on_publish: AtomicBool,
+    auto_create_topic_on_subscribe: AtomicBool,
+}
+
+impl RuntimeSettings {
+    pub fn new(settings: &SettingsModel) -> Self {
+        let delivery_timeout = settings
+            .delivery_timeout
+            .unwrap_or(DEFAULT_DELIVERY_TIMEOUT);
+
+        Self {
+            delivery_timeout_micros: AtomicU64::new(delivery_timeout.as_micros() as u64),
+            max_delivery_size: AtomicUsize::new(settings.max_delivery_size),
+            auto_create_topic_on_publish: AtomicBool::new(settings.auto_create_topic_on_publish),
+            auto_create_topic_on_subscribe: AtomicBool::new(
+                settings.auto_create_topic_on_subscribe,
+            ),
+        }
+    }
+
+    pub fn get_delivery_timeout(&self) -> Duration {
+        Duration::from_micros(self.delivery_timeout_micros.load(Ordering::Relaxed))
+    }
+
+    pub fn get_max_delivery_size(&self) -> usize {
+        self.max_delivery_size.load(Ordering::Relaxed)
+    }
+
+    pub fn auto_create_topic_on_publish(&self) -> bool {
+        self.auto_create_topic_on_publish.load(Ordering::Relaxed)
+    }
+
+    pub fn auto_create_topic_on_subscribe(&self) -> bool {
+        self.auto_create_topic_on_subscribe.load(Ordering::Relaxed)
+    }
+
+    pub fn get_values(&self) -> RuntimeSettingsValues {
+        RuntimeSettingsValues {
+            delivery_timeout: self.get_delivery_timeout(),
+            max_delivery_size: self.get_max_delivery_size(),
+            auto_create_topic_on_publish: self.auto_create_topic_on_publish(),
+            auto_create_topic_on_subscribe: self.auto_create_topic_on_subscribe(),
+        }
+    }
+
+    pub fn apply(&self, values: &RuntimeSettingsValues) {
+        self.delivery_timeout_micros
+            .store(values.delivery_timeout.as_micros() as u64, Ordering::SeqCst);
+        self.max_delivery_size
+            .store(values.max_delivery_size, Ordering::SeqCst);
+        self.auto_create_topic_on_publish
+            .store(values.auto_create_topic_on_publish, Ordering::SeqCst);
+        self.auto_create_topic_on_subscribe
+            .store(values.auto_create_topic_on_subscribe, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_and_get_changes() {
+        let settings = SettingsModel::create_test_settings(16);
+        let runtime_settings = RuntimeSettings::new(&settings);
+
+        let old_values = runtime_settings.get_values();
+
+        let mut new_values = old_values.clone();
+        new_values.max_delivery_size = 32;
+        new_values.delivery_timeout = Duration::from_secs(5);
+
+        let changes = old_values.get_changes(&new_values);
+
+        assert_eq!(2, changes.len());
+        assert_eq!("DeliveryTimeout", changes[0].0);
+        assert_eq!("MaxDeliverySize", changes[1].0);
+        assert_eq!("16", changes[1].1);
+        assert_eq!("32", changes[1].2);
+
+        runtime_settings.apply(&new_values);
+
+        assert_eq!(new_values, runtime_settings.get_values());
+        assert!(runtime_settings
+            .get_values()
+            .get_changes(&new_values)
+            .is_empty());
+    }
+}
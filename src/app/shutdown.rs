@@ -1,30 +1,117 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use super::AppContext;
 
+const DRAIN_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
 pub async fn execute(app: Arc<AppContext>) {
+    if app.replication.is_passive() {
+        println!("Passive node does not persist topics and queues on shutdown");
+        return;
+    }
+
+    wait_until_messages_on_delivery_are_confirmed(app.as_ref()).await;
+    requeue_messages_on_delivery(app.as_ref()).await;
     empty_persistence_queues(app.clone()).await;
     make_last_topics_and_queues_persist(app.clone()).await;
 }
 
+async fn get_messages_on_delivery(app: &AppContext) -> usize {
+    let mut result = 0;
+
+    for topic in app.topic_list.get_all().await {
+        let topic_data = topic.get_access().await;
+
+        for queue in topic_data.queues.get_all() {
+            result += queue.get_on_delivery();
+        }
+    }
+
+    result
+}
+
+async fn wait_until_messages_on_delivery_are_confirmed(app: &AppContext) {
+    let started = DateTimeAsMicroseconds::now();
+
+    loop {
+        let on_delivery = get_messages_on_delivery(app).await;
+
+        if on_delivery == 0 {
+            println!("All messages on delivery are confirmed");
+            return;
+        }
+
+        let waiting = DateTimeAsMicroseconds::now()
+            .duration_since(started)
+            .as_positive_or_zero();
+
+        if waiting >= app.settings.shutdown_drain_timeout {
+            println!(
+                "{} messages are still on delivery after {:?}",
+                on_delivery, waiting
+            );
+            return;
+        }
+
+        tokio::time::sleep(DRAIN_CHECK_INTERVAL).await;
+    }
+}
+
+async fn requeue_messages_on_delivery(app: &AppContext) {
+    for topic in app.topic_list.get_all().await {
+        let mut topic_data = topic.get_access().await;
+
+        for queue in topic_data.queues.get_all_mut() {
+            let requeued = queue.requeue_messages_on_delivery();
+
+            if requeued > 0 {
+                println!(
+                    "{} not confirmed messages are returned to queue {}/{}",
+                    requeued,
+                    topic.topic_id.as_str(),
+                    queue.queue_id.as_str()
+                );
+            }
+        }
+    }
+}
+
 async fn empty_persistence_queues(app: Arc<AppContext>) {
     for topic in app.topic_list.get_all().await {
-        let metrics = topic.get_topic_size_metrics().await;
+        let mut persist_size = topic.get_topic_size_metrics().await.persist_size;
 
-        while metrics.persist_size > 0 {
+        while persist_size > 0 {
             println!(
                 "Topic {} has {} messages to persist. Doing Force Persist",
                 topic.topic_id.as_str(),
-                metrics.persist_size
+                persist_size
             );
 
             crate::operations::persist_topic_messages(&app, &topic).await;
+
+            let new_persist_size = topic.get_topic_size_metrics().await.persist_size;
+
+            // Publishers are rejected already. No progress means persistence is not available
+            if new_persist_size >= persist_size {
+                println!(
+                    "Topic {} still has {} messages to persist. Giving up",
+                    topic.topic_id.as_str(),
+                    new_persist_size
+                );
+                break;
+            }
+
+            persist_size = new_persist_size;
         }
 
-        println!(
-            "Topic {} has no messages to persist.",
-            topic.topic_id.as_str()
-        );
+        if persist_size == 0 {
+            println!(
+                "Topic {} has no messages to persist.",
+                topic.topic_id.as_str()
+            );
+        }
     }
 }
 
@@ -34,3 +121,161 @@ async fn make_last_topics_and_queues_persist(app: Arc<AppContext>) {
     crate::operations::persist_topics_and_queues(&app, &mut reusable_topics).await;
     println!("Final topics and queues snapshot save is done");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use my_service_bus::abstractions::{
+        publisher::MessageToPublish, subscriber::TopicQueueType, SbMessageHeaders,
+    };
+
+    use crate::{app::AppContext, sessions::MyServiceBusSession, settings::SettingsModel};
+
+    const TOPIC_NAME: &str = "test-topic";
+    const QUEUE_NAME: &str = "test-queue";
+
+    async fn create_app(shutdown_drain_timeout: Duration, subscribe: bool) -> Arc<AppContext> {
+        let mut settings = SettingsModel::create_test_settings(16);
+        settings.shutdown_drain_timeout = shutdown_drain_timeout;
+
+        let app = Arc::new(AppContext::new(settings).await);
+
+        let session = app.sessions.add_test("127.0.0.1").await;
+
+        crate::operations::publisher::create_topic_if_not_exists(
+            &app,
+            Some(session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        if subscribe {
+            crate::operations::subscriber::subscribe_to_queue(
+                &app,
+                TOPIC_NAME.to_string(),
+                QUEUE_NAME.to_string(),
+                TopicQueueType::PermanentWithSingleConnection,
+                session.clone(),
+            )
+            .await
+            .unwrap();
+        }
+
+        let messages = (0..2)
+            .map(|i| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![i as u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(
+            &app,
+            TOPIC_NAME,
+            messages,
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        app
+    }
+
+    async fn get_queue_size(app: &AppContext) -> usize {
+        let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+        let topic_data = topic.get_access().await;
+        topic_data.queues.get(QUEUE_NAME).unwrap().get_queue_size()
+    }
+
+    #[tokio::test]
+    async fn test_messages_on_delivery_are_drained() {
+        let app = create_app(Duration::from_secs(10), true).await;
+
+        assert_eq!(super::get_messages_on_delivery(&app).await, 2);
+
+        let (subscriber_id, session_id) = {
+            let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+            let topic_data = topic.get_access().await;
+            let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+            let subscriber = queue.subscribers.get_all().unwrap()[0];
+            (subscriber.id, subscriber.session.get_session_id())
+        };
+
+        let app_to_confirm = app.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            crate::operations::delivery_confirmation::all_confirmed(
+                &app_to_confirm,
+                TOPIC_NAME,
+                QUEUE_NAME,
+                subscriber_id,
+                session_id,
+            )
+            .await
+            .unwrap();
+        });
+
+        tokio::time::timeout(Duration::from_secs(5), super::execute(app.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(super::get_messages_on_delivery(&app).await, 0);
+        assert_eq!(get_queue_size(&app).await, 0);
+        assert_eq!(
+            app.prometheus
+                .get_queue_delivered_and_failed_messages(TOPIC_NAME, QUEUE_NAME),
+            (2, 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_not_confirmed_messages_are_requeued_when_drain_timeout_expires() {
+        let app = create_app(Duration::from_millis(300), true).await;
+
+        assert_eq!(super::get_messages_on_delivery(&app).await, 2);
+
+        tokio::time::timeout(Duration::from_secs(5), super::execute(app.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(super::get_messages_on_delivery(&app).await, 0);
+        assert_eq!(get_queue_size(&app).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_persisted_on_shutdown() {
+        let app = create_app(Duration::from_secs(1), false).await;
+
+        let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+        assert_eq!(topic.get_topic_size_metrics().await.persist_size, 2);
+
+        tokio::time::timeout(Duration::from_secs(5), super::execute(app.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(topic.get_topic_size_metrics().await.persist_size, 0);
+        assert_eq!(
+            app.messages_pages_repo
+                .unwrap_mock()
+                .get_messages_amount(TOPIC_NAME)
+                .await,
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_persist_loop_exits_when_persistence_is_not_available() {
+        let app = create_app(Duration::from_secs(1), false).await;
+
+        app.messages_pages_repo.unwrap_mock().set_fail_saves(true);
+
+        tokio::time::timeout(Duration::from_secs(10), super::execute(app.clone()))
+            .await
+            .unwrap();
+
+        let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+        assert_eq!(topic.get_topic_size_metrics().await.persist_size, 2);
+    }
+}
@@ -0,0 +1,331 @@
+use my_logger::LogEventCtx;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+use tokio::{fs::File, io::AsyncWriteExt, sync::Mutex};
+
+use crate::settings::{AdminKey, AdminScope, SettingsModel};
+
+use super::constant_time_eq;
+
+const ANONYMOUS_IDENTITY: &str = "anonymous";
+
+// Method, Path. Client api which applications authenticate with api keys, not with admin keys
+const PUBLIC_ROUTES: [(&str, &str); 7] = [
+    ("POST", "/api/greeting"),
+    ("POST", "/api/greeting/ping"),
+    ("POST", "/greeting"),
+    ("POST", "/greeting/ping"),
+    ("POST", "/api/publish"),
+    ("POST", "/publish"),
+    ("POST", "/api/subscribers/*"),
+];
+
+// Routes outside of /api which require admin credentials
+const PROTECTED_ROUTES: [&str; 2] = ["/metrics", "/status"];
+
+// Every /api route which is not public requires admin credentials, so new routes are protected by default.
+// Reads require ReadOnly scope, everything else requires Admin scope. Ui pages and static files are not protected
+pub fn get_required_admin_scope(method: &str, path: &str) -> Option<AdminScope> {
+    let path = path.trim_end_matches('/').to_lowercase();
+
+    let is_public = PUBLIC_ROUTES.iter().any(|(route_method, route_path)| {
+        if !route_method.eq_ignore_ascii_case(method) {
+            return false;
+        }
+
+        match route_path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path.as_str() == *route_path,
+        }
+    });
+
+    if is_public {
+        return None;
+    }
+
+    let is_protected =
+        path == "/api" || path.starts_with("/api/") || PROTECTED_ROUTES.contains(&path.as_str());
+
+    if !is_protected {
+        return None;
+    }
+
+    if method.eq_ignore_ascii_case("GET") || method.eq_ignore_ascii_case("HEAD") {
+        Some(AdminScope::ReadOnly)
+    } else {
+        Some(AdminScope::Admin)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminAccessDenied {
+    Unauthorized,
+    Forbidden,
+}
+
+impl AdminAccessDenied {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminAccessDenied::Unauthorized => "unauthorized",
+            AdminAccessDenied::Forbidden => "forbidden",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct AdminAuditRecord<'s> {
+    #[serde(rename = "dateTime")]
+    date_time: String,
+    identity: &'s str,
+    ip: &'s str,
+    method: &'s str,
+    path: &'s str,
+    result: &'s str,
+}
+
+pub struct AdminAccess {
+    // If None, admin api is closed unless it is explicitly insecure
+    keys: Option<Vec<AdminKey>>,
+    insecure: bool,
+    audit_file_path: Option<String>,
+    audit_file: Mutex<Option<File>>,
+}
+
+impl AdminAccess {
+    pub fn new(settings: &SettingsModel) -> Self {
+        Self {
+            keys: settings.admin_keys.clone(),
+            insecure: settings.admin_api_insecure,
+            audit_file_path: settings.admin_audit_file.clone(),
+            audit_file: Mutex::new(None),
+        }
+    }
+
+    // Every mutating call of admin api is audited whether it is allowed or not
+    pub async fn authorize(
+        &self,
+        method: &str,
+        path: &str,
+        admin_key: Option<&str>,
+        ip: &str,
+    ) -> Result<(), AdminAccessDenied> {
+        let required_scope = match get_required_admin_scope(method, path) {
+            Some(required_scope) => required_scope,
+            None => return Ok(()),
+        };
+
+        let (identity, result) = self.check_key(required_scope, admin_key);
+
+        if !method.eq_ignore_ascii_case("GET") {
+            let record = AdminAuditRecord {
+                date_time: DateTimeAsMicroseconds::now().to_rfc3339(),
+                identity,
+                ip,
+                method,
+                path,
+                result: match &result {
+                    Ok(_) => "allowed",
+                    Err(err) => err.as_str(),
+                },
+            };
+
+            self.write_audit_record(&record).await;
+        }
+
+        result
+    }
+
+    fn check_key(
+        &self,
+        required_scope: AdminScope,
+        admin_key: Option<&str>,
+    ) -> (&str, Result<(), AdminAccessDenied>) {
+        let keys = match &self.keys {
+            Some(keys) => keys,
+            None if self.insecure => return (ANONYMOUS_IDENTITY, Ok(())),
+            None => return (ANONYMOUS_IDENTITY, Err(AdminAccessDenied::Unauthorized)),
+        };
+
+        let admin_key = match admin_key {
+            Some(admin_key) => admin_key,
+            None => return (ANONYMOUS_IDENTITY, Err(AdminAccessDenied::Unauthorized)),
+        };
+
+        // Every key is compared to not leak which one has matched by timing
+        let mut found = None;
+
+        for key in keys {
+            if constant_time_eq(key.key.as_bytes(), admin_key.as_bytes()) {
+                found = Some(key);
+            }
+        }
+
+        match found {
+            Some(key) if key.scope >= required_scope => (key.identity.as_str(), Ok(())),
+            Some(key) => (key.identity.as_str(), Err(AdminAccessDenied::Forbidden)),
+            None => (ANONYMOUS_IDENTITY, Err(AdminAccessDenied::Unauthorized)),
+        }
+    }
+
+    async fn write_audit_record(&self, record: &AdminAuditRecord<'_>) {
+        my_logger::LOGGER.write_info(
+            "Admin Audit",
+            format!("{} {} is {}", record.method, record.path, record.result),
+            LogEventCtx::new()
+                .add("identity", record.identity)
+                .add("ip", record.ip),
+        );
+
+        let audit_file_path = match &self.audit_file_path {
+            Some(audit_file_path) => audit_file_path,
+            None => return,
+        };
+
+        let mut line = match serde_json::to_vec(record) {
+            Ok(line) => line,
+            Err(_) => return,
+        };
+        line.push(b'\n');
+
+        let mut audit_file = self.audit_file.lock().await;
+
+        if audit_file.is_none() {
+            match tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(audit_file_path)
+                .await
+            {
+                Ok(file) => *audit_file = Some(file),
+                Err(err) => {
+                    write_audit_file_error(audit_file_path, err);
+                    return;
+                }
+            }
+        }
+
+        let file = audit_file.as_mut().unwrap();
+
+        if let Err(err) = file.write_all(line.as_slice()).await {
+            write_audit_file_error(audit_file_path, err);
+            // File is reopened on next record
+            *audit_file = None;
+            return;
+        }
+
+        if let Err(err) = file.sync_data().await {
+            write_audit_file_error(audit_file_path, err);
+        }
+    }
+}
+
+fn write_audit_file_error(audit_file_path: &str, err: std::io::Error) {
+    my_logger::LOGGER.write_error(
+        "Admin Audit",
+        format!("Can not write audit record. {:?}", err),
+        LogEventCtx::new().add("auditFile", audit_file_path),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_required_admin_scope() {
+        assert_eq!(
+            Some(AdminScope::Admin),
+            get_required_admin_scope("DELETE", "/api/Queues")
+        );
+        assert_eq!(
+            Some(AdminScope::ReadOnly),
+            get_required_admin_scope("GET", "/api/Queues/")
+        );
+        assert_eq!(
+            Some(AdminScope::ReadOnly),
+            get_required_admin_scope("GET", "/api/Debug/OnDelivery")
+        );
+        assert_eq!(
+            Some(AdminScope::ReadOnly),
+            get_required_admin_scope("GET", "/api/Logs/Topic/test-topic")
+        );
+        assert_eq!(
+            Some(AdminScope::Admin),
+            get_required_admin_scope("POST", "/api/Topics/Create")
+        );
+        assert_eq!(None, get_required_admin_scope("POST", "/api/Publish"));
+        assert_eq!(None, get_required_admin_scope("POST", "/Greeting"));
+        assert_eq!(
+            None,
+            get_required_admin_scope("POST", "/api/Subscribers/Await")
+        );
+        assert_eq!(
+            Some(AdminScope::ReadOnly),
+            get_required_admin_scope("GET", "/metrics")
+        );
+        assert_eq!(
+            Some(AdminScope::ReadOnly),
+            get_required_admin_scope("GET", "/Status")
+        );
+        assert_eq!(None, get_required_admin_scope("GET", "/"));
+        assert_eq!(None, get_required_admin_scope("GET", "/js/app.js"));
+    }
+
+    #[test]
+    fn test_admin_api_is_closed_without_keys_unless_insecure() {
+        let mut settings = SettingsModel::create_test_settings(16);
+
+        let admin_access = AdminAccess::new(&settings);
+        assert_eq!(
+            Err(AdminAccessDenied::Unauthorized),
+            admin_access.check_key(AdminScope::ReadOnly, None).1
+        );
+
+        settings.admin_api_insecure = true;
+
+        let admin_access = AdminAccess::new(&settings);
+        assert_eq!(Ok(()), admin_access.check_key(AdminScope::Admin, None).1);
+
+        settings.admin_keys = Some(vec![AdminKey {
+            identity: "ops".to_string(),
+            key: "ops-key".to_string(),
+            scope: AdminScope::ReadOnly,
+        }]);
+
+        let admin_access = AdminAccess::new(&settings);
+        assert_eq!(
+            Err(AdminAccessDenied::Unauthorized),
+            admin_access.check_key(AdminScope::ReadOnly, None).1
+        );
+        assert_eq!(
+            Ok(()),
+            admin_access
+                .check_key(AdminScope::ReadOnly, Some("ops-key"))
+                .1
+        );
+        assert_eq!(
+            Err(AdminAccessDenied::Forbidden),
+            admin_access.check_key(AdminScope::Admin, Some("ops-key")).1
+        );
+    }
+
+    #[test]
+    fn test_api_routes_which_are_not_listed_are_protected() {
+        assert_eq!(
+            Some(AdminScope::ReadOnly),
+            get_required_admin_scope("GET", "/api/Sessions")
+        );
+        assert_eq!(
+            Some(AdminScope::Admin),
+            get_required_admin_scope("PUT", "/api/SomethingNew")
+        );
+        assert_eq!(
+            Some(AdminScope::ReadOnly),
+            get_required_admin_scope("GET", "/api/Publish")
+        );
+        assert_eq!(
+            Some(AdminScope::Admin),
+            get_required_admin_scope("DELETE", "/api/Subscribers/Await")
+        );
+    }
+}
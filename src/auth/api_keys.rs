@@ -0,0 +1,235 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::settings::ApiKeySettings;
+
+use super::ANONYMOUS_ACL_IDENTITY;
+
+// Token is hmac:<Identity>:<UnixSeconds>:<hex of HMAC-SHA256(Key, "<Identity>:<UnixSeconds>")>
+pub const HMAC_TOKEN_PREFIX: &str = "hmac:";
+pub const HMAC_TOKEN_MAX_CLOCK_SKEW_SECS: i64 = 300;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthFailReason {
+    NoCredentials,
+    UnknownApiKey,
+    InvalidToken(String),
+    TokenIsExpired,
+}
+
+pub fn check_api_keys(api_keys: &[ApiKeySettings]) -> Result<(), String> {
+    for (index, api_key) in api_keys.iter().enumerate() {
+        if api_key.identity.is_empty() {
+            return Err("Identity of api key can not be empty".to_string());
+        }
+
+        if api_key.identity == ANONYMOUS_ACL_IDENTITY {
+            return Err(format!(
+                "Identity {} is reserved for unauthenticated sessions",
+                ANONYMOUS_ACL_IDENTITY
+            ));
+        }
+
+        if api_key.key.is_empty() {
+            return Err(format!("Key of identity {} is empty", api_key.identity));
+        }
+
+        if api_key.key.starts_with(HMAC_TOKEN_PREFIX) {
+            return Err(format!(
+                "Key of identity {} can not start with {}",
+                api_key.identity, HMAC_TOKEN_PREFIX
+            ));
+        }
+
+        if api_keys[..index].iter().any(|itm| itm.key == api_key.key) {
+            return Err(format!(
+                "Key of identity {} is duplicated",
+                api_key.identity
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub struct ApiKeysList {
+    items: Vec<ApiKeySettings>,
+}
+
+impl ApiKeysList {
+    pub fn new(items: Vec<ApiKeySettings>) -> Self {
+        Self { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    // Returns identity of the key. Credential is either the key itself or hmac token
+    pub fn authenticate(
+        &self,
+        credential: &str,
+        now_unix_secs: i64,
+    ) -> Result<String, AuthFailReason> {
+        if credential.is_empty() {
+            return Err(AuthFailReason::NoCredentials);
+        }
+
+        if let Some(token) = credential.strip_prefix(HMAC_TOKEN_PREFIX) {
+            return self.authenticate_hmac_token(token, now_unix_secs);
+        }
+
+        // Every key is compared to not leak which one has matched by timing
+        let mut result = None;
+
+        for api_key in &self.items {
+            if constant_time_eq(api_key.key.as_bytes(), credential.as_bytes()) {
+                result = Some(api_key.identity.to_string());
+            }
+        }
+
+        result.ok_or(AuthFailReason::UnknownApiKey)
+    }
+
+    fn authenticate_hmac_token(
+        &self,
+        token: &str,
+        now_unix_secs: i64,
+    ) -> Result<String, AuthFailReason> {
+        let mut parts = token.rsplitn(3, ':');
+
+        let (signature, timestamp, identity) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(signature), Some(timestamp), Some(identity)) => (signature, timestamp, identity),
+            _ => {
+                return Err(AuthFailReason::InvalidToken(
+                    "Token must be hmac:<Identity>:<UnixSeconds>:<Signature>".to_string(),
+                ))
+            }
+        };
+
+        let timestamp_secs: i64 = timestamp.parse().map_err(|_| {
+            AuthFailReason::InvalidToken(format!("Invalid token timestamp {}", timestamp))
+        })?;
+
+        if (now_unix_secs - timestamp_secs).abs() > HMAC_TOKEN_MAX_CLOCK_SKEW_SECS {
+            return Err(AuthFailReason::TokenIsExpired);
+        }
+
+        let signature = decode_hex(signature).ok_or_else(|| {
+            AuthFailReason::InvalidToken("Signature must be hex encoded".to_string())
+        })?;
+
+        let message = format!("{}:{}", identity, timestamp);
+
+        for api_key in self.items.iter().filter(|itm| itm.identity == identity) {
+            let mut mac = HmacSha256::new_from_slice(api_key.key.as_bytes())
+                .expect("HMAC accepts keys of any size");
+            mac.update(message.as_bytes());
+
+            if mac.verify_slice(signature.as_slice()).is_ok() {
+                return Ok(api_key.identity.to_string());
+            }
+        }
+
+        Err(AuthFailReason::InvalidToken(
+            "Signature does not match".to_string(),
+        ))
+    }
+}
+
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn decode_hex(src: &str) -> Option<Vec<u8>> {
+    if src.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..src.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(src.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_700_000_000;
+
+    fn create_list() -> ApiKeysList {
+        ApiKeysList::new(vec![ApiKeySettings {
+            identity: "orders-service".to_string(),
+            key: "secret-key".to_string(),
+        }])
+    }
+
+    fn sign(key: &str, message: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(key.as_bytes()).unwrap();
+        mac.update(message.as_bytes());
+
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    #[test]
+    fn test_api_key() {
+        let list = create_list();
+
+        assert_eq!(
+            "orders-service",
+            list.authenticate("secret-key", NOW).unwrap()
+        );
+        assert_eq!(
+            AuthFailReason::UnknownApiKey,
+            list.authenticate("other-key", NOW).err().unwrap()
+        );
+        assert_eq!(
+            AuthFailReason::NoCredentials,
+            list.authenticate("", NOW).err().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hmac_token() {
+        let list = create_list();
+
+        let message = format!("orders-service:{}", NOW);
+        let token = format!("hmac:{}:{}", message, sign("secret-key", message.as_str()));
+
+        assert_eq!(
+            "orders-service",
+            list.authenticate(token.as_str(), NOW + 10).unwrap()
+        );
+
+        assert_eq!(
+            AuthFailReason::TokenIsExpired,
+            list.authenticate(token.as_str(), NOW + HMAC_TOKEN_MAX_CLOCK_SKEW_SECS + 1)
+                .err()
+                .unwrap()
+        );
+
+        let forged = format!("hmac:{}:{}", message, sign("other-key", message.as_str()));
+        assert!(list.authenticate(forged.as_str(), NOW).is_err());
+    }
+
+    #[test]
+    fn test_anonymous_identity_is_reserved() {
+        let api_keys = vec![ApiKeySettings {
+            identity: ANONYMOUS_ACL_IDENTITY.to_string(),
+            key: "secret-key".to_string(),
+        }];
+
+        assert!(check_api_keys(&api_keys).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::RwLock;
+
+use crate::settings::{ApiKeySettings, AuthSettings};
+
+use super::{check_api_keys, ApiKeysList, AuthFailReason};
+
+pub struct Authenticator {
+    settings_api_keys: Vec<ApiKeySettings>,
+    api_keys_file: Option<String>,
+    api_keys: RwLock<ApiKeysList>,
+}
+
+impl Authenticator {
+    pub async fn new(settings: &AuthSettings) -> Result<Self, String> {
+        let result = Self {
+            settings_api_keys: settings.api_keys.clone(),
+            api_keys_file: settings.api_keys_file.clone(),
+            api_keys: RwLock::new(ApiKeysList::new(vec![])),
+        };
+
+        result.reload_api_keys_file().await?;
+
+        Ok(result)
+    }
+
+    // Keys of the settings are kept. Keys of the file are replaced. Returns amount of keys
+    pub async fn reload_api_keys_file(&self) -> Result<usize, String> {
+        let mut api_keys = self.settings_api_keys.clone();
+
+        if let Some(api_keys_file) = &self.api_keys_file {
+            api_keys.extend(read_api_keys_file(api_keys_file.as_str()).await?);
+        }
+
+        check_api_keys(&api_keys)?;
+
+        let api_keys = ApiKeysList::new(api_keys);
+        let result = api_keys.len();
+
+        let mut write_access = self.api_keys.write().await;
+        *write_access = api_keys;
+
+        Ok(result)
+    }
+
+    pub fn has_api_keys_file(&self) -> bool {
+        self.api_keys_file.is_some()
+    }
+
+    // Returns identity of authenticated application
+    pub async fn authenticate(&self, credential: Option<&str>) -> Result<String, AuthFailReason> {
+        let credential = match credential {
+            Some(credential) => credential,
+            None => return Err(AuthFailReason::NoCredentials),
+        };
+
+        let now_unix_secs = DateTimeAsMicroseconds::now().unix_microseconds / 1_000_000;
+
+        let read_access = self.api_keys.read().await;
+        read_access.authenticate(credential, now_unix_secs)
+    }
+}
+
+async fn read_api_keys_file(file_name: &str) -> Result<Vec<ApiKeySettings>, String> {
+    let content = tokio::fs::read(file_name).await.map_err(|err| {
+        format!(
+            "Can not read api keys file {}. The reason is: {:?}",
+            file_name, err
+        )
+    })?;
+
+    if content.is_empty() {
+        return Ok(vec![]);
+    }
+
+    serde_yaml::from_slice(content.as_slice()).map_err(|err| {
+        format!(
+            "Can not parse api keys file {}. The reason is: {:?}",
+            file_name, err
+        )
+    })
+}
@@ -0,0 +1,9 @@
+mod admin_access;
+mod api_keys;
+mod authenticator;
+mod topic_acl;
+
+pub use admin_access::*;
+pub use api_keys::*;
+pub use authenticator::*;
+pub use topic_acl::*;
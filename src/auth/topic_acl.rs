@@ -0,0 +1,128 @@
+use crate::settings::TopicAclSettings;
+
+// Sessions of listeners which do not require authentication have no identity.
+// They get permissions of the entry with this identity
+pub const ANONYMOUS_ACL_IDENTITY: &str = "anonymous";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AclPermission {
+    Publish,
+    Subscribe,
+    CreateQueue,
+}
+
+impl AclPermission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AclPermission::Publish => "publish",
+            AclPermission::Subscribe => "subscribe",
+            AclPermission::CreateQueue => "create_queue",
+        }
+    }
+}
+
+pub struct TopicAcl {
+    items: Vec<TopicAclSettings>,
+}
+
+impl TopicAcl {
+    pub fn new(items: Vec<TopicAclSettings>) -> Self {
+        Self { items }
+    }
+
+    // Name is topic id for Publish and Subscribe and queue id for CreateQueue.
+    // Identity which is not listed has no permissions
+    pub fn is_allowed(&self, identity: &str, permission: AclPermission, name: &str) -> bool {
+        let item = match self.items.iter().find(|itm| itm.identity == identity) {
+            Some(item) => item,
+            None => return false,
+        };
+
+        let patterns = match permission {
+            AclPermission::Publish => item.publish.as_ref(),
+            AclPermission::Subscribe => item.subscribe.as_ref(),
+            AclPermission::CreateQueue => match item.create_queues.as_ref() {
+                Some(patterns) => Some(patterns),
+                None => return true,
+            },
+        };
+
+        match patterns {
+            Some(patterns) => patterns
+                .iter()
+                .any(|pattern| matches_pattern(pattern.as_str(), name)),
+            None => false,
+        }
+    }
+}
+
+// * matches any sequence of characters. Comparison is case insensitive as topic ids are
+pub fn matches_pattern(pattern: &str, value: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let value = value.to_lowercase();
+
+    let mut parts = pattern.split('*');
+
+    let first = parts.next().unwrap_or_default();
+
+    let mut rest = match value.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+
+    let parts: Vec<&str> = parts.collect();
+
+    let last = match parts.last() {
+        Some(last) => *last,
+        None => return rest.is_empty(),
+    };
+
+    for part in &parts[..parts.len() - 1] {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("orders", "orders"));
+        assert!(matches_pattern("orders", "Orders"));
+        assert!(!matches_pattern("orders", "orders-eu"));
+        assert!(matches_pattern("orders-*", "orders-eu"));
+        assert!(!matches_pattern("orders-*", "payments-eu"));
+        assert!(matches_pattern("*", "anything"));
+        assert!(matches_pattern("*-events", "orders-events"));
+        assert!(matches_pattern("orders-*-events", "orders-eu-events"));
+        assert!(!matches_pattern("orders-*-events", "orders-eu"));
+        assert!(!matches_pattern("a*a", "a"));
+    }
+
+    #[test]
+    fn test_is_allowed() {
+        let acl = TopicAcl::new(vec![TopicAclSettings {
+            identity: "orders-service".to_string(),
+            publish: Some(vec!["orders-*".to_string()]),
+            subscribe: Some(vec!["payments".to_string()]),
+            create_queues: Some(vec!["orders-service-*".to_string()]),
+        }]);
+
+        assert!(acl.is_allowed("orders-service", AclPermission::Publish, "orders-eu"));
+        assert!(!acl.is_allowed("orders-service", AclPermission::Publish, "payments"));
+        assert!(acl.is_allowed("orders-service", AclPermission::Subscribe, "payments"));
+        assert!(acl.is_allowed(
+            "orders-service",
+            AclPermission::CreateQueue,
+            "orders-service-main"
+        ));
+        assert!(!acl.is_allowed("orders-service", AclPermission::CreateQueue, "main"));
+        assert!(!acl.is_allowed("other-service", AclPermission::Subscribe, "payments"));
+    }
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use rust_extensions::MyTimerTick;
+
+use crate::app::AppContext;
+
+pub struct ClusterAssignmentTimer {
+    app: Arc<AppContext>,
+}
+
+impl ClusterAssignmentTimer {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for ClusterAssignmentTimer {
+    async fn tick(&self) {
+        if let Some(cluster) = &self.app.cluster {
+            cluster.reload_assignments().await;
+        }
+    }
+}
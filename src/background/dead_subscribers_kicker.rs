@@ -4,7 +4,7 @@ use my_logger::LogEventCtx;
 use rust_extensions::MyTimerTick;
 use tokio::sync::Mutex;
 
-use crate::{app::AppContext, topics::ReusableTopicsList};
+use crate::{app::AppContext, topics::ReusableTopicsList, webhooks::WebhookEvent};
 
 pub struct DeadSubscribersKickerTimer {
     app: Arc<AppContext>,
@@ -41,7 +41,7 @@ impl MyTimerTick for DeadSubscribersKickerTimer {
 
         for topic in topics.iter() {
             if let Some(dead_subscribers) = topic
-                .find_subscribers_dead_on_delivery(self.app.delivery_timeout)
+                .find_subscribers_dead_on_delivery(self.app.runtime_settings.get_delivery_timeout())
                 .await
             {
                 for dead_subscriber in dead_subscribers {
@@ -55,6 +55,13 @@ impl MyTimerTick for DeadSubscribersKickerTimer {
                         LogEventCtx::new().add("topicId", topic.topic_id.as_str()),
                     );
 
+                    self.app.webhooks.publish(WebhookEvent::SubscriberKicked {
+                        topic_id: topic.topic_id.as_str().to_string(),
+                        queue_id: dead_subscriber.queue_id.as_str().to_string(),
+                        subscriber_id: dead_subscriber.subscriber_id.get_value(),
+                        session_id: dead_subscriber.session.get_session_id().get_value(),
+                    });
+
                     dead_subscriber.session.disconnect().await;
                 }
             }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use futures_util::lock::Mutex;
 use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
 
-use crate::{app::AppContext, topics::ReusableTopicsList};
+use crate::{app::AppContext, topics::ReusableTopicsList, webhooks::WebhookEvent};
 
 //const PAGE_GC_DELAY: Duration = Duration::from_secs(10);
 
@@ -61,6 +61,11 @@ impl MyTimerTick for GcTimer {
                     self.app
                         .prometheus
                         .queue_is_deleted(topic.topic_id.as_str(), queue_id);
+
+                    self.app.webhooks.publish(WebhookEvent::QueueDeleted {
+                        topic_id: topic.topic_id.as_str().to_string(),
+                        queue_id: queue_id.to_string(),
+                    });
                 }
             }
         }
@@ -68,5 +73,6 @@ impl MyTimerTick for GcTimer {
         self.put_reusable_topics_vec_back(topic_list).await;
 
         crate::operations::gc_http_connections(self.app.as_ref()).await;
+        crate::operations::gc_grpc_connections(self.app.as_ref()).await;
     }
 }
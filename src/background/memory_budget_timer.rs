@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use rust_extensions::MyTimerTick;
+
+use crate::app::AppContext;
+
+pub struct MemoryBudgetTimer {
+    app: Arc<AppContext>,
+    memory_budget: usize,
+}
+
+impl MemoryBudgetTimer {
+    pub fn new(app: Arc<AppContext>, memory_budget: usize) -> Self {
+        Self { app, memory_budget }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for MemoryBudgetTimer {
+    async fn tick(&self) {
+        crate::operations::evict_sub_pages_over_memory_budget(
+            self.app.as_ref(),
+            self.memory_budget,
+        )
+        .await;
+    }
+}
@@ -1,16 +1,19 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use my_http_server::HttpConnectionsCounter;
 use my_tcp_sockets::ThreadsStatistics;
-use rust_extensions::MyTimerTick;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, MyTimerTick};
 use tokio::sync::Mutex;
 
-use crate::{app::AppContext, topics::ReusableTopicsList};
+use crate::{
+    app::{AppContext, GRPC_PROTOCOL, HTTP_PROTOCOL},
+    topics::{ReusableTopicsList, TopicInner},
+};
 
 pub struct MetricsTimer {
     app: Arc<AppContext>,
     http_connections_counter: HttpConnectionsCounter,
-    threads_statistics: Arc<ThreadsStatistics>,
+    threads_statistics: Vec<Arc<ThreadsStatistics>>,
     reusable_topics_vec: Mutex<Option<ReusableTopicsList>>,
 }
 
@@ -18,7 +21,7 @@ impl MetricsTimer {
     pub fn new(
         app: Arc<AppContext>,
         http_connections_counter: HttpConnectionsCounter,
-        threads_statistics: Arc<ThreadsStatistics>,
+        threads_statistics: Vec<Arc<ThreadsStatistics>>,
     ) -> Self {
         Self {
             app,
@@ -59,9 +62,12 @@ impl MyTimerTick for MetricsTimer {
 
         self.app.topic_list.fill_topics(&mut reusable_topics).await;
 
+        let now = DateTimeAsMicroseconds::now();
+
         for topic in reusable_topics.iter() {
             let metrics = {
-                let mut topic_data = topic.get_access().await;
+                let mut topic_access = topic.get_access().await;
+                let topic_data: &mut TopicInner = &mut topic_access;
 
                 topic_data.one_second_tick();
 
@@ -77,6 +83,21 @@ impl MyTimerTick for MetricsTimer {
                         queue_size,
                     );
 
+                    // Age is unknown while sub page of the oldest message is not loaded
+                    let oldest_message_age = match queue.get_oldest_undelivered_message_id() {
+                        Some(message_id) => topic_data
+                            .pages
+                            .get_message_time(message_id)
+                            .map(|time| now.duration_since(time).as_positive_or_zero()),
+                        None => Some(Duration::from_secs(0)),
+                    };
+
+                    self.app.prometheus.update_queue_oldest_message_age(
+                        topic.topic_id.as_str(),
+                        queue.queue_id.as_str(),
+                        oldest_message_age,
+                    );
+
                     if queue.is_permanent() && queue.subscribers.get_amount() == 0 {
                         permanent_queues_without_subscribers += 1;
                     }
@@ -106,6 +127,17 @@ impl MyTimerTick for MetricsTimer {
 
         self.put_reusable_topics_vec_back(reusable_topics).await;
 
+        if let Some(http_listener) = self.app.listeners.get(HTTP_PROTOCOL, HTTP_PROTOCOL) {
+            let http_connections_amount = self.http_connections_counter.get_connections_amount();
+            http_listener.set_connections(http_connections_amount.max(0) as usize);
+        }
+
+        // Grpc streams are served by tonic, so connections are counted by sessions
+        if let Some(grpc_listener) = self.app.listeners.get(GRPC_PROTOCOL, GRPC_PROTOCOL) {
+            let grpc_sessions_amount = self.app.sessions.get_grpc_sessions_amount().await;
+            grpc_listener.set_connections(grpc_sessions_amount);
+        }
+
         self.app
             .prometheus
             .update_permanent_queues_without_subscribers(permanent_queues_without_subscribers);
@@ -113,5 +145,9 @@ impl MyTimerTick for MetricsTimer {
         self.app
             .prometheus
             .update_topics_without_queues(topics_without_queues);
+
+        self.app
+            .prometheus
+            .update_page_loads_in_flight(self.app.page_loads.get_in_flight_amount());
     }
 }
@@ -1,10 +1,18 @@
+mod cluster_assignment_timer;
 mod dead_subscribers_kicker;
 mod gc_timer;
 mod immediately_persist_event_loop;
+mod memory_budget_timer;
 mod metrics_timer;
 mod persist_topics_and_queues;
+mod settings_reload_signal;
+mod tls_certificates_timer;
+pub use cluster_assignment_timer::ClusterAssignmentTimer;
 pub use dead_subscribers_kicker::DeadSubscribersKickerTimer;
 pub use gc_timer::GcTimer;
 pub use immediately_persist_event_loop::*;
+pub use memory_budget_timer::MemoryBudgetTimer;
 pub use metrics_timer::MetricsTimer;
 pub use persist_topics_and_queues::PersistTopicsAndQueuesTimer;
+pub use settings_reload_signal::start_settings_reload_signal_listener;
+pub use tls_certificates_timer::TlsCertificatesTimer;
@@ -36,6 +36,11 @@ impl PersistTopicsAndQueuesTimer {
 #[async_trait::async_trait]
 impl MyTimerTick for PersistTopicsAndQueuesTimer {
     async fn tick(&self) {
+        // Active node owns the persisted snapshot
+        if self.app.replication.is_passive() {
+            return;
+        }
+
         let mut reusable_topics = self.get_reusable_topics_vec().await;
         crate::operations::persist_topics_and_queues(&self.app, &mut reusable_topics).await;
         self.put_reusable_topics_vec_back(reusable_topics).await;
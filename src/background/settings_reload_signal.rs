@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use crate::app::AppContext;
+
+#[cfg(unix)]
+pub fn start_settings_reload_signal_listener(app: Arc<AppContext>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sighup = match signal(SignalKind::hangup()) {
+        Ok(sighup) => sighup,
+        Err(err) => {
+            println!("Can not subscribe to SIGHUP. Settings reload is available through http only. Err: {:?}", err);
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        while sighup.recv().await.is_some() {
+            println!("SIGHUP is received. Reloading settings");
+            let _ = crate::operations::reload_settings::reload_settings(app.as_ref()).await;
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn start_settings_reload_signal_listener(_app: Arc<AppContext>) {}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use rust_extensions::MyTimerTick;
+
+use crate::tcp::tls::TlsServerConfig;
+
+pub struct TlsCertificatesTimer {
+    configs: Vec<Arc<TlsServerConfig>>,
+}
+
+impl TlsCertificatesTimer {
+    pub fn new(configs: Vec<Arc<TlsServerConfig>>) -> Self {
+        Self { configs }
+    }
+}
+
+#[async_trait::async_trait]
+impl MyTimerTick for TlsCertificatesTimer {
+    async fn tick(&self) {
+        for config in &self.configs {
+            config.reload_if_changed();
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use crate::settings::ClusterNodeSettings;
+
+// Shared file with explicit topic assignments:
+// TopicId: NodeId
+pub async fn load_assignment_table(
+    file_path: &str,
+    nodes: &[ClusterNodeSettings],
+) -> Result<BTreeMap<String, String>, String> {
+    let content = tokio::fs::read(file_path).await.map_err(|err| {
+        format!(
+            "Can not read assignment table {}. Err: {:?}",
+            file_path, err
+        )
+    })?;
+
+    parse_assignment_table(content.as_slice(), nodes)
+}
+
+pub fn parse_assignment_table(
+    content: &[u8],
+    nodes: &[ClusterNodeSettings],
+) -> Result<BTreeMap<String, String>, String> {
+    if content.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let table: BTreeMap<String, String> = serde_yaml::from_slice(content)
+        .map_err(|err| format!("Can not parse assignment table. Err: {:?}", err))?;
+
+    for (topic_id, node_id) in &table {
+        if !nodes.iter().any(|itm| &itm.id == node_id) {
+            return Err(format!(
+                "Topic {} is assigned to unknown node {}",
+                topic_id, node_id
+            ));
+        }
+    }
+
+    Ok(table)
+}
+
+// Topic can not change its owner at runtime: messages and queue positions are not handed off.
+// Every node applies the same rule, so owner change is refused cluster-wide.
+// Returns assignments with owner changes reverted and the topics which keep their owners
+pub fn refuse_reassignments(
+    current: &BTreeMap<String, String>,
+    mut new: BTreeMap<String, String>,
+    nodes: &[ClusterNodeSettings],
+) -> (BTreeMap<String, String>, Vec<String>) {
+    let mut refused = Vec::new();
+
+    let topics: Vec<String> = current.keys().chain(new.keys()).cloned().collect();
+
+    for topic_id in topics {
+        let current_owner = match current.get(&topic_id) {
+            Some(node_id) => node_id.to_string(),
+            None => get_default_owner(topic_id.as_str(), nodes).id.to_string(),
+        };
+
+        let new_owner = match new.get(&topic_id) {
+            Some(node_id) => node_id.to_string(),
+            None => get_default_owner(topic_id.as_str(), nodes).id.to_string(),
+        };
+
+        if current_owner != new_owner {
+            new.insert(topic_id.to_string(), current_owner);
+
+            if !refused.contains(&topic_id) {
+                refused.push(topic_id);
+            }
+        }
+    }
+
+    (new, refused)
+}
+
+// Rendezvous hashing. Every node gets the same owner for the topic which is not in assignment table
+pub fn get_default_owner<'s>(
+    topic_id: &str,
+    nodes: &'s [ClusterNodeSettings],
+) -> &'s ClusterNodeSettings {
+    nodes
+        .iter()
+        .max_by_key(|node| get_score(node.id.as_str(), topic_id))
+        .unwrap()
+}
+
+fn get_score(node_id: &str, topic_id: &str) -> u64 {
+    let digest = md5::compute(format!("{}/{}", node_id, topic_id));
+
+    let mut result = [0u8; 8];
+    result.copy_from_slice(&digest.0[..8]);
+    u64::from_le_bytes(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_nodes() -> Vec<ClusterNodeSettings> {
+        ["node-a", "node-b", "node-c"]
+            .iter()
+            .map(|id| ClusterNodeSettings {
+                id: id.to_string(),
+                tcp_address: format!("{}:6421", id),
+                http_url: format!("http://{}:6123", id),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_assignment_table() {
+        let nodes = create_nodes();
+
+        let table = parse_assignment_table(b"topic-1: node-b\ntopic-2: node-c", &nodes).unwrap();
+
+        assert_eq!("node-b", table.get("topic-1").unwrap());
+        assert_eq!("node-c", table.get("topic-2").unwrap());
+
+        assert!(parse_assignment_table(b"topic-1: node-x", &nodes).is_err());
+    }
+
+    #[test]
+    fn test_reassignment_is_refused() {
+        let nodes = create_nodes();
+
+        let current = parse_assignment_table(b"topic-1: node-b\ntopic-2: node-c", &nodes).unwrap();
+
+        let default_owner = get_default_owner("topic-3", &nodes).id.clone();
+        let other_node = nodes
+            .iter()
+            .find(|itm| itm.id != default_owner)
+            .unwrap()
+            .id
+            .clone();
+
+        let new = parse_assignment_table(
+            format!(
+                "topic-1: node-b\ntopic-2: node-a\ntopic-3: {}\ntopic-4: {}",
+                other_node,
+                get_default_owner("topic-4", &nodes).id
+            )
+            .as_bytes(),
+            &nodes,
+        )
+        .unwrap();
+
+        let (result, refused) = refuse_reassignments(&current, new, &nodes);
+
+        assert_eq!("node-b", result.get("topic-1").unwrap());
+        assert_eq!("node-c", result.get("topic-2").unwrap());
+        assert_eq!(&default_owner, result.get("topic-3").unwrap());
+        assert_eq!(
+            &get_default_owner("topic-4", &nodes).id,
+            result.get("topic-4").unwrap()
+        );
+        assert_eq!(vec!["topic-2".to_string(), "topic-3".to_string()], refused);
+    }
+
+    #[test]
+    fn test_default_owner_is_stable() {
+        let nodes = create_nodes();
+
+        let owner = get_default_owner("test-topic", &nodes).id.clone();
+
+        let mut reversed_nodes = create_nodes();
+        reversed_nodes.reverse();
+
+        assert_eq!(owner, get_default_owner("test-topic", &reversed_nodes).id);
+    }
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use my_logger::LogEventCtx;
+use tokio::sync::RwLock;
+
+use crate::{
+    operations::OperationFailResult,
+    settings::{ClusterNodeSettings, ClusterSettings},
+};
+
+pub struct ClusterTopology {
+    pub node_id: String,
+    pub nodes: Vec<ClusterNodeSettings>,
+    assignment_table_path: Option<String>,
+    assignments: RwLock<BTreeMap<String, String>>,
+}
+
+impl ClusterTopology {
+    pub async fn new(settings: &ClusterSettings) -> Self {
+        let result = Self {
+            node_id: settings.node_id.to_string(),
+            nodes: settings.nodes.clone(),
+            assignment_table_path: settings.assignment_table_path.clone(),
+            assignments: RwLock::new(BTreeMap::new()),
+        };
+
+        if let Some(assignment_table_path) = &result.assignment_table_path {
+            match super::load_assignment_table(assignment_table_path.as_str(), &result.nodes).await
+            {
+                Ok(assignments) => {
+                    *result.assignments.write().await = assignments;
+                }
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        result
+    }
+
+    // Keeps previous assignments if the shared table can not be read.
+    // Owner changes are applied only at startup, so they need a coordinated restart of the cluster
+    pub async fn reload_assignments(&self) {
+        let assignment_table_path = match &self.assignment_table_path {
+            Some(assignment_table_path) => assignment_table_path,
+            None => return,
+        };
+
+        match super::load_assignment_table(assignment_table_path.as_str(), &self.nodes).await {
+            Ok(assignments) => {
+                let mut write_access = self.assignments.write().await;
+
+                let (assignments, refused) =
+                    super::refuse_reassignments(&write_access, assignments, &self.nodes);
+
+                for topic_id in refused {
+                    my_logger::LOGGER.write_error(
+                        "Cluster",
+                        format!(
+                            "Topic can not change its owner at runtime. Keeping owner {}",
+                            assignments.get(&topic_id).unwrap()
+                        ),
+                        LogEventCtx::new()
+                            .add("topicId", topic_id.as_str())
+                            .add("assignmentTable", assignment_table_path.as_str()),
+                    );
+                }
+
+                *write_access = assignments;
+            }
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "Cluster",
+                    err,
+                    LogEventCtx::new().add("assignmentTable", assignment_table_path.as_str()),
+                );
+            }
+        }
+    }
+
+    pub async fn get_owner(&self, topic_id: &str) -> &ClusterNodeSettings {
+        let read_access = self.assignments.read().await;
+
+        if let Some(node_id) = read_access.get(topic_id) {
+            if let Some(node) = self.nodes.iter().find(|itm| &itm.id == node_id) {
+                return node;
+            }
+        }
+
+        super::get_default_owner(topic_id, &self.nodes)
+    }
+
+    pub async fn check_topic_owner(&self, topic_id: &str) -> Result<(), OperationFailResult> {
+        let owner = self.get_owner(topic_id).await;
+
+        if owner.id == self.node_id {
+            return Ok(());
+        }
+
+        Err(OperationFailResult::TopicIsOwnedByOtherNode {
+            topic_id: topic_id.to_string(),
+            node_id: owner.id.to_string(),
+            tcp_address: owner.tcp_address.to_string(),
+            http_url: owner.http_url.to_string(),
+        })
+    }
+
+    // Owners of explicitly assigned topics and of the given ones. Nodes do not exchange their topics,
+    // so topics of other nodes which are not in the assignment table are not listed
+    pub async fn get_known_topic_owners(&self, topics: &[String]) -> BTreeMap<String, String> {
+        let mut result = {
+            let read_access = self.assignments.read().await;
+            read_access.clone()
+        };
+
+        for topic_id in topics {
+            if !result.contains_key(topic_id) {
+                let owner = self.get_owner(topic_id.as_str()).await;
+                result.insert(topic_id.to_string(), owner.id.to_string());
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,5 @@
+mod assignment_table;
+mod cluster_topology;
+
+pub use assignment_table::*;
+pub use cluster_topology::*;
@@ -11,3 +11,27 @@ impl From<String> for OperationFailResult {
         Self::Other(src)
     }
 }
+
+impl From<OperationFailResult> for tonic::Status {
+    fn from(src: OperationFailResult) -> Self {
+        let message = format!("{:?}", src);
+        match src {
+            OperationFailResult::TopicNotFound { .. } => Self::not_found(message),
+            OperationFailResult::QueueNotFound { .. } => Self::not_found(message),
+            OperationFailResult::SubscriberNotFound { .. } => Self::not_found(message),
+            OperationFailResult::SessionIsDisconnected => Self::unauthenticated(message),
+            OperationFailResult::ShuttingDown => Self::unavailable(message),
+            OperationFailResult::NodeIsPassive => Self::unavailable(message),
+            OperationFailResult::TopicIsOwnedByOtherNode { .. } => {
+                Self::failed_precondition(message)
+            }
+            OperationFailResult::TopicOrQueueValidationError(_) => Self::invalid_argument(message),
+            OperationFailResult::AccessDenied { .. } => Self::permission_denied(message),
+            OperationFailResult::PublishRateLimitIsExceeded { .. } => {
+                Self::resource_exhausted(message)
+            }
+            OperationFailResult::TonicError(status) => status,
+            _ => Self::internal(message),
+        }
+    }
+}
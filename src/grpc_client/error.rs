@@ -8,6 +8,14 @@ pub enum PersistenceError {
     InvalidProtobufPayload(String),
     CompressedPageReaderError(CompressedPageReaderError),
     Timeout(Option<tokio::time::error::Elapsed>),
+    IoError(std::io::Error),
+    CircuitIsOpen,
+}
+
+impl From<std::io::Error> for PersistenceError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
 }
 
 impl From<tokio::time::error::Elapsed> for PersistenceError {
@@ -8,16 +8,28 @@ use crate::{messages_page::MySbMessageContent, settings::SettingsModel};
 
 #[cfg(test)]
 use super::MessagesPagesMockRepo;
-use super::{MessagesPagesGrpcRepo, PersistenceError};
+use super::{MessagesPagesDiskRepo, MessagesPagesGrpcRepo, PersistenceError};
+
+const LOCAL_MESSAGES_FOLDER: &str = "messages";
 
 pub enum MessagesPagesRepo {
     Grpc(MessagesPagesGrpcRepo),
+    Disk(MessagesPagesDiskRepo),
     #[cfg(test)]
     Mock(MessagesPagesMockRepo),
 }
 
 impl MessagesPagesRepo {
     pub async fn create_production_instance(settings: &SettingsModel) -> Self {
+        if let Some(local_storage_path) = &settings.local_storage_path {
+            return Self::Disk(
+                MessagesPagesDiskRepo::new(
+                    std::path::Path::new(local_storage_path).join(LOCAL_MESSAGES_FOLDER),
+                )
+                .await,
+            );
+        }
+
         Self::Grpc(MessagesPagesGrpcRepo::new(settings.persistence_grpc_url.to_string()).await)
     }
 
@@ -26,6 +38,14 @@ impl MessagesPagesRepo {
         Self::Mock(MessagesPagesMockRepo::new())
     }
 
+    #[cfg(test)]
+    pub fn unwrap_mock(&self) -> &MessagesPagesMockRepo {
+        match self {
+            Self::Mock(repo) => repo,
+            _ => panic!("Mock repo is expected"),
+        }
+    }
+
     pub async fn load_page(
         &self,
         topic_id: &str,
@@ -38,6 +58,10 @@ impl MessagesPagesRepo {
                 repo.load_page(topic_id, page_id, from_message_id, to_message_id)
                     .await
             }
+            MessagesPagesRepo::Disk(repo) => {
+                repo.load_page(topic_id, from_message_id, to_message_id)
+                    .await
+            }
             #[cfg(test)]
             MessagesPagesRepo::Mock(repo) => {
                 repo.load_page(topic_id, from_message_id, to_message_id)
@@ -53,6 +77,7 @@ impl MessagesPagesRepo {
     ) -> Result<(), PersistenceError> {
         match self {
             MessagesPagesRepo::Grpc(repo) => repo.save_messages(topic_id, messages).await,
+            MessagesPagesRepo::Disk(repo) => repo.save_messages(topic_id, messages, true).await,
             #[cfg(test)]
             MessagesPagesRepo::Mock(repo) => repo.save_messages(topic_id, messages).await,
         }
@@ -67,14 +92,32 @@ impl MessagesPagesRepo {
             MessagesPagesRepo::Grpc(repo) => {
                 repo.save_messages_uncompressed(topic_id, messages).await
             }
+            MessagesPagesRepo::Disk(repo) => repo.save_messages(topic_id, messages, false).await,
             #[cfg(test)]
             MessagesPagesRepo::Mock(repo) => repo.save_messages(topic_id, messages).await,
         }
     }
 
+    pub async fn get_message(
+        &self,
+        topic_id: &str,
+        message_id: MessageId,
+    ) -> Result<Option<MySbMessageContent>, PersistenceError> {
+        match self {
+            MessagesPagesRepo::Grpc(repo) => repo.get_message(topic_id, message_id).await,
+            MessagesPagesRepo::Disk(repo) => repo.get_message(topic_id, message_id).await,
+            #[cfg(test)]
+            MessagesPagesRepo::Mock(repo) => {
+                let result = repo.load_page(topic_id, message_id, message_id).await?;
+                Ok(result.and_then(|mut messages| messages.remove(&message_id.get_value())))
+            }
+        }
+    }
+
     pub async fn get_persistence_version(&self) -> Option<String> {
         let result = match self {
             MessagesPagesRepo::Grpc(repo) => repo.get_persistence_version().await,
+            MessagesPagesRepo::Disk(_) => Ok(format!("Local disk {}", crate::app::APP_VERSION)),
             #[cfg(test)]
             MessagesPagesRepo::Mock(_) => Ok("Mock".to_string()),
         };
@@ -88,6 +131,7 @@ impl MessagesPagesRepo {
     pub async fn delete_topic(&self, topic_id: &str, hard_delete_moment: DateTimeAsMicroseconds) {
         match self {
             MessagesPagesRepo::Grpc(repo) => repo.delete_topic(topic_id, hard_delete_moment).await,
+            MessagesPagesRepo::Disk(repo) => repo.delete_topic(topic_id, hard_delete_moment).await,
             #[cfg(test)]
             MessagesPagesRepo::Mock(_) => {
                 println!("Delete topic {} is invoked", topic_id);
@@ -98,6 +142,7 @@ impl MessagesPagesRepo {
     pub async fn restore_topic(&self, topic_id: &str) -> Option<MessageId> {
         match self {
             MessagesPagesRepo::Grpc(repo) => repo.restore_topic(topic_id).await,
+            MessagesPagesRepo::Disk(repo) => repo.restore_topic(topic_id).await,
             #[cfg(test)]
             MessagesPagesRepo::Mock(_) => {
                 panic!("Restore topic topic {} is invoked", topic_id);
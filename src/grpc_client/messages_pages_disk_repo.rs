@@ -0,0 +1,611 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use my_service_bus::shared::sub_page::SubPageId;
+use my_service_bus::shared::zip::ZipArchive;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::messages_page::MySbMessageContent;
+
+use super::protobuf_models::MessagesProtobufModel;
+use super::PersistenceError;
+
+const SEGMENT_FILE_EXTENSION: &str = "seg";
+const DELETED_MARKER_FILE_NAME: &str = ".deleted";
+
+const RECORD_UNCOMPRESSED: u8 = 0;
+const RECORD_COMPRESSED: u8 = 1;
+
+const RECORD_HEADER_SIZE: usize = 5;
+
+// Each sub page is stored as an append-only segment file: {root}/{topic_id}/{sub_page_id}.seg
+// Segment is a sequence of records: [compressed flag: u8][payload len: u32 LE][MessagesProtobufModel]
+// If the same message is saved twice - the latest record wins.
+// Topic directory name is topic_id with every char except [A-Za-z0-9_-] percent encoded.
+pub struct MessagesPagesDiskRepo {
+    root_path: PathBuf,
+    // Segments which are checked for torn records since start and are safe to append to
+    checked_segments: Mutex<HashSet<PathBuf>>,
+}
+
+impl MessagesPagesDiskRepo {
+    pub async fn new(root_path: impl Into<PathBuf>) -> Self {
+        let root_path = root_path.into();
+
+        if let Err(err) = tokio::fs::create_dir_all(&root_path).await {
+            panic!(
+                "Can not create messages storage directory {:?}. Err: {:?}",
+                root_path, err
+            );
+        }
+
+        Self {
+            root_path,
+            checked_segments: Mutex::new(HashSet::new()),
+        }
+    }
+
+    fn get_topic_path(&self, topic_id: &str) -> PathBuf {
+        self.root_path.join(encode_topic_id(topic_id))
+    }
+
+    fn get_segment_path(&self, topic_id: &str, sub_page_id: SubPageId) -> PathBuf {
+        self.get_topic_path(topic_id).join(format!(
+            "{}.{}",
+            sub_page_id.get_value(),
+            SEGMENT_FILE_EXTENSION
+        ))
+    }
+
+    pub async fn save_messages(
+        &self,
+        topic_id: &str,
+        messages: Vec<MessageProtobufModel>,
+        compress: bool,
+    ) -> Result<(), PersistenceError> {
+        let mut by_sub_page: BTreeMap<i64, Vec<MessageProtobufModel>> = BTreeMap::new();
+
+        for message in messages {
+            let sub_page_id: SubPageId = message.get_message_id().into();
+
+            by_sub_page
+                .entry(sub_page_id.get_value())
+                .or_default()
+                .push(message);
+        }
+
+        let mut checked_segments = self.checked_segments.lock().await;
+
+        let topic_path = self.get_topic_path(topic_id);
+
+        // Topic was soft deleted and created again. Old data must not be mixed with the new one
+        if tokio::fs::try_exists(topic_path.join(DELETED_MARKER_FILE_NAME)).await? {
+            self.move_deleted_topic_aside(topic_id, &mut checked_segments)
+                .await?;
+        }
+
+        if !tokio::fs::try_exists(&topic_path).await? {
+            tokio::fs::create_dir_all(&topic_path).await?;
+            sync_dir(self.root_path.as_path()).await?;
+        }
+
+        for (sub_page_id, messages) in by_sub_page {
+            let record = compile_record(MessagesProtobufModel { messages }, compress)?;
+
+            let segment_path = self.get_segment_path(topic_id, SubPageId::new(sub_page_id));
+
+            let segment_is_new =
+                prepare_segment_to_append(&mut checked_segments, segment_path.as_path()).await?;
+
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(segment_path.as_path())
+                .await?;
+
+            file.write_all(record.as_slice()).await?;
+            file.sync_data().await?;
+
+            if segment_is_new {
+                sync_dir(topic_path.as_path()).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn move_deleted_topic_aside(
+        &self,
+        topic_id: &str,
+        checked_segments: &mut HashSet<PathBuf>,
+    ) -> Result<(), PersistenceError> {
+        let topic_path = self.get_topic_path(topic_id);
+
+        // '~' is always encoded in topic directory names, so the name never clashes with a topic.
+        // Directory keeps .deleted marker, so it is removed by gc at hard delete moment
+        let aside_path = self.root_path.join(format!(
+            "{}~{}",
+            encode_topic_id(topic_id),
+            DateTimeAsMicroseconds::now().unix_microseconds
+        ));
+
+        tokio::fs::rename(&topic_path, &aside_path).await?;
+        sync_dir(self.root_path.as_path()).await?;
+
+        checked_segments.retain(|itm| !itm.starts_with(&topic_path));
+
+        Ok(())
+    }
+
+    pub async fn load_page(
+        &self,
+        topic_id: &str,
+        from_message_id: MessageId,
+        to_message_id: MessageId,
+    ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError> {
+        let from_sub_page_id: SubPageId = from_message_id.into();
+        let to_sub_page_id: SubPageId = to_message_id.into();
+
+        let mut result = BTreeMap::new();
+
+        for sub_page_id in from_sub_page_id.get_value()..=to_sub_page_id.get_value() {
+            let messages = self
+                .read_segment(topic_id, SubPageId::new(sub_page_id))
+                .await?;
+
+            for message in messages {
+                let message_id = message.get_message_id().get_value();
+
+                if !(from_message_id.get_value()..=to_message_id.get_value()).contains(&message_id)
+                {
+                    continue;
+                }
+
+                result.insert(message_id, message.into());
+            }
+        }
+
+        Ok(Some(result))
+    }
+
+    pub async fn get_message(
+        &self,
+        topic_id: &str,
+        message_id: MessageId,
+    ) -> Result<Option<MySbMessageContent>, PersistenceError> {
+        let mut result = self.load_page(topic_id, message_id, message_id).await?;
+
+        Ok(result
+            .as_mut()
+            .and_then(|messages| messages.remove(&message_id.get_value())))
+    }
+
+    pub async fn delete_topic(&self, topic_id: &str, hard_delete_moment: DateTimeAsMicroseconds) {
+        let mut checked_segments = self.checked_segments.lock().await;
+
+        self.gc_deleted_topics().await;
+
+        let topic_path = self.get_topic_path(topic_id);
+
+        checked_segments.retain(|itm| !itm.starts_with(&topic_path));
+
+        if hard_delete_moment.unix_microseconds <= DateTimeAsMicroseconds::now().unix_microseconds {
+            let _ = tokio::fs::remove_dir_all(topic_path).await;
+            return;
+        }
+
+        if let Err(err) = tokio::fs::create_dir_all(&topic_path).await {
+            println!("Can not mark topic {} as deleted. Err: {:?}", topic_id, err);
+            return;
+        }
+
+        if let Err(err) = tokio::fs::write(
+            topic_path.join(DELETED_MARKER_FILE_NAME),
+            hard_delete_moment.unix_microseconds.to_string(),
+        )
+        .await
+        {
+            println!("Can not mark topic {} as deleted. Err: {:?}", topic_id, err);
+        }
+    }
+
+    pub async fn restore_topic(&self, topic_id: &str) -> Option<MessageId> {
+        let _write_access = self.checked_segments.lock().await;
+
+        self.gc_deleted_topics().await;
+
+        let topic_path = self.get_topic_path(topic_id);
+
+        tokio::fs::remove_file(topic_path.join(DELETED_MARKER_FILE_NAME))
+            .await
+            .ok()?;
+
+        let last_sub_page_id = self.get_last_sub_page_id(topic_id).await;
+
+        let next_message_id = match last_sub_page_id {
+            Some(sub_page_id) => match self.read_segment(topic_id, sub_page_id).await {
+                Ok(messages) => messages
+                    .iter()
+                    .map(|itm| itm.get_message_id().get_value() + 1)
+                    .max()
+                    .unwrap_or(0),
+                Err(err) => {
+                    println!(
+                        "Can not read last segment of topic {} during restore. Err: {:?}",
+                        topic_id, err
+                    );
+                    return None;
+                }
+            },
+            None => 0,
+        };
+
+        Some(MessageId::new(next_message_id))
+    }
+
+    async fn read_segment(
+        &self,
+        topic_id: &str,
+        sub_page_id: SubPageId,
+    ) -> Result<Vec<MessageProtobufModel>, PersistenceError> {
+        let content = match tokio::fs::read(self.get_segment_path(topic_id, sub_page_id)).await {
+            Ok(content) => content,
+            Err(err) => {
+                if err.kind() == std::io::ErrorKind::NotFound {
+                    return Ok(vec![]);
+                }
+
+                return Err(err.into());
+            }
+        };
+
+        let (messages, _) = parse_segment(content.as_slice())?;
+
+        Ok(messages)
+    }
+
+    async fn get_last_sub_page_id(&self, topic_id: &str) -> Option<SubPageId> {
+        let mut read_dir = tokio::fs::read_dir(self.get_topic_path(topic_id))
+            .await
+            .ok()?;
+
+        let mut result = None;
+
+        while let Ok(Some(entry)) = read_dir.next_entry().await {
+            let path = entry.path();
+
+            if path.extension().and_then(|itm| itm.to_str()) != Some(SEGMENT_FILE_EXTENSION) {
+                continue;
+            }
+
+            let sub_page_id = path
+                .file_stem()
+                .and_then(|itm| itm.to_str())
+                .and_then(|itm| itm.parse::<i64>().ok());
+
+            if let Some(sub_page_id) = sub_page_id {
+                if result.unwrap_or(-1) < sub_page_id {
+                    result = Some(sub_page_id);
+                }
+            }
+        }
+
+        result.map(SubPageId::new)
+    }
+
+    async fn gc_deleted_topics(&self) {
+        let mut read_dir = match tokio::fs::read_dir(&self.root_path).await {
+            Ok(read_dir) => read_dir,
+            Err(_) => return,
+        };
+
+        let now = DateTimeAsMicroseconds::now();
+
+        while let Ok(Some(entry)) = read_dir.next_entry().await {
+            let topic_path = entry.path();
+
+            let hard_delete_moment =
+                match tokio::fs::read_to_string(topic_path.join(DELETED_MARKER_FILE_NAME)).await {
+                    Ok(content) => content.trim().parse::<i64>().unwrap_or(0),
+                    Err(_) => continue,
+                };
+
+            if hard_delete_moment <= now.unix_microseconds {
+                let _ = tokio::fs::remove_dir_all(topic_path).await;
+            }
+        }
+    }
+}
+
+fn compile_record(
+    messages: MessagesProtobufModel,
+    compress: bool,
+) -> Result<Vec<u8>, PersistenceError> {
+    let mut payload: Vec<u8> = Vec::new();
+    prost::Message::encode(&messages, &mut payload).unwrap();
+
+    let (flag, payload) = if compress {
+        (
+            RECORD_COMPRESSED,
+            my_service_bus::shared::page_compressor::zip::compress_payload(payload.as_slice())?,
+        )
+    } else {
+        (RECORD_UNCOMPRESSED, payload)
+    };
+
+    let mut result = Vec::with_capacity(payload.len() + RECORD_HEADER_SIZE);
+    result.push(flag);
+    result.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    result.extend_from_slice(payload.as_slice());
+
+    Ok(result)
+}
+
+// Returns messages and the size of the segment part which contains complete records
+fn parse_segment(content: &[u8]) -> Result<(Vec<MessageProtobufModel>, usize), PersistenceError> {
+    let mut result = Vec::new();
+    let mut pos = 0;
+    let mut valid_len = 0;
+
+    while pos + RECORD_HEADER_SIZE <= content.len() {
+        let flag = content[pos];
+        let len = u32::from_le_bytes([
+            content[pos + 1],
+            content[pos + 2],
+            content[pos + 3],
+            content[pos + 4],
+        ]) as usize;
+
+        pos += RECORD_HEADER_SIZE;
+
+        if pos + len > content.len() {
+            // Record was not written completely. Everything before it is still valid
+            break;
+        }
+
+        let payload = &content[pos..pos + len];
+        pos += len;
+
+        let messages: MessagesProtobufModel = if flag == RECORD_COMPRESSED {
+            let payload = decompress_payload(payload)?;
+            prost::Message::decode(payload.as_slice())?
+        } else {
+            prost::Message::decode(payload)?
+        };
+
+        result.extend(messages.messages);
+        valid_len = pos;
+    }
+
+    Ok((result, valid_len))
+}
+
+// Returns true if segment does not exist yet.
+// Segment with torn record at the end is truncated, so new records are not appended after it
+async fn prepare_segment_to_append(
+    checked_segments: &mut HashSet<PathBuf>,
+    segment_path: &Path,
+) -> Result<bool, PersistenceError> {
+    if checked_segments.contains(segment_path) {
+        return Ok(false);
+    }
+
+    let content = match tokio::fs::read(segment_path).await {
+        Ok(content) => content,
+        Err(err) => {
+            if err.kind() == std::io::ErrorKind::NotFound {
+                checked_segments.insert(segment_path.to_path_buf());
+                return Ok(true);
+            }
+
+            return Err(err.into());
+        }
+    };
+
+    let (_, valid_len) = parse_segment(content.as_slice())?;
+
+    if valid_len < content.len() {
+        println!(
+            "Segment {:?} has torn record at the end. Truncating it from {} to {} bytes",
+            segment_path,
+            content.len(),
+            valid_len
+        );
+
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(segment_path)
+            .await?;
+
+        file.set_len(valid_len as u64).await?;
+        file.sync_data().await?;
+    }
+
+    checked_segments.insert(segment_path.to_path_buf());
+
+    Ok(false)
+}
+
+async fn sync_dir(path: &Path) -> Result<(), PersistenceError> {
+    tokio::fs::File::open(path).await?.sync_all().await?;
+    Ok(())
+}
+
+fn encode_topic_id(topic_id: &str) -> String {
+    let mut result = String::with_capacity(topic_id.len());
+
+    for b in topic_id.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'-' || b == b'_' {
+            result.push(b as char);
+        } else {
+            result.push_str(format!("%{:02X}", b).as_str());
+        }
+    }
+
+    result
+}
+
+fn decompress_payload(payload: &[u8]) -> Result<Vec<u8>, PersistenceError> {
+    let mut archive = ZipArchive::new(Cursor::new(payload))?;
+
+    let mut result = Vec::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        file.read_to_end(&mut result)?;
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::SbMessageHeaders;
+
+    use super::*;
+
+    fn create_message(message_id: i64, content: &[u8]) -> MessageProtobufModel {
+        let message = MySbMessageContent::new(
+            message_id.into(),
+            content.to_vec(),
+            SbMessageHeaders::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+
+        (&message).into()
+    }
+
+    #[tokio::test]
+    async fn test_save_load_and_restore() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let repo = MessagesPagesDiskRepo::new(root_path.clone()).await;
+
+        repo.save_messages(
+            "test-topic",
+            vec![create_message(1, &[1]), create_message(2, &[2])],
+            false,
+        )
+        .await
+        .unwrap();
+
+        repo.save_messages("test-topic", vec![create_message(2, &[3])], false)
+            .await
+            .unwrap();
+
+        let messages = repo
+            .load_page("test-topic", 0.into(), 999.into())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(2, messages.len());
+        assert_eq!(vec![1u8], messages.get(&1).unwrap().content);
+        assert_eq!(vec![3u8], messages.get(&2).unwrap().content);
+
+        repo.delete_topic(
+            "test-topic",
+            DateTimeAsMicroseconds::new(
+                DateTimeAsMicroseconds::now().unix_microseconds + 60_000_000,
+            ),
+        )
+        .await;
+
+        let message_id = repo.restore_topic("test-topic").await.unwrap();
+        assert_eq!(3, message_id.get_value());
+
+        assert!(repo.restore_topic("test-topic").await.is_none());
+
+        let _ = tokio::fs::remove_dir_all(root_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_torn_record_is_truncated_before_append() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let repo = MessagesPagesDiskRepo::new(root_path.clone()).await;
+
+        repo.save_messages("test-topic", vec![create_message(1, &[1])], false)
+            .await
+            .unwrap();
+
+        let segment_path = repo.get_segment_path("test-topic", SubPageId::new(0));
+        let mut content = tokio::fs::read(&segment_path).await.unwrap();
+        let valid_len = content.len();
+        content.extend_from_slice(&[RECORD_UNCOMPRESSED, 100, 0, 0, 0, 1, 2]);
+        tokio::fs::write(&segment_path, content).await.unwrap();
+
+        // New instance simulates restart after a crash in the middle of append
+        let repo = MessagesPagesDiskRepo::new(root_path.clone()).await;
+
+        repo.save_messages("test-topic", vec![create_message(2, &[2])], false)
+            .await
+            .unwrap();
+
+        let content = tokio::fs::read(&segment_path).await.unwrap();
+        let (messages, parsed_len) = parse_segment(content.as_slice()).unwrap();
+
+        assert_eq!(content.len(), parsed_len);
+        assert!(parsed_len > valid_len);
+        assert_eq!(2, messages.len());
+
+        let _ = tokio::fs::remove_dir_all(root_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_recreated_topic_does_not_get_deleted_data() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let repo = MessagesPagesDiskRepo::new(root_path.clone()).await;
+
+        repo.save_messages("test-topic", vec![create_message(1, &[1])], false)
+            .await
+            .unwrap();
+
+        repo.delete_topic(
+            "test-topic",
+            DateTimeAsMicroseconds::new(
+                DateTimeAsMicroseconds::now().unix_microseconds + 60_000_000,
+            ),
+        )
+        .await;
+
+        // Topic is created again by publish
+        repo.save_messages("test-topic", vec![create_message(0, &[2])], false)
+            .await
+            .unwrap();
+
+        let messages = repo
+            .load_page("test-topic", 0.into(), 999.into())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(1, messages.len());
+        assert_eq!(vec![2u8], messages.get(&0).unwrap().content);
+
+        // Gc runs on delete and must keep data of the recreated topic
+        repo.delete_topic("other-topic", DateTimeAsMicroseconds::now())
+            .await;
+
+        assert!(repo.restore_topic("test-topic").await.is_none());
+
+        let messages = repo
+            .load_page("test-topic", 0.into(), 999.into())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(1, messages.len());
+
+        let _ = tokio::fs::remove_dir_all(root_path).await;
+    }
+
+    #[test]
+    fn test_topic_id_is_encoded() {
+        assert_eq!("test-topic_1", encode_topic_id("test-topic_1"));
+        assert_eq!("%2E%2E%2Fetc", encode_topic_id("../etc"));
+        assert_eq!("a%5Cb%7E1", encode_topic_id("a\\b~1"));
+    }
+}
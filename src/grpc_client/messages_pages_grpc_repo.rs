@@ -2,6 +2,7 @@ use std::collections::BTreeMap;
 use std::time::Duration;
 
 use futures_util::stream;
+use my_logger::LogEventCtx;
 
 use my_service_bus::abstractions::MessageId;
 use my_service_bus::abstractions::SbMessageHeaders;
@@ -26,11 +27,8 @@ pub struct MessagesPagesGrpcRepo {
 
 impl MessagesPagesGrpcRepo {
     pub async fn new(grpc_address: String) -> Self {
-        let channel = Channel::from_shared(grpc_address)
-            .unwrap()
-            .connect()
-            .await
-            .unwrap();
+        // Channel is connected lazily and reconnects by itself, so persistence being down does not stop the node
+        let channel = Channel::from_shared(grpc_address).unwrap().connect_lazy();
         Self {
             time_out: Duration::from_secs(5),
             channel,
@@ -171,16 +169,61 @@ impl MessagesPagesGrpcRepo {
         Ok(Some(messages))
     }
 
+    pub async fn get_message(
+        &self,
+        topic_id: &str,
+        message_id: MessageId,
+    ) -> Result<Option<MySbMessageContent>, PersistenceError> {
+        let mut grpc_client = self.create_grpc_service();
+
+        let result = tokio::time::timeout(
+            self.time_out,
+            grpc_client.get_message(GetMessageGrpcRequest {
+                topic_id: topic_id.to_string(),
+                message_id: message_id.into(),
+            }),
+        )
+        .await?;
+
+        let grpc_model = match result {
+            Ok(result) => result.into_inner(),
+            Err(status) => {
+                if status.code() == tonic::Code::NotFound {
+                    return Ok(None);
+                }
+
+                return Err(PersistenceError::TonicError(status));
+            }
+        };
+
+        Ok(Some(MySbMessageContent {
+            id: grpc_model.message_id.into(),
+            content: grpc_model.data,
+            time: DateTimeAsMicroseconds::new(grpc_model.created),
+            headers: SbMessageHeaders::from_iterator(
+                grpc_model.meta_data.len().into(),
+                grpc_model.meta_data.into_iter().map(|x| (x.key, x.value)),
+            ),
+        }))
+    }
+
     pub async fn delete_topic(&self, topic_id: &str, hard_delete_moment: DateTimeAsMicroseconds) {
         let mut grpc_client = self.create_grpc_service();
 
-        grpc_client
+        let result = grpc_client
             .delete_topic(DeleteTopicGrpcRequest {
                 topic_id: topic_id.to_string(),
                 delete_after: hard_delete_moment.unix_microseconds,
             })
-            .await
-            .unwrap();
+            .await;
+
+        if let Err(err) = result {
+            my_logger::LOGGER.write_error(
+                "delete_topic",
+                format!("Can not delete topic in persistence. Err: {:?}", err),
+                LogEventCtx::new().add("topicId", topic_id),
+            );
+        }
     }
 
     pub async fn restore_topic(&self, topic_id: &str) -> Option<MessageId> {
@@ -190,10 +233,19 @@ impl MessagesPagesGrpcRepo {
             .restore_topic(RestoreTopicRequest {
                 topic_id: topic_id.to_string(),
             })
-            .await
-            .unwrap();
-
-        let result = result.into_inner();
+            .await;
+
+        let result = match result {
+            Ok(result) => result.into_inner(),
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "restore_topic",
+                    format!("Can not restore topic in persistence. Err: {:?}", err),
+                    LogEventCtx::new().add("topicId", topic_id),
+                );
+                return None;
+            }
+        };
         if !result.result {
             return None;
         }
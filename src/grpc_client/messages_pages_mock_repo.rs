@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use my_service_bus::abstractions::MessageId;
 use my_service_bus::shared::protobuf_models::MessageProtobufModel;
@@ -10,21 +11,53 @@ use super::PersistenceError;
 
 pub struct MessagesPagesMockRepo {
     messages: Mutex<BTreeMap<String, BTreeMap<i64, MySbMessageContent>>>,
+    fail_saves: AtomicBool,
+    loads_to_fail: AtomicUsize,
 }
 
 impl MessagesPagesMockRepo {
     pub fn new() -> Self {
         Self {
             messages: Mutex::new(BTreeMap::new()),
+            fail_saves: AtomicBool::new(false),
+            loads_to_fail: AtomicUsize::new(0),
         }
     }
 
+    pub fn set_fail_saves(&self, value: bool) {
+        self.fail_saves.store(value, Ordering::SeqCst);
+    }
+
+    pub fn fail_next_loads(&self, amount: usize) {
+        self.loads_to_fail.store(amount, Ordering::SeqCst);
+    }
+
+    pub async fn get_messages_amount(&self, topic_id: &str) -> usize {
+        let read_access = self.messages.lock().await;
+        read_access.get(topic_id).map(|itm| itm.len()).unwrap_or(0)
+    }
+
+    fn create_error() -> PersistenceError {
+        PersistenceError::IoError(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "Mock persistence is not available",
+        ))
+    }
+
     pub async fn load_page(
         &self,
         topic_id: &str,
         from_message_id: MessageId,
         to_message_id: MessageId,
     ) -> Result<Option<BTreeMap<i64, MySbMessageContent>>, PersistenceError> {
+        let loads_to_fail = self.loads_to_fail.load(Ordering::SeqCst);
+
+        if loads_to_fail > 0 {
+            self.loads_to_fail
+                .store(loads_to_fail - 1, Ordering::SeqCst);
+            return Err(Self::create_error());
+        }
+
         let mut result = BTreeMap::new();
 
         let mut write_access = self.messages.lock().await;
@@ -53,6 +86,10 @@ impl MessagesPagesMockRepo {
         topic_id: &str,
         messages: Vec<MessageProtobufModel>,
     ) -> Result<(), PersistenceError> {
+        if self.fail_saves.load(Ordering::SeqCst) {
+            return Err(Self::create_error());
+        }
+
         let mut write_access = self.messages.lock().await;
         if !write_access.contains_key(topic_id) {
             write_access.insert(topic_id.to_string(), BTreeMap::new());
@@ -0,0 +1,668 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, Mutex};
+
+use super::protobuf_models::NewMessagesProtobufContract;
+use super::PersistenceError;
+
+const SEGMENT_FILE_EXTENSION: &str = "wal";
+const MAX_SEGMENT_SIZE: usize = 1024 * 1024 * 16;
+const MAX_RECORDS_PER_BATCH: usize = 1024;
+
+const RECORD_HEADER_SIZE: usize = 4;
+
+struct WalAppendRequest {
+    topic_id: String,
+    max_message_id: i64,
+    record: Vec<u8>,
+    reply: oneshot::Sender<Result<(), String>>,
+}
+
+struct WalCurrentSegment {
+    segment_no: u64,
+    file: tokio::fs::File,
+    size: usize,
+}
+
+// Max message id of each topic which is written to the segment
+type SegmentMaxIds = HashMap<String, i64>;
+
+struct WalSegments {
+    current: Option<WalCurrentSegment>,
+    segments: BTreeMap<u64, SegmentMaxIds>,
+    persisted_below: HashMap<String, i64>,
+    // Topics with messages from the previous run which are not replayed to persistence yet
+    replay_pending: HashSet<String>,
+    next_segment_no: u64,
+}
+
+impl WalSegments {
+    fn is_segment_persisted(&self, max_ids: &SegmentMaxIds) -> bool {
+        max_ids.iter().all(
+            |(topic_id, max_id)| match self.persisted_below.get(topic_id) {
+                Some(persisted_below) => max_id < persisted_below,
+                None => false,
+            },
+        )
+    }
+}
+
+// Write-ahead spool of published messages which are not persisted yet: {root}/{segment_no}.wal
+// Segment is a sequence of records: [payload len: u32 LE][NewMessagesProtobufContract]
+// Appends from all the publishers are written and fsynced in batches.
+// Segment is removed as soon as all the messages it contains are confirmed as persisted.
+pub struct MessagesWal {
+    root_path: PathBuf,
+    sender: UnboundedSender<WalAppendRequest>,
+    segments: Arc<Mutex<WalSegments>>,
+}
+
+impl MessagesWal {
+    pub async fn new(root_path: impl Into<PathBuf>) -> Self {
+        let root_path = root_path.into();
+
+        if let Err(err) = tokio::fs::create_dir_all(&root_path).await {
+            panic!(
+                "Can not create write-ahead spool directory {:?}. Err: {:?}",
+                root_path, err
+            );
+        }
+
+        let mut segments = BTreeMap::new();
+
+        for segment_no in get_segment_numbers(&root_path).await {
+            let max_ids = match read_segment_and_truncate_torn_tail(&root_path, segment_no).await {
+                Ok(records) => get_max_ids(&records),
+                Err(err) => panic!(
+                    "Can not read write-ahead spool segment {}. Err: {:?}",
+                    segment_no, err
+                ),
+            };
+
+            segments.insert(segment_no, max_ids);
+        }
+
+        let next_segment_no = match segments.keys().last() {
+            Some(segment_no) => segment_no + 1,
+            None => 0,
+        };
+
+        let segments = Arc::new(Mutex::new(WalSegments {
+            current: None,
+            segments,
+            persisted_below: HashMap::new(),
+            replay_pending: HashSet::new(),
+            next_segment_no,
+        }));
+
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+
+        tokio::spawn(write_loop(root_path.clone(), segments.clone(), receiver));
+
+        Self {
+            root_path,
+            sender,
+            segments,
+        }
+    }
+
+    // Returns as soon as messages are fsynced to disk
+    pub async fn append(
+        &self,
+        topic_id: &str,
+        messages: Vec<MessageProtobufModel>,
+    ) -> Result<(), String> {
+        let max_message_id = match messages
+            .iter()
+            .map(|itm| itm.get_message_id().get_value())
+            .max()
+        {
+            Some(max_message_id) => max_message_id,
+            None => return Ok(()),
+        };
+
+        let contract = NewMessagesProtobufContract {
+            topic_id: topic_id.to_string(),
+            messages,
+        };
+
+        let (reply, result) = oneshot::channel();
+
+        let request = WalAppendRequest {
+            topic_id: topic_id.to_string(),
+            max_message_id,
+            record: compile_record(&contract),
+            reply,
+        };
+
+        if self.sender.send(request).is_err() {
+            return Err("Write-ahead spool writer is stopped".to_string());
+        }
+
+        match result.await {
+            Ok(result) => result,
+            Err(_) => Err("Write-ahead spool writer is stopped".to_string()),
+        }
+    }
+
+    // Segments with messages of the topic are kept until confirm_replayed is called,
+    // even if newer messages of the topic are confirmed as persisted
+    pub async fn set_replay_pending(&self, topic_id: &str) {
+        let mut write_access = self.segments.lock().await;
+        write_access.replay_pending.insert(topic_id.to_string());
+    }
+
+    pub async fn confirm_replayed(&self, topic_id: &str, persisted_below: i64) {
+        {
+            let mut write_access = self.segments.lock().await;
+            write_access.replay_pending.remove(topic_id);
+        }
+
+        self.confirm_persisted(topic_id, persisted_below).await;
+    }
+
+    // All the messages of the topic with id below the value are persisted
+    pub async fn confirm_persisted(&self, topic_id: &str, persisted_below: i64) {
+        let mut write_access = self.segments.lock().await;
+
+        if write_access.replay_pending.contains(topic_id) {
+            return;
+        }
+
+        match write_access.persisted_below.get_mut(topic_id) {
+            Some(value) => {
+                if *value < persisted_below {
+                    *value = persisted_below;
+                }
+            }
+            None => {
+                write_access
+                    .persisted_below
+                    .insert(topic_id.to_string(), persisted_below);
+            }
+        }
+
+        let current_segment_no = write_access.current.as_ref().map(|itm| itm.segment_no);
+
+        let segments_to_remove: Vec<u64> = write_access
+            .segments
+            .iter()
+            .filter(|(_, max_ids)| write_access.is_segment_persisted(max_ids))
+            .map(|(segment_no, _)| *segment_no)
+            .collect();
+
+        for segment_no in segments_to_remove {
+            if Some(segment_no) == current_segment_no {
+                // Next append is going to start a new segment
+                write_access.current = None;
+            }
+
+            write_access.segments.remove(&segment_no);
+
+            if let Err(err) =
+                tokio::fs::remove_file(get_segment_path(&self.root_path, segment_no)).await
+            {
+                println!(
+                    "Can not remove write-ahead spool segment {}. Err: {:?}",
+                    segment_no, err
+                );
+            }
+        }
+    }
+
+    // Messages which are not confirmed as persisted. Used to replay them at startup
+    pub async fn read_not_persisted(
+        &self,
+    ) -> Result<BTreeMap<String, Vec<MessageProtobufModel>>, PersistenceError> {
+        let segment_numbers: Vec<u64> = {
+            let read_access = self.segments.lock().await;
+            read_access.segments.keys().copied().collect()
+        };
+
+        let mut by_topic: BTreeMap<String, BTreeMap<i64, MessageProtobufModel>> = BTreeMap::new();
+
+        for segment_no in segment_numbers {
+            for record in read_segment(&self.root_path, segment_no).await? {
+                let messages = by_topic.entry(record.topic_id).or_default();
+
+                for message in record.messages {
+                    messages.insert(message.get_message_id().get_value(), message);
+                }
+            }
+        }
+
+        Ok(by_topic
+            .into_iter()
+            .map(|(topic_id, messages)| (topic_id, messages.into_values().collect()))
+            .collect())
+    }
+
+    pub async fn get_segments_amount(&self) -> usize {
+        let read_access = self.segments.lock().await;
+        read_access.segments.len()
+    }
+}
+
+async fn write_loop(
+    root_path: PathBuf,
+    segments: Arc<Mutex<WalSegments>>,
+    mut receiver: UnboundedReceiver<WalAppendRequest>,
+) {
+    while let Some(request) = receiver.recv().await {
+        let mut batch = vec![request];
+
+        while batch.len() < MAX_RECORDS_PER_BATCH {
+            match receiver.try_recv() {
+                Ok(request) => batch.push(request),
+                Err(_) => break,
+            }
+        }
+
+        let mut write_access = segments.lock().await;
+
+        let size_before_batch = write_access.current.as_ref().map(|itm| itm.size);
+
+        let result = write_batch(&root_path, &mut write_access, &batch)
+            .await
+            .map_err(|err| format!("Can not write to write-ahead spool. Err: {:?}", err));
+
+        if result.is_err() {
+            // Publish is rejected and its message ids are reused, so records of the batch
+            // must not be replayed on restart
+            truncate_failed_batch(
+                &root_path,
+                &mut write_access,
+                size_before_batch.unwrap_or(0),
+            )
+            .await;
+        }
+
+        drop(write_access);
+
+        for request in batch {
+            let _ = request.reply.send(result.clone());
+        }
+    }
+}
+
+async fn write_batch(
+    root_path: &Path,
+    segments: &mut WalSegments,
+    batch: &[WalAppendRequest],
+) -> Result<(), std::io::Error> {
+    if segments.current.is_none() {
+        let segment_no = segments.next_segment_no;
+        segments.next_segment_no += 1;
+
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(get_segment_path(root_path, segment_no))
+            .await?;
+
+        segments.segments.insert(segment_no, HashMap::new());
+
+        segments.current = Some(WalCurrentSegment {
+            segment_no,
+            file,
+            size: 0,
+        });
+    }
+
+    let current = segments.current.as_mut().unwrap();
+    let segment_no = current.segment_no;
+
+    for request in batch {
+        current.file.write_all(request.record.as_slice()).await?;
+        current.size += request.record.len();
+    }
+
+    current.file.sync_data().await?;
+
+    let is_full = current.size >= MAX_SEGMENT_SIZE;
+
+    if let Some(max_ids) = segments.segments.get_mut(&segment_no) {
+        for request in batch {
+            let max_id = max_ids.entry(request.topic_id.clone()).or_insert(-1);
+
+            if *max_id < request.max_message_id {
+                *max_id = request.max_message_id;
+            }
+        }
+    }
+
+    if is_full {
+        segments.current = None;
+    }
+
+    Ok(())
+}
+
+// Continues with the new segment after truncation, since the file may be in the unknown state
+async fn truncate_failed_batch(
+    root_path: &Path,
+    segments: &mut WalSegments,
+    size_before_batch: usize,
+) {
+    let current = match segments.current.take() {
+        Some(current) => current,
+        None => return,
+    };
+
+    let result = match current.file.set_len(size_before_batch as u64).await {
+        Ok(_) => current.file.sync_all().await,
+        Err(err) => Err(err),
+    };
+
+    if let Err(err) = result {
+        println!(
+            "Can not truncate failed batch of write-ahead spool segment {:?}. Err: {:?}",
+            get_segment_path(root_path, current.segment_no),
+            err
+        );
+    }
+}
+
+fn get_segment_path(root_path: &Path, segment_no: u64) -> PathBuf {
+    root_path.join(format!("{:020}.{}", segment_no, SEGMENT_FILE_EXTENSION))
+}
+
+async fn get_segment_numbers(root_path: &Path) -> Vec<u64> {
+    let mut result = Vec::new();
+
+    let mut read_dir = match tokio::fs::read_dir(root_path).await {
+        Ok(read_dir) => read_dir,
+        Err(_) => return result,
+    };
+
+    while let Ok(Some(entry)) = read_dir.next_entry().await {
+        let path = entry.path();
+
+        if path.extension().and_then(|itm| itm.to_str()) != Some(SEGMENT_FILE_EXTENSION) {
+            continue;
+        }
+
+        let segment_no = path
+            .file_stem()
+            .and_then(|itm| itm.to_str())
+            .and_then(|itm| itm.parse::<u64>().ok());
+
+        if let Some(segment_no) = segment_no {
+            result.push(segment_no);
+        }
+    }
+
+    result.sort();
+    result
+}
+
+fn get_max_ids(records: &[NewMessagesProtobufContract]) -> SegmentMaxIds {
+    let mut result = HashMap::new();
+
+    for record in records {
+        for message in &record.messages {
+            let max_id = result.entry(record.topic_id.clone()).or_insert(-1);
+            let message_id = message.get_message_id().get_value();
+
+            if *max_id < message_id {
+                *max_id = message_id;
+            }
+        }
+    }
+
+    result
+}
+
+fn compile_record(contract: &NewMessagesProtobufContract) -> Vec<u8> {
+    let payload = contract.into_protobuf_vec();
+
+    let mut result = Vec::with_capacity(payload.len() + RECORD_HEADER_SIZE);
+    result.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    result.extend_from_slice(payload.as_slice());
+    result
+}
+
+async fn read_segment_content(
+    root_path: &Path,
+    segment_no: u64,
+) -> Result<Vec<u8>, PersistenceError> {
+    match tokio::fs::read(get_segment_path(root_path, segment_no)).await {
+        Ok(content) => Ok(content),
+        Err(err) => {
+            if err.kind() == std::io::ErrorKind::NotFound {
+                return Ok(vec![]);
+            }
+
+            Err(err.into())
+        }
+    }
+}
+
+async fn read_segment(
+    root_path: &Path,
+    segment_no: u64,
+) -> Result<Vec<NewMessagesProtobufContract>, PersistenceError> {
+    let content = read_segment_content(root_path, segment_no).await?;
+    let (records, _) = parse_segment(content.as_slice());
+    Ok(records)
+}
+
+// Used at startup. Records after the torn one are not replayed and the next segment is started after the file
+async fn read_segment_and_truncate_torn_tail(
+    root_path: &Path,
+    segment_no: u64,
+) -> Result<Vec<NewMessagesProtobufContract>, PersistenceError> {
+    let content = read_segment_content(root_path, segment_no).await?;
+    let (records, valid_len) = parse_segment(content.as_slice());
+
+    if valid_len < content.len() {
+        let path = get_segment_path(root_path, segment_no);
+
+        println!(
+            "Write-ahead spool segment {:?} has torn tail of {} bytes after {} records. Truncating it",
+            path,
+            content.len() - valid_len,
+            records.len()
+        );
+
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .await?;
+        file.set_len(valid_len as u64).await?;
+        file.sync_all().await?;
+    }
+
+    Ok(records)
+}
+
+// Returns records and the length of the content they take. Record which is not complete or can not
+// be decoded was not fsynced before the crash, so publisher did not get confirmation for it
+fn parse_segment(content: &[u8]) -> (Vec<NewMessagesProtobufContract>, usize) {
+    let mut result = Vec::new();
+    let mut pos = 0;
+
+    while pos + RECORD_HEADER_SIZE <= content.len() {
+        let len = u32::from_le_bytes([
+            content[pos],
+            content[pos + 1],
+            content[pos + 2],
+            content[pos + 3],
+        ]) as usize;
+
+        let payload_pos = pos + RECORD_HEADER_SIZE;
+
+        if payload_pos + len > content.len() {
+            break;
+        }
+
+        match prost::Message::decode(&content[payload_pos..payload_pos + len]) {
+            Ok(record) => result.push(record),
+            Err(_) => break,
+        }
+
+        pos = payload_pos + len;
+    }
+
+    (result, pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::SbMessageHeaders;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::messages_page::MySbMessageContent;
+
+    use super::*;
+
+    fn create_message(message_id: i64) -> MessageProtobufModel {
+        let message = MySbMessageContent::new(
+            message_id.into(),
+            vec![message_id as u8],
+            SbMessageHeaders::new(),
+            DateTimeAsMicroseconds::now(),
+        );
+
+        (&message).into()
+    }
+
+    #[tokio::test]
+    async fn test_append_replay_and_trim() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+
+        {
+            let wal = MessagesWal::new(root_path.clone()).await;
+
+            wal.append("topic-1", vec![create_message(1), create_message(2)])
+                .await
+                .unwrap();
+
+            wal.append("topic-2", vec![create_message(5)])
+                .await
+                .unwrap();
+        }
+
+        let wal = MessagesWal::new(root_path.clone()).await;
+
+        let not_persisted = wal.read_not_persisted().await.unwrap();
+
+        assert_eq!(2, not_persisted.get("topic-1").unwrap().len());
+        assert_eq!(1, not_persisted.get("topic-2").unwrap().len());
+
+        wal.confirm_persisted("topic-1", 3).await;
+        assert_eq!(1, wal.get_segments_amount().await);
+
+        wal.confirm_persisted("topic-2", 6).await;
+        assert_eq!(0, wal.get_segments_amount().await);
+
+        assert_eq!(0, wal.read_not_persisted().await.unwrap().len());
+
+        let _ = tokio::fs::remove_dir_all(root_path).await;
+    }
+    #[tokio::test]
+    async fn test_replay_pending_topic_is_kept_until_replayed() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+
+        {
+            let wal = MessagesWal::new(root_path.clone()).await;
+
+            wal.append("topic-1", vec![create_message(1)])
+                .await
+                .unwrap();
+        }
+
+        let wal = MessagesWal::new(root_path.clone()).await;
+        wal.set_replay_pending("topic-1").await;
+
+        // Persist loop confirms messages published after restart
+        wal.confirm_persisted("topic-1", 10).await;
+        assert_eq!(1, wal.get_segments_amount().await);
+
+        wal.confirm_replayed("topic-1", 2).await;
+        assert_eq!(0, wal.get_segments_amount().await);
+
+        let _ = tokio::fs::remove_dir_all(root_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_torn_tail_is_truncated_at_startup() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+
+        {
+            let wal = MessagesWal::new(root_path.clone()).await;
+
+            wal.append("topic-1", vec![create_message(1)])
+                .await
+                .unwrap();
+        }
+
+        let segment_path = get_segment_path(&root_path, 0);
+        let valid_len = tokio::fs::metadata(&segment_path).await.unwrap().len();
+
+        {
+            let mut file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&segment_path)
+                .await
+                .unwrap();
+
+            // Record which can not be decoded
+            file.write_all(&[3, 0, 0, 0, 0xff, 0xff, 0xff])
+                .await
+                .unwrap();
+            file.sync_all().await.unwrap();
+        }
+
+        let wal = MessagesWal::new(root_path.clone()).await;
+
+        assert_eq!(
+            valid_len,
+            tokio::fs::metadata(&segment_path).await.unwrap().len()
+        );
+
+        let not_persisted = wal.read_not_persisted().await.unwrap();
+        assert_eq!(1, not_persisted.get("topic-1").unwrap().len());
+
+        let _ = tokio::fs::remove_dir_all(root_path).await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_truncated() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+
+        let wal = MessagesWal::new(root_path.clone()).await;
+
+        wal.append("topic-1", vec![create_message(1)])
+            .await
+            .unwrap();
+
+        let segment_path = get_segment_path(&root_path, 0);
+        let valid_len = tokio::fs::metadata(&segment_path).await.unwrap().len();
+
+        {
+            let mut write_access = wal.segments.lock().await;
+
+            // Records of the batch which is failed after they are written
+            let current = write_access.current.as_mut().unwrap();
+            current.file.write_all(&[1, 2, 3, 4, 5]).await.unwrap();
+            current.file.flush().await.unwrap();
+
+            truncate_failed_batch(&root_path, &mut write_access, valid_len as usize).await;
+
+            assert!(write_access.current.is_none());
+        }
+
+        assert_eq!(
+            valid_len,
+            tokio::fs::metadata(&segment_path).await.unwrap().len()
+        );
+
+        let _ = tokio::fs::remove_dir_all(root_path).await;
+    }
+}
@@ -1,17 +1,24 @@
 mod error;
 mod grpc_client;
 pub mod mappers;
+mod messages_pages_disk_repo;
 mod messages_pages_grpc_repo;
 #[cfg(test)]
 mod messages_pages_mock_repo;
+mod messages_wal;
+mod persistence_circuit_breaker;
 mod protobuf_models;
 
 pub use grpc_client::*;
 
 pub use error::*;
+pub use persistence_circuit_breaker::PersistenceCircuitBreaker;
+pub use messages_pages_disk_repo::MessagesPagesDiskRepo;
 pub use messages_pages_grpc_repo::MessagesPagesGrpcRepo;
 #[cfg(test)]
 pub use messages_pages_mock_repo::MessagesPagesMockRepo;
+pub use messages_wal::MessagesWal;
+mod topics_and_queues_snapshot_file_repo;
 mod topics_and_queues_snapshot_grpc_repo;
 #[cfg(test)]
 mod topics_and_queues_snapshot_mock_repo;
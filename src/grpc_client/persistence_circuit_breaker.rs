@@ -0,0 +1,137 @@
+use std::{
+    sync::atomic::{AtomicI64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::sync::Mutex;
+
+const FAILURES_TO_OPEN: usize = 5;
+const OPEN_TIMEOUT: Duration = Duration::from_secs(10);
+
+const CLOSED: i64 = 0;
+
+// Stops calling persistence after several failures in a row.
+// While circuit is open - only one probe call per OPEN_TIMEOUT is allowed (half-open state).
+pub struct PersistenceCircuitBreaker {
+    consecutive_failures: AtomicUsize,
+    opened_at: AtomicI64,
+    total_failures: AtomicUsize,
+    last_error: Mutex<Option<String>>,
+}
+
+impl PersistenceCircuitBreaker {
+    pub fn new() -> Self {
+        Self {
+            consecutive_failures: AtomicUsize::new(0),
+            opened_at: AtomicI64::new(CLOSED),
+            total_failures: AtomicUsize::new(0),
+            last_error: Mutex::new(None),
+        }
+    }
+
+    pub fn is_call_allowed(&self, now: DateTimeAsMicroseconds) -> bool {
+        let opened_at = self.opened_at.load(Ordering::SeqCst);
+
+        if opened_at == CLOSED {
+            return true;
+        }
+
+        if now.unix_microseconds - opened_at < OPEN_TIMEOUT.as_micros() as i64 {
+            return false;
+        }
+
+        // Half-open: let exactly one caller probe persistence and postpone others
+        self.opened_at
+            .compare_exchange(
+                opened_at,
+                now.unix_microseconds,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_ok()
+    }
+
+    // Returns true if persistence is recovered after being degraded
+    pub async fn register_success(&self) -> bool {
+        let failures = self.consecutive_failures.swap(0, Ordering::SeqCst);
+        self.opened_at.store(CLOSED, Ordering::SeqCst);
+
+        let mut write_access = self.last_error.lock().await;
+        *write_access = None;
+
+        failures > 0
+    }
+
+    // Returns true if persistence became degraded with this failure
+    pub async fn register_failure(&self, now: DateTimeAsMicroseconds, err: String) -> bool {
+        self.total_failures.fetch_add(1, Ordering::SeqCst);
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1;
+
+        if failures >= FAILURES_TO_OPEN {
+            self.opened_at
+                .store(now.unix_microseconds, Ordering::SeqCst);
+        }
+
+        let mut write_access = self.last_error.lock().await;
+        *write_access = Some(err);
+
+        failures == 1
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.opened_at.load(Ordering::SeqCst) != CLOSED
+    }
+
+    pub fn is_degraded(&self) -> bool {
+        self.consecutive_failures.load(Ordering::SeqCst) > 0
+    }
+
+    pub fn get_consecutive_failures(&self) -> usize {
+        self.consecutive_failures.load(Ordering::SeqCst)
+    }
+
+    pub fn get_total_failures(&self) -> usize {
+        self.total_failures.load(Ordering::SeqCst)
+    }
+
+    pub async fn get_last_error(&self) -> Option<String> {
+        let read_access = self.last_error.lock().await;
+        read_access.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_circuit_opens_and_half_opens() {
+        let circuit_breaker = PersistenceCircuitBreaker::new();
+
+        let now = DateTimeAsMicroseconds::now();
+
+        for _ in 0..FAILURES_TO_OPEN {
+            assert!(circuit_breaker.is_call_allowed(now));
+            circuit_breaker
+                .register_failure(now, "Timeout".to_string())
+                .await;
+        }
+
+        assert!(circuit_breaker.is_open());
+        assert!(!circuit_breaker.is_call_allowed(now));
+
+        let after_timeout =
+            DateTimeAsMicroseconds::new(now.unix_microseconds + OPEN_TIMEOUT.as_micros() as i64);
+
+        assert!(circuit_breaker.is_call_allowed(after_timeout));
+        assert!(!circuit_breaker.is_call_allowed(after_timeout));
+
+        assert!(circuit_breaker.register_success().await);
+
+        assert!(!circuit_breaker.is_open());
+        assert!(!circuit_breaker.is_degraded());
+        assert!(circuit_breaker.is_call_allowed(after_timeout));
+        assert_eq!(FAILURES_TO_OPEN, circuit_breaker.get_total_failures());
+    }
+}
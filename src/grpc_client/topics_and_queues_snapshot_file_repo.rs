@@ -0,0 +1,118 @@
+use std::path::PathBuf;
+
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::persistence_grpc::SaveQueueSnapshotGrpcRequest;
+use crate::topics::TopicSnapshot;
+
+use super::PersistenceError;
+
+pub struct TopicsAndQueuesSnapshotFileRepo {
+    file_path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl TopicsAndQueuesSnapshotFileRepo {
+    pub fn new(file_path: impl Into<PathBuf>) -> Self {
+        Self {
+            file_path: file_path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    pub fn get_file_path(&self) -> &PathBuf {
+        &self.file_path
+    }
+
+    pub async fn load(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
+        let content = match tokio::fs::read(&self.file_path).await {
+            Ok(content) => content,
+            Err(err) => {
+                if err.kind() == std::io::ErrorKind::NotFound {
+                    return Ok(vec![]);
+                }
+
+                return Err(err.into());
+            }
+        };
+
+        let snapshot: SaveQueueSnapshotGrpcRequest = prost::Message::decode(content.as_slice())?;
+
+        Ok(snapshot
+            .queue_snapshot
+            .into_iter()
+            .map(|itm| itm.into())
+            .collect())
+    }
+
+    pub async fn save(&self, snapshot: Vec<TopicSnapshot>) -> Result<(), PersistenceError> {
+        let snapshot: SaveQueueSnapshotGrpcRequest = snapshot.into();
+
+        let mut content = Vec::new();
+        prost::Message::encode(&snapshot, &mut content).unwrap();
+
+        let _write_access = self.write_lock.lock().await;
+
+        if let Some(parent) = self.file_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut temp_file_path = self.file_path.clone().into_os_string();
+        temp_file_path.push(".tmp");
+
+        let mut file = tokio::fs::File::create(&temp_file_path).await?;
+        file.write_all(content.as_slice()).await?;
+        file.sync_all().await?;
+
+        tokio::fs::rename(&temp_file_path, &self.file_path).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{
+        queue_with_intervals::QueueIndexRange, subscriber::TopicQueueType,
+    };
+
+    use crate::topics::TopicQueueSnapshot;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_save_and_load() {
+        let file_path = std::env::temp_dir().join(format!("{}.snapshot", uuid::Uuid::new_v4()));
+        let repo = TopicsAndQueuesSnapshotFileRepo::new(file_path.clone());
+
+        assert_eq!(0, repo.load().await.unwrap().len());
+
+        repo.save(vec![TopicSnapshot {
+            topic_id: "test-topic".into(),
+            message_id: 10,
+            persist: true,
+            queues: vec![TopicQueueSnapshot {
+                queue_id: "test-queue".to_string(),
+                queue_type: TopicQueueType::Permanent,
+                ranges: vec![QueueIndexRange {
+                    from_id: 5,
+                    to_id: 9,
+                }],
+            }],
+        }])
+        .await
+        .unwrap();
+
+        let result = repo.load().await.unwrap();
+
+        assert_eq!(1, result.len());
+        assert_eq!("test-topic", result[0].topic_id.as_str());
+        assert_eq!(10, result[0].message_id);
+        assert_eq!("test-queue", result[0].queues[0].queue_id);
+        assert_eq!(5, result[0].queues[0].ranges[0].from_id);
+        assert_eq!(9, result[0].queues[0].ranges[0].to_id);
+
+        let _ = tokio::fs::remove_file(file_path).await;
+    }
+}
@@ -17,11 +17,8 @@ pub struct TopcsAndQueuesSnapshotGrpcRepo {
 
 impl TopcsAndQueuesSnapshotGrpcRepo {
     pub async fn new(grpc_address: String) -> Self {
-        let channel = Channel::from_shared(grpc_address)
-            .unwrap()
-            .connect()
-            .await
-            .unwrap();
+        // Channel is connected lazily, so node can still start from local snapshot if persistence is down
+        let channel = Channel::from_shared(grpc_address).unwrap().connect_lazy();
         Self {
             timeout: Duration::from_secs(5),
             channel,
@@ -35,17 +32,13 @@ impl TopcsAndQueuesSnapshotGrpcRepo {
     pub async fn load(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
         let mut grpc_client = self.create_grpc_service();
 
-        let result = load_snapshot_with_timeout(&mut grpc_client, self.timeout).await;
-
-        Ok(result)
+        load_snapshot_with_timeout(&mut grpc_client, self.timeout).await
     }
 
     pub async fn save(&self, snapshot: Vec<TopicSnapshot>) -> Result<(), PersistenceError> {
         let mut grpc_client = self.create_grpc_service();
 
-        save_snapshot_with_timeout(&mut grpc_client, snapshot, self.timeout).await;
-
-        Ok(())
+        save_snapshot_with_timeout(&mut grpc_client, snapshot, self.timeout).await
     }
 }
 
@@ -53,48 +46,27 @@ async fn save_snapshot_with_timeout(
     grpc: &mut MyServiceBusQueuePersistenceGrpcServiceClient<Channel>,
     snapshot: Vec<TopicSnapshot>,
     timeout: Duration,
-) {
+) -> Result<(), PersistenceError> {
     let grpc_request: SaveQueueSnapshotGrpcRequest = snapshot.into();
 
-    match tokio::time::timeout(timeout, grpc.save_snapshot(grpc_request)).await {
-        Ok(result) => {
-            result.unwrap();
-            return;
-        }
-        Err(_) => panic!("save_snapshot timeout"),
-    }
+    tokio::time::timeout(timeout, grpc.save_snapshot(grpc_request)).await??;
+
+    Ok(())
 }
 
 async fn load_snapshot_with_timeout(
     grpc: &mut MyServiceBusQueuePersistenceGrpcServiceClient<Channel>,
     timeout: Duration,
-) -> Vec<TopicSnapshot> {
-    let mut attempt_no = 0;
-
-    loop {
-        match tokio::time::timeout(timeout, grpc.get_snapshot(())).await {
-            Ok(response) => {
-                let response = response.unwrap();
+) -> Result<Vec<TopicSnapshot>, PersistenceError> {
+    let mut response = tokio::time::timeout(timeout, grpc.get_snapshot(()))
+        .await??
+        .into_inner();
 
-                let mut response = response.into_inner();
+    let mut result: Vec<TopicSnapshot> = Vec::new();
 
-                let mut result: Vec<TopicSnapshot> = Vec::new();
-
-                while let Some(item) = response.next().await {
-                    let grpc_model = item.unwrap();
-                    result.push(grpc_model.into());
-                }
-
-                return result;
-            }
-            Err(_) => {
-                if attempt_no >= 5 {
-                    panic!("save_snapshot timeout");
-                }
-
-                attempt_no += 1;
-                tokio::time::sleep(Duration::from_secs(1)).await;
-            }
-        }
+    while let Some(item) = tokio::time::timeout(timeout, response.next()).await? {
+        result.push(item?.into());
     }
+
+    Ok(result)
 }
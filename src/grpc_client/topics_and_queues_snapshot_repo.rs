@@ -1,23 +1,57 @@
-use crate::{settings::SettingsModel, topics::TopicSnapshot};
+use my_logger::LogEventCtx;
+
+use crate::{
+    settings::{LocalSnapshotMode, SettingsModel},
+    topics::TopicSnapshot,
+};
 
 #[cfg(test)]
 use super::topics_and_queues_snapshot_mock_repo::TopicsAndQueuesSnapshotMockRepo;
 
 use super::{
+    topics_and_queues_snapshot_file_repo::TopicsAndQueuesSnapshotFileRepo,
     topics_and_queues_snapshot_grpc_repo::TopcsAndQueuesSnapshotGrpcRepo, PersistenceError,
 };
 
+const LOCAL_SNAPSHOT_FILE_NAME: &str = "topics_and_queues.snapshot";
+
 pub enum TopicsAndQueuesSnapshotRepo {
     Grpc(TopcsAndQueuesSnapshotGrpcRepo),
+    File(TopicsAndQueuesSnapshotFileRepo),
+    GrpcWithLocalMirror(
+        TopcsAndQueuesSnapshotGrpcRepo,
+        TopicsAndQueuesSnapshotFileRepo,
+    ),
     #[cfg(test)]
     Mock(TopicsAndQueuesSnapshotMockRepo),
 }
 
 impl TopicsAndQueuesSnapshotRepo {
     pub async fn create_production_instance(settings: &SettingsModel) -> Self {
-        let grpc_repo =
-            TopcsAndQueuesSnapshotGrpcRepo::new(settings.persistence_grpc_url.to_string()).await;
-        Self::Grpc(grpc_repo)
+        let local_snapshot = settings
+            .local_snapshot_path
+            .as_ref()
+            .map(|local_snapshot_path| {
+                TopicsAndQueuesSnapshotFileRepo::new(
+                    std::path::Path::new(local_snapshot_path).join(LOCAL_SNAPSHOT_FILE_NAME),
+                )
+            });
+
+        match (settings.local_snapshot_mode, local_snapshot) {
+            (Some(LocalSnapshotMode::Primary), Some(file_repo)) => Self::File(file_repo),
+            (Some(LocalSnapshotMode::Mirror), Some(file_repo)) => {
+                let grpc_repo =
+                    TopcsAndQueuesSnapshotGrpcRepo::new(settings.persistence_grpc_url.to_string())
+                        .await;
+                Self::GrpcWithLocalMirror(grpc_repo, file_repo)
+            }
+            _ => {
+                let grpc_repo =
+                    TopcsAndQueuesSnapshotGrpcRepo::new(settings.persistence_grpc_url.to_string())
+                        .await;
+                Self::Grpc(grpc_repo)
+            }
+        }
     }
 
     #[cfg(test)]
@@ -28,13 +62,38 @@ impl TopicsAndQueuesSnapshotRepo {
     pub async fn load(&self) -> Result<Vec<TopicSnapshot>, PersistenceError> {
         match self {
             TopicsAndQueuesSnapshotRepo::Grpc(repo) => repo.load().await,
+            TopicsAndQueuesSnapshotRepo::File(repo) => repo.load().await,
+            TopicsAndQueuesSnapshotRepo::GrpcWithLocalMirror(repo, _) => repo.load().await,
             #[cfg(test)]
             TopicsAndQueuesSnapshotRepo::Mock(repo) => repo.load().await,
         }
     }
+
+    // Returns None if there is no local mirror to fall back to
+    pub async fn load_local_mirror(&self) -> Option<Result<Vec<TopicSnapshot>, PersistenceError>> {
+        match self {
+            TopicsAndQueuesSnapshotRepo::GrpcWithLocalMirror(_, mirror) => {
+                Some(mirror.load().await)
+            }
+            _ => None,
+        }
+    }
+
     pub async fn save(&self, snapshot: Vec<TopicSnapshot>) -> Result<(), PersistenceError> {
         match self {
             TopicsAndQueuesSnapshotRepo::Grpc(repo) => repo.save(snapshot).await,
+            TopicsAndQueuesSnapshotRepo::File(repo) => repo.save(snapshot).await,
+            TopicsAndQueuesSnapshotRepo::GrpcWithLocalMirror(repo, mirror) => {
+                if let Err(err) = mirror.save(snapshot.clone()).await {
+                    my_logger::LOGGER.write_error(
+                        "save_local_snapshot_mirror",
+                        format!("Failed to save local snapshot mirror: {:?}", err),
+                        LogEventCtx::new().add("filePath", format!("{:?}", mirror.get_file_path())),
+                    );
+                }
+
+                repo.save(snapshot).await
+            }
             #[cfg(test)]
             TopicsAndQueuesSnapshotRepo::Mock(repo) => repo.save(snapshot).await,
         }
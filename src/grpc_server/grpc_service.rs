@@ -0,0 +1,756 @@
+use std::{pin::Pin, sync::Arc};
+
+use futures_util::Stream;
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, queue_with_intervals::QueueWithIntervals,
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::{
+    app::{AppContext, ListenerStatus},
+    operations,
+    service_bus_grpc::{
+        my_service_bus_grpc_service_server::MyServiceBusGrpcService, subscribe_stream_grpc_request,
+        subscribe_stream_grpc_response, *,
+    },
+    sessions::{grpc::MyServiceBusGrpcSession, MyServiceBusSession},
+};
+
+const DELIVERY_STREAM_BUFFER_SIZE: usize = 32;
+const API_KEY_METADATA: &str = "x-api-key";
+
+const CONFIRMATION_TYPE_ALL_FAIL: i32 = ConfirmationTypeGrpcEnum::AllFail as i32;
+const CONFIRMATION_TYPE_SOME_OK: i32 = ConfirmationTypeGrpcEnum::SomeOk as i32;
+
+pub struct MyServiceBusGrpcServer {
+    app: Arc<AppContext>,
+    listener: Arc<ListenerStatus>,
+}
+
+impl MyServiceBusGrpcServer {
+    pub fn new(app: Arc<AppContext>, listener: Arc<ListenerStatus>) -> Self {
+        Self { app, listener }
+    }
+
+    async fn get_session(&self, session_key: &str) -> Result<Arc<MyServiceBusGrpcSession>, Status> {
+        match self.app.sessions.get_grpc(session_key).await {
+            Some(session) => {
+                session.ping();
+                Ok(session)
+            }
+            None => Err(Status::unauthenticated(format!(
+                "Session {} is not found",
+                session_key
+            ))),
+        }
+    }
+
+    async fn publish_messages(&self, request: PublishGrpcRequest) -> Result<usize, Status> {
+        let session = self.get_session(request.session_key.as_str()).await?;
+
+        let mut content_size = 0;
+        let mut messages: Vec<MessageToPublish> = Vec::with_capacity(request.messages.len());
+
+        for msg in request.messages {
+            content_size += msg.content.len();
+            messages.push(msg.into());
+        }
+
+        let messages_count = messages.len();
+
+        let delay = operations::publisher::publish_with_rate_limit(
+            &self.app,
+            session.get_session_id(),
+            session.name.as_str(),
+            request.topic_id.as_str(),
+            messages,
+            request.persist_immediately,
+        )
+        .await?;
+
+        session.update_written_amount(content_size);
+
+        if let Some(delay) = delay {
+            tokio::time::sleep(delay).await;
+        }
+
+        Ok(messages_count)
+    }
+}
+
+#[tonic::async_trait]
+impl MyServiceBusGrpcService for MyServiceBusGrpcServer {
+    type SubscribeStream =
+        Pin<Box<dyn Stream<Item = Result<SubscribeStreamGrpcResponse, Status>> + Send + 'static>>;
+
+    async fn greeting(
+        &self,
+        request: Request<GreetingGrpcRequest>,
+    ) -> Result<Response<GreetingGrpcResponse>, Status> {
+        let ip = match request.remote_addr() {
+            Some(addr) => addr.to_string(),
+            None => "???".to_string(),
+        };
+
+        let credential = request
+            .metadata()
+            .get(API_KEY_METADATA)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.to_string());
+
+        let request = request.into_inner();
+
+        println!(
+            "New grpc session from {} with name: {} and version {}",
+            ip, request.app_name, request.app_version
+        );
+
+        if self.app.states.is_shutting_down() {
+            return Err(operations::OperationFailResult::ShuttingDown.into());
+        }
+
+        let identity = operations::authenticate_session(
+            self.app.as_ref(),
+            self.listener.as_ref(),
+            request.app_name.as_str(),
+            credential.as_deref(),
+            ip.as_str(),
+        )
+        .await
+        .map_err(|err| Status::unauthenticated(format!("{:?}", err)))?;
+
+        let session = self
+            .app
+            .sessions
+            .add_grpc(
+                identity.clone().unwrap_or(request.app_name),
+                request.app_version,
+                ip,
+                identity,
+            )
+            .await;
+
+        Ok(Response::new(GreetingGrpcResponse {
+            session_key: session.session_key.to_string(),
+        }))
+    }
+
+    async fn ping(&self, request: Request<PingGrpcRequest>) -> Result<Response<()>, Status> {
+        self.get_session(request.get_ref().session_key.as_str())
+            .await?;
+        Ok(Response::new(()))
+    }
+
+    async fn publish(
+        &self,
+        request: Request<PublishGrpcRequest>,
+    ) -> Result<Response<PublishGrpcResponse>, Status> {
+        let published_count = self.publish_messages(request.into_inner()).await?;
+
+        Ok(Response::new(PublishGrpcResponse {
+            published_count: published_count as i64,
+        }))
+    }
+
+    async fn publish_stream(
+        &self,
+        request: Request<Streaming<PublishGrpcRequest>>,
+    ) -> Result<Response<PublishGrpcResponse>, Status> {
+        let mut stream = request.into_inner();
+
+        let mut published_count = 0;
+
+        while let Some(request) = stream.next().await {
+            published_count += self.publish_messages(request?).await?;
+        }
+
+        Ok(Response::new(PublishGrpcResponse {
+            published_count: published_count as i64,
+        }))
+    }
+
+    async fn subscribe(
+        &self,
+        request: Request<Streaming<SubscribeStreamGrpcRequest>>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let mut in_stream = request.into_inner();
+
+        let first_request = match in_stream.next().await {
+            Some(request) => request?,
+            None => {
+                return Err(Status::invalid_argument(
+                    "Subscribe stream is closed before the first request",
+                ))
+            }
+        };
+
+        let session = self.get_session(first_request.session_key.as_str()).await?;
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(DELIVERY_STREAM_BUFFER_SIZE);
+
+        if !session.attach_delivery_stream(sender) {
+            return Err(Status::already_exists(format!(
+                "Session {} already has subscribe stream",
+                session.session_key
+            )));
+        }
+
+        let app = self.app.clone();
+
+        tokio::spawn(async move {
+            let mut next_request = Some(first_request);
+
+            while let Some(request) = next_request.take() {
+                session.ping();
+
+                if let Err(err) = handle_subscribe_stream_request(&app, &session, request).await {
+                    let payload =
+                        subscribe_stream_grpc_response::Payload::Reject(RejectGrpcModel {
+                            message: format!("{:?}", err),
+                        });
+                    session.send_to_delivery_stream(payload).await;
+                }
+
+                next_request = match in_stream.next().await {
+                    Some(Ok(request)) => Some(request),
+                    _ => None,
+                };
+            }
+
+            session.disconnect().await;
+
+            if let Some(session) = app.sessions.remove_by_session_id(session.session_id).await {
+                crate::operations::sessions::disconnect(app.as_ref(), session).await;
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(receiver))))
+    }
+
+    async fn create_topic_if_not_exists(
+        &self,
+        request: Request<TopicGrpcRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+        let session = self.get_session(request.session_key.as_str()).await?;
+
+        operations::publisher::create_topic_if_not_exists(
+            &self.app,
+            Some(session.get_session_id()),
+            request.topic_id.as_str(),
+        )
+        .await?;
+
+        Ok(Response::new(()))
+    }
+
+    async fn delete_topic(
+        &self,
+        request: Request<DeleteTopicGrpcRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+        self.get_session(request.session_key.as_str()).await?;
+
+        operations::delete_topic(
+            &self.app,
+            request.topic_id.as_str(),
+            DateTimeAsMicroseconds::new(request.hard_delete_moment),
+        )
+        .await?;
+
+        Ok(Response::new(()))
+    }
+
+    async fn get_topics(
+        &self,
+        request: Request<GetTopicsGrpcRequest>,
+    ) -> Result<Response<GetTopicsGrpcResponse>, Status> {
+        self.get_session(request.get_ref().session_key.as_str())
+            .await?;
+
+        let mut topics = Vec::new();
+
+        for topic in self.app.topic_list.get_all().await {
+            let topic_data = topic.get_access().await;
+            topics.push(TopicGrpcModel {
+                topic_id: topic.topic_id.to_string(),
+                message_id: topic_data.message_id.get_value(),
+                persist: topic_data.persist,
+            });
+        }
+
+        Ok(Response::new(GetTopicsGrpcResponse { topics }))
+    }
+
+    async fn get_queues(
+        &self,
+        request: Request<TopicGrpcRequest>,
+    ) -> Result<Response<GetQueuesGrpcResponse>, Status> {
+        let request = request.into_inner();
+        self.get_session(request.session_key.as_str()).await?;
+
+        let topic = self
+            .app
+            .topic_list
+            .get(request.topic_id.as_str())
+            .await
+            .ok_or(Status::not_found(format!(
+                "Topic {} not found",
+                request.topic_id
+            )))?;
+
+        let mut queues = Vec::new();
+
+        {
+            let topic_data = topic.get_access().await;
+            for queue in topic_data.queues.get_all() {
+                queues.push(QueueGrpcModel {
+                    queue_id: queue.queue_id.to_string(),
+                    queue_type: queue.queue_type.into_u8() as i32,
+                    size: queue.get_queue_size() as i64,
+                    subscribers_count: queue.subscribers.get_amount() as i32,
+                });
+            }
+        }
+
+        Ok(Response::new(GetQueuesGrpcResponse { queues }))
+    }
+
+    async fn delete_queue(
+        &self,
+        request: Request<QueueGrpcRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+        self.get_session(request.session_key.as_str()).await?;
+
+        operations::queues::delete_queue(
+            self.app.as_ref(),
+            request.topic_id.as_str(),
+            request.queue_id.as_str(),
+        )
+        .await?;
+
+        Ok(Response::new(()))
+    }
+
+    async fn set_queue_message_id(
+        &self,
+        request: Request<SetQueueMessageIdGrpcRequest>,
+    ) -> Result<Response<()>, Status> {
+        let request = request.into_inner();
+        self.get_session(request.session_key.as_str()).await?;
+
+        operations::queues::set_message_id(
+            self.app.as_ref(),
+            request.topic_id.as_str(),
+            request.queue_id.as_str(),
+            request.message_id.into(),
+        )
+        .await?;
+
+        Ok(Response::new(()))
+    }
+}
+
+async fn handle_subscribe_stream_request(
+    app: &Arc<AppContext>,
+    session: &Arc<MyServiceBusGrpcSession>,
+    request: SubscribeStreamGrpcRequest,
+) -> Result<(), operations::OperationFailResult> {
+    let payload = match request.payload {
+        Some(payload) => payload,
+        None => return Ok(()),
+    };
+
+    match payload {
+        subscribe_stream_grpc_request::Payload::Subscribe(subscribe) => {
+            operations::subscriber::subscribe_to_queue(
+                app,
+                subscribe.topic_id.to_string(),
+                subscribe.queue_id.to_string(),
+                super::mappers::queue_type_from_grpc(subscribe.queue_type)?,
+                session.clone(),
+            )
+            .await?;
+
+            let payload = subscribe_stream_grpc_response::Payload::SubscribeResponse(
+                SubscribeResponseGrpcModel {
+                    topic_id: subscribe.topic_id,
+                    queue_id: subscribe.queue_id,
+                },
+            );
+
+            session.send_to_delivery_stream(payload).await;
+        }
+        subscribe_stream_grpc_request::Payload::Confirmation(confirmation) => {
+            let subscriber_id = confirmation.confirmation_id.into();
+
+            match confirmation.confirmation_type {
+                CONFIRMATION_TYPE_ALL_FAIL => {
+                    operations::delivery_confirmation::all_fail(
+                        app,
+                        confirmation.topic_id.as_str(),
+                        confirmation.queue_id.as_str(),
+                        subscriber_id,
+                        session.session_id,
+                    )
+                    .await?;
+                }
+                CONFIRMATION_TYPE_SOME_OK => {
+                    let delivered = confirmation
+                        .delivered
+                        .into_iter()
+                        .map(|itm| itm.into())
+                        .collect();
+
+                    operations::delivery_confirmation::some_messages_are_confirmed(
+                        app,
+                        confirmation.topic_id.as_str(),
+                        confirmation.queue_id.as_str(),
+                        subscriber_id,
+                        session.session_id,
+                        QueueWithIntervals::restore(delivered),
+                    )
+                    .await?;
+                }
+                _ => {
+                    operations::delivery_confirmation::all_confirmed(
+                        app,
+                        confirmation.topic_id.as_str(),
+                        confirmation.queue_id.as_str(),
+                        subscriber_id,
+                        session.session_id,
+                    )
+                    .await?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+    use tonic::{Code, Request};
+
+    use crate::{
+        app::{AppContext, ListenerStatus, GRPC_PROTOCOL},
+        service_bus_grpc::{
+            my_service_bus_grpc_service_server::MyServiceBusGrpcService,
+            subscribe_stream_grpc_request, subscribe_stream_grpc_response, *,
+        },
+        sessions::MyServiceBusSession,
+        settings::{
+            ApiKeySettings, AuthSettings, PublishRateLimit, PublishRateLimitScope, SettingsModel,
+        },
+    };
+
+    use super::MyServiceBusGrpcServer;
+
+    const TOPIC_NAME: &str = "test-topic";
+    const QUEUE_NAME: &str = "test-queue";
+
+    fn create_server(app: Arc<AppContext>) -> MyServiceBusGrpcServer {
+        let listener = ListenerStatus::new(
+            GRPC_PROTOCOL,
+            GRPC_PROTOCOL.to_string(),
+            SocketAddr::from(([127, 0, 0, 1], 6125)),
+            app.settings.auth.is_some(),
+        );
+
+        MyServiceBusGrpcServer::new(app, Arc::new(listener))
+    }
+
+    async fn greeting(server: &MyServiceBusGrpcServer) -> String {
+        server
+            .greeting(Request::new(GreetingGrpcRequest {
+                app_name: "test-app".to_string(),
+                app_version: "1.0.0".to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .session_key
+    }
+
+    fn subscribe_request(session_key: &str, queue_type: i32) -> SubscribeStreamGrpcRequest {
+        SubscribeStreamGrpcRequest {
+            session_key: session_key.to_string(),
+            payload: Some(subscribe_stream_grpc_request::Payload::Subscribe(
+                SubscribeGrpcModel {
+                    topic_id: TOPIC_NAME.to_string(),
+                    queue_id: QUEUE_NAME.to_string(),
+                    queue_type,
+                },
+            )),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_subscribe_and_confirm() {
+        let app = Arc::new(AppContext::new(SettingsModel::create_test_settings(16)).await);
+        let server = create_server(app.clone());
+
+        let session_key = greeting(&server).await;
+
+        server
+            .create_topic_if_not_exists(Request::new(TopicGrpcRequest {
+                session_key: session_key.to_string(),
+                topic_id: TOPIC_NAME.to_string(),
+            }))
+            .await
+            .unwrap();
+
+        let session = app.sessions.get_grpc(session_key.as_str()).await.unwrap();
+        let (sender, mut receiver) = tokio::sync::mpsc::channel(16);
+        assert!(session.attach_delivery_stream(sender));
+
+        super::handle_subscribe_stream_request(
+            &app,
+            &session,
+            subscribe_request(session_key.as_str(), QueueTypeGrpcEnum::Permanent as i32),
+        )
+        .await
+        .unwrap();
+
+        match receiver.recv().await.unwrap().unwrap().payload.unwrap() {
+            subscribe_stream_grpc_response::Payload::SubscribeResponse(response) => {
+                assert_eq!(TOPIC_NAME, response.topic_id);
+                assert_eq!(QUEUE_NAME, response.queue_id);
+            }
+            _ => panic!("Subscribe response is expected"),
+        }
+
+        let response = server
+            .publish(Request::new(PublishGrpcRequest {
+                session_key: session_key.to_string(),
+                topic_id: TOPIC_NAME.to_string(),
+                persist_immediately: false,
+                messages: vec![
+                    MessageToPublishGrpcModel {
+                        headers: vec![],
+                        content: vec![0u8, 1u8, 2u8],
+                    },
+                    MessageToPublishGrpcModel {
+                        headers: vec![],
+                        content: vec![3u8, 4u8, 5u8],
+                    },
+                ],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+
+        assert_eq!(2, response.published_count);
+
+        let confirmation_id = match receiver.recv().await.unwrap().unwrap().payload.unwrap() {
+            subscribe_stream_grpc_response::Payload::NewMessages(new_messages) => {
+                assert_eq!(2, new_messages.messages.len());
+                assert_eq!(vec![0u8, 1u8, 2u8], new_messages.messages[0].content);
+                new_messages.confirmation_id
+            }
+            _ => panic!("New messages are expected"),
+        };
+
+        let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+
+        {
+            let topic_data = topic.get_access().await;
+            let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+            assert_eq!(2, queue.get_on_delivery());
+        }
+
+        super::handle_subscribe_stream_request(
+            &app,
+            &session,
+            SubscribeStreamGrpcRequest {
+                session_key: session_key.to_string(),
+                payload: Some(subscribe_stream_grpc_request::Payload::Confirmation(
+                    ConfirmationGrpcModel {
+                        topic_id: TOPIC_NAME.to_string(),
+                        queue_id: QUEUE_NAME.to_string(),
+                        confirmation_id,
+                        confirmation_type: ConfirmationTypeGrpcEnum::AllOk as i32,
+                        delivered: vec![],
+                    },
+                )),
+            },
+        )
+        .await
+        .unwrap();
+
+        let topic_data = topic.get_access().await;
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+        assert_eq!(0, queue.get_on_delivery());
+        assert_eq!(0, queue.get_queue_size());
+    }
+
+    fn publish_request(session_key: &str, messages_count: usize) -> PublishGrpcRequest {
+        PublishGrpcRequest {
+            session_key: session_key.to_string(),
+            topic_id: TOPIC_NAME.to_string(),
+            persist_immediately: false,
+            messages: (0..messages_count)
+                .map(|_| MessageToPublishGrpcModel {
+                    headers: vec![],
+                    content: vec![0u8, 1u8, 2u8],
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_is_throttled() {
+        let mut settings = SettingsModel::create_test_settings(16);
+
+        settings.publish_rate_limits = vec![PublishRateLimit {
+            app_name: "test-app".to_string(),
+            topic_id: TOPIC_NAME.to_string(),
+            scope: PublishRateLimitScope::Session,
+            messages_per_second: Some(10),
+            bytes_per_second: None,
+        }];
+
+        settings.publish_rate_limit_max_delay = Duration::from_secs(1);
+
+        let app = Arc::new(AppContext::new(settings).await);
+        let server = create_server(app.clone());
+
+        let session_key = greeting(&server).await;
+
+        server
+            .create_topic_if_not_exists(Request::new(TopicGrpcRequest {
+                session_key: session_key.to_string(),
+                topic_id: TOPIC_NAME.to_string(),
+            }))
+            .await
+            .unwrap();
+
+        server
+            .publish(Request::new(publish_request(session_key.as_str(), 10)))
+            .await
+            .unwrap();
+
+        let started = tokio::time::Instant::now();
+
+        server
+            .publish(Request::new(publish_request(session_key.as_str(), 5)))
+            .await
+            .unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(400));
+
+        let err = server
+            .publish(Request::new(publish_request(session_key.as_str(), 20)))
+            .await
+            .unwrap_err();
+
+        assert_eq!(Code::ResourceExhausted, err.code());
+
+        let session = app.sessions.get_grpc(session_key.as_str()).await.unwrap();
+
+        let rate = app
+            .publish_rate_limiter
+            .get_session_rate(session.get_session_id(), DateTimeAsMicroseconds::now())
+            .unwrap();
+
+        assert_eq!(1, rate.throttled);
+        assert_eq!(1, rate.rejected);
+
+        let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+        let topic_data = topic.get_access().await;
+        assert_eq!(15, topic_data.message_id.get_value());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_queue_type_is_rejected() {
+        let app = Arc::new(AppContext::new(SettingsModel::create_test_settings(16)).await);
+        let server = create_server(app.clone());
+
+        let session_key = greeting(&server).await;
+        let session = app.sessions.get_grpc(session_key.as_str()).await.unwrap();
+
+        let err = super::handle_subscribe_stream_request(
+            &app,
+            &session,
+            subscribe_request(session_key.as_str(), 100),
+        )
+        .await
+        .unwrap_err();
+
+        let status: tonic::Status = err.into();
+        assert_eq!(tonic::Code::InvalidArgument, status.code());
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_session_are_rejected() {
+        let app = Arc::new(AppContext::new(SettingsModel::create_test_settings(16)).await);
+        let server = create_server(app.clone());
+
+        let status = server
+            .get_topics(Request::new(GetTopicsGrpcRequest {
+                session_key: "unknown".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(tonic::Code::Unauthenticated, status.code());
+
+        let status = server
+            .get_queues(Request::new(TopicGrpcRequest {
+                session_key: "unknown".to_string(),
+                topic_id: TOPIC_NAME.to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(tonic::Code::Unauthenticated, status.code());
+    }
+
+    #[tokio::test]
+    async fn test_greeting_is_authenticated_by_api_key_metadata() {
+        let mut settings = SettingsModel::create_test_settings(16);
+        settings.auth = Some(AuthSettings {
+            api_keys: vec![ApiKeySettings {
+                identity: "orders-service".to_string(),
+                key: "secret-key".to_string(),
+            }],
+            api_keys_file: None,
+        });
+
+        let app = Arc::new(AppContext::new(settings).await);
+        let server = create_server(app.clone());
+
+        let status = server
+            .greeting(Request::new(GreetingGrpcRequest {
+                app_name: "test-app".to_string(),
+                app_version: "1.0.0".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(tonic::Code::Unauthenticated, status.code());
+
+        let mut request = Request::new(GreetingGrpcRequest {
+            app_name: "test-app".to_string(),
+            app_version: "1.0.0".to_string(),
+        });
+        request
+            .metadata_mut()
+            .insert(super::API_KEY_METADATA, "secret-key".parse().unwrap());
+
+        let session_key = server
+            .greeting(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .session_key;
+
+        let session = app.sessions.get_grpc(session_key.as_str()).await.unwrap();
+        assert_eq!(Some("orders-service"), session.identity.as_deref());
+        assert_eq!("orders-service", session.name);
+    }
+}
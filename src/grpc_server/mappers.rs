@@ -0,0 +1,48 @@
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, queue_with_intervals::QueueIndexRange, subscriber::TopicQueueType,
+    SbMessageHeaders,
+};
+
+use crate::{operations::OperationFailResult, service_bus_grpc::*};
+
+const QUEUE_TYPE_PERMANENT: i32 = QueueTypeGrpcEnum::Permanent as i32;
+const QUEUE_TYPE_DELETE_ON_DISCONNECT: i32 = QueueTypeGrpcEnum::DeleteOnDisconnect as i32;
+const QUEUE_TYPE_PERMANENT_WITH_SINGLE_CONNECTION: i32 =
+    QueueTypeGrpcEnum::PermanentWithSingleConnection as i32;
+
+impl From<MessageToPublishGrpcModel> for MessageToPublish {
+    fn from(src: MessageToPublishGrpcModel) -> Self {
+        let mut headers = SbMessageHeaders::with_capacity(src.headers.len());
+
+        for header in src.headers {
+            headers = headers.add(header.key, header.value);
+        }
+
+        Self {
+            headers,
+            content: src.content,
+        }
+    }
+}
+
+impl From<QueueIndexRangeGrpcModel> for QueueIndexRange {
+    fn from(src: QueueIndexRangeGrpcModel) -> Self {
+        Self {
+            from_id: src.from_id,
+            to_id: src.to_id,
+        }
+    }
+}
+
+pub fn queue_type_from_grpc(src: i32) -> Result<TopicQueueType, OperationFailResult> {
+    match src {
+        QUEUE_TYPE_PERMANENT => Ok(TopicQueueType::Permanent),
+        QUEUE_TYPE_DELETE_ON_DISCONNECT => Ok(TopicQueueType::DeleteOnDisconnect),
+        QUEUE_TYPE_PERMANENT_WITH_SINGLE_CONNECTION => {
+            Ok(TopicQueueType::PermanentWithSingleConnection)
+        }
+        _ => Err(OperationFailResult::TonicError(
+            tonic::Status::invalid_argument(format!("Invalid queue type: {}", src)),
+        )),
+    }
+}
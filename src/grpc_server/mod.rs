@@ -0,0 +1,5 @@
+mod grpc_service;
+pub use grpc_service::*;
+mod mappers;
+mod server;
+pub use server::start;
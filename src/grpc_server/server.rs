@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use tonic::transport::Server;
+
+use crate::{
+    app::{AppContext, ListenerStatus},
+    service_bus_grpc::my_service_bus_grpc_service_server::MyServiceBusGrpcServiceServer,
+};
+
+use super::MyServiceBusGrpcServer;
+
+pub fn start(app: Arc<AppContext>, listener_status: Arc<ListenerStatus>) {
+    let addr = listener_status.address;
+
+    tokio::spawn(async move {
+        println!("Grpc server is started at {}", addr);
+
+        let service =
+            MyServiceBusGrpcServiceServer::new(MyServiceBusGrpcServer::new(app, listener_status));
+
+        if let Err(err) = Server::builder().add_service(service).serve(addr).await {
+            panic!("Can not start Grpc server at {}. Err: {:?}", addr, err);
+        }
+    });
+}
@@ -1,6 +1,13 @@
+use std::sync::Arc;
+
 use my_http_server::*;
 
+use crate::{app::AppContext, auth::AdminAccessDenied};
+
 const AUTH_HEADER: &str = "authorization";
+const ADMIN_KEY_HEADER: &str = "x-admin-key";
+const COOKIE_HEADER: &str = "cookie";
+const ADMIN_KEY_COOKIE: &str = "AdminKey";
 
 pub struct SessionToken {
     pub session: String,
@@ -16,7 +23,15 @@ impl RequestCredentials for SessionToken {
     }
 }
 
-pub struct AuthMiddleware;
+pub struct AuthMiddleware {
+    app: Arc<AppContext>,
+}
+
+impl AuthMiddleware {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
 
 #[async_trait::async_trait]
 impl HttpServerMiddleware for AuthMiddleware {
@@ -37,6 +52,77 @@ impl HttpServerMiddleware for AuthMiddleware {
             }
         }
 
-        None
+        let admin_key = ctx
+            .request
+            .get_headers()
+            .try_get_case_insensitive(ADMIN_KEY_HEADER)
+            .and_then(|header| header.as_str().ok())
+            .map(|header| header.to_string())
+            .or_else(|| get_admin_key_cookie(ctx));
+
+        let ip = ctx.request.get_ip().get_real_ip().to_string();
+
+        let result = self
+            .app
+            .admin_access
+            .authorize(
+                ctx.request.method.as_str(),
+                ctx.request.http_path.as_str(),
+                admin_key.as_deref(),
+                ip.as_str(),
+            )
+            .await;
+
+        match result {
+            Ok(()) => None,
+            Err(AdminAccessDenied::Unauthorized) => Some(Err(HttpFailResult::as_unauthorized(
+                Some("Admin key is required".to_string()),
+            ))),
+            Err(AdminAccessDenied::Forbidden) => Some(Err(HttpFailResult::as_forbidden(Some(
+                "Admin key has no permission for this call".to_string(),
+            )))),
+        }
+    }
+}
+
+// Ui page and logs pages can not send the header, so ui keeps admin key in the cookie.
+// Cookie is accepted only for reads, so other sites can not make mutating calls with it
+fn get_admin_key_cookie(ctx: &HttpContext) -> Option<String> {
+    let method = ctx.request.method.as_str();
+
+    if !method.eq_ignore_ascii_case("GET") && !method.eq_ignore_ascii_case("HEAD") {
+        return None;
+    }
+
+    let header = ctx
+        .request
+        .get_headers()
+        .try_get_case_insensitive(COOKIE_HEADER)?;
+
+    get_cookie_value(header.as_str().ok()?, ADMIN_KEY_COOKIE)
+}
+
+fn get_cookie_value(cookie_header: &str, name: &str) -> Option<String> {
+    for cookie in cookie_header.split(';') {
+        if let Some((cookie_name, value)) = cookie.trim().split_once('=') {
+            if cookie_name == name && !value.is_empty() {
+                return Some(value.to_string());
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_get_cookie_value() {
+        assert_eq!(
+            Some("key-1".to_string()),
+            super::get_cookie_value("theme=dark; AdminKey=key-1", "AdminKey")
+        );
+        assert_eq!(None, super::get_cookie_value("theme=dark", "AdminKey"));
+        assert_eq!(None, super::get_cookie_value("AdminKey=", "AdminKey"));
     }
 }
@@ -53,12 +53,32 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
     controllers
         .register_delete_action(Arc::new(super::queues::DeleteQueueAction::new(app.clone())));
 
+    controllers.register_get_action(Arc::new(
+        super::replication_controller::GetReplicationStatusAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(super::replication_controller::PromoteAction::new(
+        app.clone(),
+    )));
+
+    controllers.register_get_action(Arc::new(
+        super::settings_controller::GetEffectiveSettingsAction::new(app.clone()),
+    ));
+
+    controllers.register_post_action(Arc::new(super::settings_controller::ReloadAction::new(
+        app.clone(),
+    )));
+
     // DEBUG
 
     controllers.register_get_action(Arc::new(
         super::debug_controller::GetMinMessageIdAction::new(app.clone()),
     ));
 
+    controllers.register_get_action(Arc::new(
+        super::debug_controller::GetPersistedMessageAction::new(app.clone()),
+    ));
+
     controllers.register_post_action(Arc::new(
         super::debug_controller::EnableDebugModeAction::new(app.clone()),
     ));
@@ -74,22 +94,19 @@ pub fn build(app: &Arc<AppContext>) -> ControllersMiddleware {
         Arc::new(super::debug_controller::OnDeliveryAction::new(app.clone()));
     controllers.register_get_action(on_delivery_controller);
 
-    /*
-       let logs_controller = Arc::new(super::logs::LogsAction::new(app.clone()));
-       controllers.register_get_action(logs_controller);
+    controllers.register_get_action(Arc::new(super::logs::LogsAction::new(app.clone())));
 
-       controllers.register_get_action(Arc::new(super::logs::GetLogsByTopicAction::new(
-           app.clone(),
-       )));
+    controllers.register_get_action(Arc::new(super::logs::GetLogsByTopicAction::new(
+        app.clone(),
+    )));
 
-       controllers.register_get_action(Arc::new(super::logs::GetLogsByProcessAction::new(
-           app.clone(),
-       )));
+    controllers.register_get_action(Arc::new(super::logs::GetLogsByProcessAction::new(
+        app.clone(),
+    )));
 
-       controllers.register_get_action(Arc::new(super::logs::SelectTopicAction::new(app.clone())));
+    controllers.register_get_action(Arc::new(super::logs::SelectTopicAction::new(app.clone())));
 
-       controllers.register_get_action(Arc::new(super::logs::SelectProcessAction::new()));
-    */
+    controllers.register_get_action(Arc::new(super::logs::SelectProcessAction::new(app.clone())));
 
     controllers.register_post_action(Arc::new(super::publisher::PublishAction::new(app.clone())));
 
@@ -0,0 +1,72 @@
+use super::models::*;
+use crate::{app::AppContext, http::controllers::MessageKeyValueJsonModel};
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use rust_extensions::base64::IntoBase64;
+use std::sync::Arc;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Debug/PersistedMessage",
+    input_data: GetPersistedMessageInputModel,
+    description: "Get message from persistence storage",
+    summary: "Get message from persistence storage",
+    controller: "Debug",
+    result:[
+        {status_code: 200, description: "Persisted message", model: PersistedMessageDebugModel},
+    ]
+)]
+pub struct GetPersistedMessageAction {
+    app: Arc<AppContext>,
+}
+
+impl GetPersistedMessageAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetPersistedMessageAction,
+    input_data: GetPersistedMessageInputModel,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let message = action
+        .app
+        .messages_pages_repo
+        .get_message(&input_data.topic_id, input_data.message_id.into())
+        .await;
+
+    let message = match message {
+        Ok(message) => message,
+        Err(err) => {
+            return Err(HttpFailResult::as_fatal_error(format!("{:?}", err)));
+        }
+    };
+
+    let message = match message {
+        Some(message) => message,
+        None => {
+            return Err(HttpFailResult::as_not_found(
+                "Message not found".to_string(),
+                false,
+            ));
+        }
+    };
+
+    let response = PersistedMessageDebugModel {
+        id: message.id.get_value(),
+        created: message.time.to_rfc3339(),
+        headers: message
+            .headers
+            .iter()
+            .map(|(k, v)| MessageKeyValueJsonModel {
+                key: k.to_string(),
+                value: v.to_string(),
+            })
+            .collect(),
+        content: message.content.into_base64(),
+    };
+
+    HttpOutput::as_json(response).into_ok_result(true).into()
+}
@@ -9,3 +9,5 @@ mod get_queues_action;
 pub use get_queues_action::*;
 mod get_min_message_id_action;
 pub use get_min_message_id_action::*;
+mod get_persisted_message_action;
+pub use get_persisted_message_action::*;
@@ -1,6 +1,8 @@
 use my_http_server::macros::{MyHttpInput, MyHttpObjectStructure};
 use serde::*;
 
+use crate::http::controllers::MessageKeyValueJsonModel;
+
 #[derive(Debug, MyHttpInput)]
 pub struct GetMinMessageIdInputModel {
     #[http_query(name = "topicId"; description = "Id of topic")]
@@ -12,6 +14,22 @@ pub struct MinMessageIdDebugModel {
     pub min_message_id: Option<i64>,
 }
 
+#[derive(Debug, MyHttpInput)]
+pub struct GetPersistedMessageInputModel {
+    #[http_query(name = "topicId"; description = "Id of topic")]
+    pub topic_id: String,
+    #[http_query(name = "messageId"; description = "Id of message")]
+    pub message_id: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct PersistedMessageDebugModel {
+    pub id: i64,
+    pub created: String,
+    pub headers: Vec<MessageKeyValueJsonModel>,
+    pub content: String,
+}
+
 #[derive(Debug, MyHttpInput)]
 pub struct EnableDebugInputModel {
     #[http_query(name = "topicId"; description = "Id of topic")]
@@ -3,10 +3,15 @@ use std::sync::Arc;
 use my_http_server::macros::http_route;
 use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
 
-use crate::app::AppContext;
+use crate::{
+    app::{AppContext, HTTP_PROTOCOL},
+    operations::OperationFailResult,
+};
 
 use super::models::{GreetingInputModel, GreetingJsonResult};
 
+const API_KEY_HEADER: &str = "x-api-key";
+
 #[http_route(
     method: "POST",
     route: "/api/Greeting",
@@ -36,12 +41,44 @@ async fn handle_request(
     input_data: GreetingInputModel,
     ctx: &mut HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
+    if action.app.states.is_shutting_down() {
+        return Err(OperationFailResult::ShuttingDown.into());
+    }
+
     let ip = ctx.request.get_ip().get_real_ip().to_string();
 
+    let credential = ctx
+        .request
+        .get_headers()
+        .try_get_case_insensitive(API_KEY_HEADER)
+        .and_then(|header| header.as_str().ok())
+        .map(|header| header.to_string());
+
+    let listener = action
+        .app
+        .listeners
+        .get(HTTP_PROTOCOL, HTTP_PROTOCOL)
+        .unwrap();
+
+    let identity = crate::operations::authenticate_session(
+        action.app.as_ref(),
+        listener.as_ref(),
+        input_data.name.as_str(),
+        credential.as_deref(),
+        ip.as_str(),
+    )
+    .await
+    .map_err(|err| HttpFailResult::as_unauthorized(Some(format!("{:?}", err))))?;
+
     let session_key = action
         .app
         .sessions
-        .add_http(input_data.name, input_data.version, ip)
+        .add_http(
+            identity.clone().unwrap_or(input_data.name),
+            input_data.version,
+            ip,
+            identity,
+        )
         .await;
 
     let result = GreetingJsonResult {
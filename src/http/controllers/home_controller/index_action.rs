@@ -24,7 +24,7 @@ async fn handle_request(
         r###"<html><head><title>{ver} MyServiceBus</title>
         <link href="/css/bootstrap.css" rel="stylesheet" type="text/css" />
         <link href="/css/site.css?ver={rnd}" rel="stylesheet" type="text/css" />
-        <script src="/js/jquery.js"></script><script src="/js/app.js?ver={rnd}"></script>
+        <script src="/js/jquery.js"></script><script src="/js/admin_key.js?ver={rnd}"></script><script src="/js/app.js?ver={rnd}"></script>
         </head><body></body></html>"###,
         ver = crate::app::APP_VERSION,
         rnd = action.app.process_id
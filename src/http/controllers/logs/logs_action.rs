@@ -5,9 +5,12 @@ use rust_extensions::StopWatch;
 
 use crate::app::AppContext;
 
+use super::models::ReadLogsInputModel;
+
 #[my_http_server::macros::http_route(
     method: "GET",
     route: "/api/Logs",
+    input_data: "ReadLogsInputModel",
 )]
 pub struct LogsAction {
     app: Arc<AppContext>,
@@ -21,11 +24,14 @@ impl LogsAction {
 
 async fn handle_request(
     action: &LogsAction,
+    input_data: ReadLogsInputModel,
     _ctx: &mut HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
+    let filter = input_data.to_filter()?;
+
     let mut sw = StopWatch::new();
     sw.start();
-    let logs = action.app.logs.get().await;
+    let logs = action.app.logs.get(&filter);
 
     return super::renderers::compile_result("logs", logs, sw);
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput, WebContentType};
 use rust_extensions::StopWatch;
 
-use crate::app::{logs::SystemProcess, AppContext};
+use crate::app::AppContext;
 
 use super::models::*;
 
@@ -28,41 +28,28 @@ async fn handle_request(
     input_data: ReadLogsByProcessInputModel,
     _ctx: &mut HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
-    let process = SystemProcess::parse(input_data.process_id.as_str());
+    let filter = input_data.to_filter()?;
+
+    let mut sw = StopWatch::new();
+    sw.start();
+    let logs = action.app.logs.get(&filter);
+
+    if logs.is_empty() {
+        sw.pause();
 
-    if process.is_none() {
         return HttpOutput::Content {
             content_type: Some(WebContentType::Text),
-            content: format!("Invalid process name: {}", input_data.process_id).into(),
+            content: format!(
+                "Result compiled in: {:?}. No log records for the process '{}'",
+                sw.duration(),
+                input_data.process_id
+            )
+            .into_bytes(),
             headers: None,
         }
         .into_ok_result(false)
         .into();
     }
 
-    let process = process.unwrap();
-
-    let mut sw = StopWatch::new();
-    sw.start();
-    let logs_result = action.app.logs.get_by_process(process).await;
-
-    match logs_result {
-        Some(logs) => super::renderers::compile_result("logs by process", logs, sw),
-        None => {
-            sw.pause();
-
-            HttpOutput::Content {
-                content_type: Some(WebContentType::Text),
-                content: format!(
-                    "Result compiled in: {:?}. No log records for the process '{}'",
-                    sw.duration(),
-                    input_data.process_id
-                )
-                .into_bytes(),
-                headers: None,
-            }
-            .into_ok_result(false)
-            .into()
-        }
-    }
+    super::renderers::compile_result("logs by process", logs, sw)
 }
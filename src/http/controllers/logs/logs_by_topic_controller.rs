@@ -26,32 +26,29 @@ async fn handle_request(
     input_data: ReadLogsByTopicInputModel,
     _ctx: &mut HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
+    let filter = input_data.to_filter()?;
+
     let mut sw = StopWatch::new();
     sw.start();
-    let logs_result = action
-        .app
-        .logs
-        .get_by_topic(input_data.topic_id.as_str())
-        .await;
-
-    match logs_result {
-        Some(logs) => super::renderers::compile_result("logs by topic", logs, sw),
-        None => {
-            sw.pause();
-
-            let content = format!(
-                "Result compiled in: {:?}. No log records for the topic '{}'",
-                sw.duration(),
-                input_data.topic_id.as_str()
-            );
-
-            HttpOutput::Content {
-                content_type: Some(WebContentType::Text),
-                content: content.into_bytes(),
-                headers: None,
-            }
-            .into_ok_result(false)
-            .into()
+    let logs = action.app.logs.get(&filter);
+
+    if logs.is_empty() {
+        sw.pause();
+
+        let content = format!(
+            "Result compiled in: {:?}. No log records for the topic '{}'",
+            sw.duration(),
+            input_data.topic_id.as_str()
+        );
+
+        return HttpOutput::Content {
+            content_type: Some(WebContentType::Text),
+            content: content.into_bytes(),
+            headers: None,
         }
+        .into_ok_result(false)
+        .into();
     }
+
+    super::renderers::compile_result("logs by topic", logs, sw)
 }
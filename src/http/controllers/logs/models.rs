@@ -1,13 +1,107 @@
-use my_http_server::macros::MyHttpInput;
+use my_http_server::{macros::MyHttpInput, HttpFailResult};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::app::logs::{LogLevel, LogsFilter};
+
+#[derive(MyHttpInput)]
+pub struct ReadLogsInputModel {
+    #[http_query(name = "topicId"; description = "Id of Topic")]
+    pub topic_id: Option<String>,
+    #[http_query(name = "process"; description = "Name of Process")]
+    pub process: Option<String>,
+    #[http_query(name = "level"; description = "Info, Warning, Error, FatalError or Debug")]
+    pub level: Option<String>,
+    #[http_query(name = "fromDate"; description = "Show records from the date")]
+    pub from_date: Option<String>,
+    #[http_query(name = "toDate"; description = "Show records up to the date")]
+    pub to_date: Option<String>,
+}
+
+impl ReadLogsInputModel {
+    pub fn to_filter(&self) -> Result<LogsFilter, HttpFailResult> {
+        Ok(LogsFilter {
+            topic_id: self.topic_id.clone(),
+            process: self.process.clone(),
+            level: parse_level(self.level.as_deref())?,
+            from: parse_date("fromDate", self.from_date.as_deref())?,
+            to: parse_date("toDate", self.to_date.as_deref())?,
+        })
+    }
+}
 
 #[derive(MyHttpInput)]
 pub struct ReadLogsByProcessInputModel {
-    #[http_path(name = "processId"; description = "Id of Process")]
+    #[http_path(name = "processId"; description = "Name of Process")]
     pub process_id: String,
+    #[http_query(name = "level"; description = "Info, Warning, Error, FatalError or Debug")]
+    pub level: Option<String>,
+    #[http_query(name = "fromDate"; description = "Show records from the date")]
+    pub from_date: Option<String>,
+    #[http_query(name = "toDate"; description = "Show records up to the date")]
+    pub to_date: Option<String>,
+}
+
+impl ReadLogsByProcessInputModel {
+    pub fn to_filter(&self) -> Result<LogsFilter, HttpFailResult> {
+        Ok(LogsFilter {
+            topic_id: None,
+            process: Some(self.process_id.clone()),
+            level: parse_level(self.level.as_deref())?,
+            from: parse_date("fromDate", self.from_date.as_deref())?,
+            to: parse_date("toDate", self.to_date.as_deref())?,
+        })
+    }
 }
 
 #[derive(MyHttpInput)]
 pub struct ReadLogsByTopicInputModel {
     #[http_path(name = "topicId"; description = "Id of Topic")]
     pub topic_id: String,
+    #[http_query(name = "level"; description = "Info, Warning, Error, FatalError or Debug")]
+    pub level: Option<String>,
+    #[http_query(name = "fromDate"; description = "Show records from the date")]
+    pub from_date: Option<String>,
+    #[http_query(name = "toDate"; description = "Show records up to the date")]
+    pub to_date: Option<String>,
+}
+
+impl ReadLogsByTopicInputModel {
+    pub fn to_filter(&self) -> Result<LogsFilter, HttpFailResult> {
+        Ok(LogsFilter {
+            topic_id: Some(self.topic_id.clone()),
+            process: None,
+            level: parse_level(self.level.as_deref())?,
+            from: parse_date("fromDate", self.from_date.as_deref())?,
+            to: parse_date("toDate", self.to_date.as_deref())?,
+        })
+    }
+}
+
+fn parse_level(src: Option<&str>) -> Result<Option<LogLevel>, HttpFailResult> {
+    match src {
+        Some(src) => match LogLevel::parse(src) {
+            Some(level) => Ok(Some(level)),
+            None => Err(HttpFailResult::as_validation_error(format!(
+                "Invalid log level: {}",
+                src
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
+fn parse_date(
+    param_name: &str,
+    src: Option<&str>,
+) -> Result<Option<DateTimeAsMicroseconds>, HttpFailResult> {
+    match src {
+        Some(src) => match DateTimeAsMicroseconds::from_str(src) {
+            Some(date) => Ok(Some(date)),
+            None => Err(HttpFailResult::as_validation_error(format!(
+                "Invalid {}: {}",
+                param_name, src
+            ))),
+        },
+        None => Ok(None),
+    }
 }
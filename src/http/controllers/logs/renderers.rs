@@ -3,7 +3,10 @@ use std::sync::Arc;
 use my_http_server::{HttpFailResult, HttpOkResult};
 use rust_extensions::{StopWatch, StringBuilder};
 
-use crate::app::logs::LogItem;
+use crate::{
+    app::logs::{LogItem, LogLevel},
+    http::html::{encode_url_component, escape},
+};
 
 pub fn compile_result(
     title: &str,
@@ -24,12 +27,20 @@ pub fn compile_result(
         "<a class='btn btn-outline-secondary btn-sm' href='/api/logs/process'>Show Log records by process</a>",
     );
 
+    for level in LogLevel::iterate() {
+        let line = format!(
+            "<a class='btn btn-outline-secondary btn-sm' href='/api/logs?level={level:?}'>{level:?}</a>",
+            level = level
+        );
+        sb.append_line(line.as_str());
+    }
+
     sb.append_line("<hr/>");
 
     for log_item in &logs {
         let line = format!(
             "<b style='background:{color}; color:white;'>{level:?}:</b> {dt}</br>",
-            color = get_log_level_color(&log_item.as_ref()),
+            color = get_log_level_color(log_item.as_ref()),
             dt = log_item.date.to_rfc3339(),
             level = log_item.level
         );
@@ -37,26 +48,25 @@ pub fn compile_result(
 
         if let Some(topic_name) = &log_item.topic {
             let line = format!(
-                "<b>Topic:</b> <a href='/api/logs/topic/{topic_name}'>{topic_name}</a></br>",
-                topic_name = topic_name
+                "<b>Topic:</b> <a href='/api/logs/topic/{topic_url}'>{topic_name}</a></br>",
+                topic_url = encode_url_component(topic_name),
+                topic_name = escape(topic_name)
             );
             sb.append_line(line.as_str());
         }
 
         let line = format!(
-            "<b>Process:</b> <a href='/api/logs/process/{process:?}'>{process:?}</a></br>",
-            process = log_item.process
+            "<b>Process:</b> <a href='/api/logs?process={process_url}'>{process}</a></br>",
+            process_url = encode_url_component(&log_item.process),
+            process = escape(&log_item.process)
         );
         sb.append_line(line.as_str());
 
-        let line = format!("<b>Process Name:</b> {}</br>", log_item.process_name);
-        sb.append_line(line.as_str());
-
-        let line = format!("<b>Msg:</b> {}</br>", log_item.message.as_str());
+        let line = format!("<b>Msg:</b> {}</br>", escape(&log_item.message));
         sb.append_line(line.as_str());
 
         if let Some(ctx) = &log_item.ctx {
-            let line = format!("<b>CTX:</b> {:?}</br>", ctx);
+            let line = format!("<b>CTX:</b> {}</br>", escape(&format!("{:?}", ctx)));
             sb.append_line(line.as_str());
         }
 
@@ -73,8 +83,10 @@ pub fn compile_result(
 
 fn get_log_level_color(item: &LogItem) -> &str {
     match &item.level {
-        crate::app::logs::LogLevel::Info => "green",
-        crate::app::logs::LogLevel::Error => "orange",
-        crate::app::logs::LogLevel::FatalError => "red",
+        LogLevel::Info => "green",
+        LogLevel::Warning => "darkgoldenrod",
+        LogLevel::Error => "orange",
+        LogLevel::FatalError => "red",
+        LogLevel::Debug => "gray",
     }
 }
@@ -1,23 +1,30 @@
+use std::sync::Arc;
+
 use my_http_server::{HttpContext, HttpFailResult, HttpOkResult};
 use rust_extensions::StringBuilder;
 
-use crate::app::logs::SystemProcess;
+use crate::{
+    app::AppContext,
+    http::html::{encode_url_component, escape},
+};
 
 #[my_http_server::macros::http_route(
     method: "GET",
     route: "/api/Logs/Process",
 )]
 
-pub struct SelectProcessAction;
+pub struct SelectProcessAction {
+    app: Arc<AppContext>,
+}
 
 impl SelectProcessAction {
-    pub fn new() -> Self {
-        Self
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
     }
 }
 
 async fn handle_request(
-    _action: &SelectProcessAction,
+    action: &SelectProcessAction,
 
     _ctx: &mut HttpContext,
 ) -> Result<HttpOkResult, HttpFailResult> {
@@ -25,13 +32,17 @@ async fn handle_request(
 
     sb.append_line("<h1>Please, select process to show logs</h1>");
 
-    for process in &SystemProcess::iterate() {
+    for process in action.app.logs.get_processes() {
         let line = format!(
-            "<a class='btn btn-sm btn-outline-primary' href='/api/logs/process/{process:?}'>{process:?}</a>",
-            process = process
+            "<a class='btn btn-sm btn-outline-primary' href='/api/logs?process={process_url}'>{process}</a>",
+            process_url = encode_url_component(&process),
+            process = escape(&process)
         );
         sb.append_line(line.as_str())
     }
 
-    crate::http::html::compile("Select topic to show logs".to_string(), sb.to_string_utf8())
+    crate::http::html::compile(
+        "Select process to show logs".to_string(),
+        sb.to_string_utf8(),
+    )
 }
@@ -3,12 +3,14 @@ pub mod debug_controller;
 //pub mod extensions;
 pub mod greeting;
 mod home_controller;
-//pub mod logs;
+pub mod logs;
 mod message_shared_contracts;
 pub mod prometheus_controller;
 pub mod publisher;
 pub mod queues;
+pub mod replication_controller;
 pub mod sessions_controller;
+pub mod settings_controller;
 pub mod status_controller;
 pub mod subscribers_controller;
 pub mod topics_controller;
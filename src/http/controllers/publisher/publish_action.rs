@@ -55,16 +55,21 @@ async fn handle_request(
         messages_to_publish.push(msg);
     }
 
-    crate::operations::publisher::publish(
+    let delay = crate::operations::publisher::publish_with_rate_limit(
         &action.app,
+        http_session.get_session_id(),
+        http_session.name.as_str(),
         http_input.topic_id.as_str(),
         messages_to_publish,
         false,
-        http_session.get_session_id(),
     )
     .await?;
 
     http_session.update_written_amount(content_size);
 
+    if let Some(delay) = delay {
+        tokio::time::sleep(delay).await;
+    }
+
     HttpOutput::Empty.into_ok_result(true).into()
 }
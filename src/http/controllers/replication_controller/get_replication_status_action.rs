@@ -0,0 +1,49 @@
+use super::models::ReplicationStatusModel;
+use crate::{app::AppContext, settings::ReplicationRole};
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use std::sync::Arc;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Replication/Status",
+    description: "Get replication status of the node",
+    summary: "Get replication status of the node",
+    controller: "Replication",
+    result:[
+        {status_code: 200, description: "Replication status", model: ReplicationStatusModel},
+    ]
+)]
+pub struct GetReplicationStatusAction {
+    app: Arc<AppContext>,
+}
+
+impl GetReplicationStatusAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetReplicationStatusAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    let role = match action.app.settings.replication_role {
+        Some(_) if action.app.replication.is_passive() => "Passive",
+        Some(ReplicationRole::Passive) => "Promoted",
+        Some(ReplicationRole::Active) => "Active",
+        None => "Disabled",
+    };
+
+    let response = ReplicationStatusModel {
+        role: role.to_string(),
+        connected_passive_nodes: action.app.replication.get_connected_passive_nodes(),
+        last_frame_received: action
+            .app
+            .replication
+            .get_last_frame_received()
+            .map(|itm| itm.to_rfc3339()),
+    };
+
+    HttpOutput::as_json(response).into_ok_result(true).into()
+}
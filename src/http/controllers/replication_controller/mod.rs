@@ -0,0 +1,5 @@
+mod get_replication_status_action;
+mod models;
+mod promote_action;
+pub use get_replication_status_action::GetReplicationStatusAction;
+pub use promote_action::PromoteAction;
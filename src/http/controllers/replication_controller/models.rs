@@ -0,0 +1,11 @@
+use my_http_server::macros::MyHttpObjectStructure;
+use serde::*;
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct ReplicationStatusModel {
+    pub role: String,
+    #[serde(rename = "connectedPassiveNodes")]
+    pub connected_passive_nodes: usize,
+    #[serde(rename = "lastFrameReceived")]
+    pub last_frame_received: Option<String>,
+}
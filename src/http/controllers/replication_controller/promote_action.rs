@@ -0,0 +1,44 @@
+use crate::app::AppContext;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use std::sync::Arc;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Replication/Promote",
+    description: "Promote passive node to active",
+    summary: "Promotes passive node to active. Make sure active node is stopped",
+    controller: "Replication",
+    result:[
+        {status_code: 202, description: "Node is being promoted"},
+        {status_code: 403, description: "Node is not passive"},
+    ]
+)]
+pub struct PromoteAction {
+    app: Arc<AppContext>,
+}
+
+impl PromoteAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &PromoteAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    if !action.app.replication.promote() {
+        return Err(HttpFailResult::as_forbidden(Some(
+            "Node is not passive".to_string(),
+        )));
+    }
+
+    my_logger::LOGGER.write_info(
+        "Replication",
+        "Node is promoted to active through http".to_string(),
+        my_logger::LogEventCtx::new(),
+    );
+
+    HttpOutput::Empty.into_ok_result(true).into()
+}
@@ -0,0 +1,39 @@
+use crate::app::AppContext;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use std::sync::Arc;
+
+#[my_http_server::macros::http_route(
+    method: "GET",
+    route: "/api/Settings/Effective",
+    description: "Get effective settings",
+    summary: "Settings which are applied at startup after settings file, environment variables and command line flags are merged. Secrets are masked",
+    controller: "Settings",
+    result:[
+        {status_code: 200, description: "Effective settings"},
+    ]
+)]
+pub struct GetEffectiveSettingsAction {
+    app: Arc<AppContext>,
+}
+
+impl GetEffectiveSettingsAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &GetEffectiveSettingsAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    match action.app.settings.get_effective_settings() {
+        Some(effective_settings) => HttpOutput::as_json(effective_settings)
+            .into_ok_result(true)
+            .into(),
+        None => Err(HttpFailResult::as_not_found(
+            "Settings are not read from settings sources".to_string(),
+            false,
+        )),
+    }
+}
@@ -0,0 +1,5 @@
+mod get_effective_settings_action;
+mod models;
+mod reload_action;
+pub use get_effective_settings_action::GetEffectiveSettingsAction;
+pub use reload_action::ReloadAction;
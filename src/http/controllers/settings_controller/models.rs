@@ -0,0 +1,37 @@
+use my_http_server::macros::MyHttpObjectStructure;
+use serde::*;
+
+use crate::operations::reload_settings::SettingsReloadResult;
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct SettingChangeModel {
+    pub name: String,
+    #[serde(rename = "oldValue")]
+    pub old_value: String,
+    #[serde(rename = "newValue")]
+    pub new_value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, MyHttpObjectStructure)]
+pub struct SettingsReloadResultModel {
+    pub applied: Vec<SettingChangeModel>,
+    #[serde(rename = "requiresRestart")]
+    pub requires_restart: Vec<String>,
+}
+
+impl From<SettingsReloadResult> for SettingsReloadResultModel {
+    fn from(src: SettingsReloadResult) -> Self {
+        Self {
+            applied: src
+                .applied
+                .into_iter()
+                .map(|itm| SettingChangeModel {
+                    name: itm.name,
+                    old_value: itm.old_value,
+                    new_value: itm.new_value,
+                })
+                .collect(),
+            requires_restart: src.requires_restart,
+        }
+    }
+}
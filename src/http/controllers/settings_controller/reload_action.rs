@@ -0,0 +1,39 @@
+use super::models::SettingsReloadResultModel;
+use crate::app::AppContext;
+
+use my_http_server::{HttpContext, HttpFailResult, HttpOkResult, HttpOutput};
+use std::sync::Arc;
+
+#[my_http_server::macros::http_route(
+    method: "POST",
+    route: "/api/Settings/Reload",
+    description: "Reload settings file",
+    summary: "Re-reads settings file and applies settings which can be changed without restart",
+    controller: "Settings",
+    result:[
+        {status_code: 200, description: "Applied changes and changes which require restart", model: SettingsReloadResultModel},
+        {status_code: 403, description: "Settings file is not valid. Nothing is applied"},
+    ]
+)]
+pub struct ReloadAction {
+    app: Arc<AppContext>,
+}
+
+impl ReloadAction {
+    pub fn new(app: Arc<AppContext>) -> Self {
+        Self { app }
+    }
+}
+
+async fn handle_request(
+    action: &ReloadAction,
+    _ctx: &mut HttpContext,
+) -> Result<HttpOkResult, HttpFailResult> {
+    match crate::operations::reload_settings::reload_settings(action.app.as_ref()).await {
+        Ok(result) => {
+            let response: SettingsReloadResultModel = result.into();
+            HttpOutput::as_json(response).into_ok_result(true).into()
+        }
+        Err(err) => Err(HttpFailResult::as_forbidden(Some(err))),
+    }
+}
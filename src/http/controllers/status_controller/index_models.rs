@@ -1,6 +1,9 @@
 use std::collections::BTreeMap;
 
-use crate::app::AppContext;
+use crate::{
+    app::{AppContext, ListenerStatus},
+    cluster::ClusterTopology,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +19,87 @@ pub struct SystemStatusModel {
     totalmem: u64,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PersistenceStatusModel {
+    pub degraded: bool,
+    #[serde(rename = "circuitOpen")]
+    pub circuit_open: bool,
+    #[serde(rename = "consecutiveFailures")]
+    pub consecutive_failures: usize,
+    #[serde(rename = "lastError")]
+    pub last_error: Option<String>,
+}
+
+impl PersistenceStatusModel {
+    pub async fn new(app: &AppContext) -> Self {
+        Self {
+            degraded: app.persistence_circuit_breaker.is_degraded(),
+            circuit_open: app.persistence_circuit_breaker.is_open(),
+            consecutive_failures: app.persistence_circuit_breaker.get_consecutive_failures(),
+            last_error: app.persistence_circuit_breaker.get_last_error().await,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListenerStatusModel {
+    pub protocol: String,
+    pub name: String,
+    pub address: String,
+    pub connections: usize,
+    #[serde(rename = "requireAuth")]
+    pub require_auth: bool,
+}
+
+impl ListenerStatusModel {
+    pub fn new(listener: &ListenerStatus) -> Self {
+        Self {
+            protocol: listener.protocol.to_string(),
+            name: listener.name.to_string(),
+            address: listener.address.to_string(),
+            connections: listener.get_connections(),
+            require_auth: listener.require_auth,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClusterNodeModel {
+    pub id: String,
+    #[serde(rename = "tcpAddress")]
+    pub tcp_address: String,
+    #[serde(rename = "httpUrl")]
+    pub http_url: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClusterStatusModel {
+    #[serde(rename = "nodeId")]
+    pub node_id: String,
+    pub nodes: Vec<ClusterNodeModel>,
+    // TopicId -> NodeId of assigned topics and topics of this node
+    #[serde(rename = "knownTopicOwners")]
+    pub known_topic_owners: BTreeMap<String, String>,
+}
+
+impl ClusterStatusModel {
+    pub async fn new(cluster: &ClusterTopology, local_topics: &[String]) -> Self {
+        Self {
+            node_id: cluster.node_id.to_string(),
+            nodes: cluster
+                .nodes
+                .iter()
+                .map(|itm| ClusterNodeModel {
+                    id: itm.id.to_string(),
+                    tcp_address: itm.tcp_address.to_string(),
+                    http_url: itm.http_url.to_string(),
+                })
+                .collect(),
+            known_topic_owners: cluster.get_known_topic_owners(local_topics).await,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct StatusJsonResult {
     pub topics: TopicsJsonResult,
@@ -24,6 +108,10 @@ pub struct StatusJsonResult {
     pub system: SystemStatusModel,
     #[serde(rename = "persistenceVersion")]
     pub persistence_version: String,
+    pub persistence: PersistenceStatusModel,
+    pub listeners: Vec<ListenerStatusModel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<ClusterStatusModel>,
     pub version: String,
 }
 
@@ -54,6 +142,16 @@ impl StatusJsonResult {
             topics.items.push(TopicJsonContract::new(&topic_data));
         }
 
+        let cluster = match &app.cluster {
+            Some(cluster) => {
+                let local_topics: Vec<String> =
+                    topics.items.iter().map(|itm| itm.id.to_string()).collect();
+
+                Some(ClusterStatusModel::new(cluster, &local_topics).await)
+            }
+            None => None,
+        };
+
         Self {
             topics,
             queues,
@@ -63,6 +161,14 @@ impl StatusJsonResult {
                 usedmem: sys_info.used_memory(),
             },
             persistence_version: app.persistence_version.get().await,
+            persistence: PersistenceStatusModel::new(app).await,
+            listeners: app
+                .listeners
+                .get_all()
+                .iter()
+                .map(|itm| ListenerStatusModel::new(itm))
+                .collect(),
+            cluster,
             version: crate::app::APP_VERSION.to_string(),
         }
     }
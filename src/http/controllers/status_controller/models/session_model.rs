@@ -24,10 +24,27 @@ pub struct SessionJsonResult {
     pub read_per_sec: usize,
     #[serde(rename = "writtenPerSec")]
     pub written_per_sec: usize,
+    #[serde(rename = "clientCertificate", skip_serializing_if = "Option::is_none")]
+    pub client_certificate_subject: Option<String>,
+    #[serde(rename = "publishRate", skip_serializing_if = "Option::is_none")]
+    pub publish_rate: Option<SessionPublishRateModel>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SessionPublishRateModel {
+    #[serde(rename = "messagesPerSec")]
+    pub messages_per_sec: usize,
+    #[serde(rename = "bytesPerSec")]
+    pub bytes_per_sec: usize,
+    pub throttled: u64,
+    pub rejected: u64,
 }
 
 impl SessionJsonResult {
-    pub async fn new(session: &Arc<dyn MyServiceBusSession + Send + Sync + 'static>) -> Self {
+    pub async fn new(
+        app: &AppContext,
+        session: &Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
+    ) -> Self {
         let now = DateTimeAsMicroseconds::now();
 
         let session_metrics = session.get_metrics();
@@ -40,6 +57,16 @@ impl SessionJsonResult {
 
         let name_and_version = session.get_name_and_version();
 
+        let publish_rate = app
+            .publish_rate_limiter
+            .get_session_rate(session.get_session_id(), now)
+            .map(|rate| SessionPublishRateModel {
+                messages_per_sec: rate.messages_per_second,
+                bytes_per_sec: rate.bytes_per_second,
+                throttled: rate.throttled,
+                rejected: rate.rejected,
+            });
+
         Self {
             id: session.get_session_id().get_value(),
             ip: session_metrics.ip,
@@ -58,6 +85,8 @@ impl SessionJsonResult {
             written_size: session_metrics.connection_metrics.written,
             read_per_sec: session_metrics.connection_metrics.read_per_sec,
             written_per_sec: session_metrics.connection_metrics.written_per_sec,
+            client_certificate_subject: session_metrics.client_certificate_subject,
+            publish_rate,
         }
     }
 }
@@ -79,7 +108,7 @@ impl SessionsJsonResult {
         };
 
         for session in &all_sessions {
-            let session_json_model = SessionJsonResult::new(session).await;
+            let session_json_model = SessionJsonResult::new(app, session).await;
             result.items.push(session_json_model);
         }
 
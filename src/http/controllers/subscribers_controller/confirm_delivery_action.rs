@@ -44,6 +44,7 @@ async fn handle_request(
                     &confirmation.topic_id,
                     &confirmation.queue_id,
                     confirmation.subscriber_id.into(),
+                    http_session.session_id,
                 )
                 .await?;
             }
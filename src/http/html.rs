@@ -17,3 +17,50 @@ pub fn compile(title: String, body: String) -> Result<HttpOkResult, HttpFailResu
     }
     .into_ok_result(false)
 }
+
+pub fn escape(src: &str) -> String {
+    let mut result = String::with_capacity(src.len());
+
+    for c in src.chars() {
+        match c {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+pub fn encode_url_component(src: &str) -> String {
+    let mut result = String::with_capacity(src.len());
+
+    for b in src.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                result.push(b as char)
+            }
+            _ => result.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_escape_and_encode_url_component() {
+        assert_eq!(
+            "&lt;b&gt;Tom &amp; &quot;Jerry&quot;&lt;/b&gt;",
+            super::escape("<b>Tom & \"Jerry\"</b>")
+        );
+        assert_eq!(
+            "Reload%20Settings%2Fa",
+            super::encode_url_component("Reload Settings/a")
+        );
+    }
+}
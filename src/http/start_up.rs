@@ -1,4 +1,4 @@
-use std::{net::SocketAddr, sync::Arc};
+use std::sync::Arc;
 
 use my_http_server::{HttpConnectionsCounter, MyHttpServer, StaticFilesMiddleware};
 
@@ -9,7 +9,7 @@ use crate::app::AppContext;
 use super::auth::AuthMiddleware;
 
 pub fn setup_server(app: &Arc<AppContext>) -> HttpConnectionsCounter {
-    let mut http_server = MyHttpServer::new(SocketAddr::from(([0, 0, 0, 0], 6123)));
+    let mut http_server = MyHttpServer::new(app.settings.http_address);
 
     let controllers = Arc::new(crate::http::controllers::builder::build(app));
 
@@ -21,7 +21,7 @@ pub fn setup_server(app: &Arc<AppContext>) -> HttpConnectionsCounter {
 
     http_server.add_middleware(Arc::new(swagger_middleware));
 
-    http_server.add_middleware(Arc::new(AuthMiddleware));
+    http_server.add_middleware(Arc::new(AuthMiddleware::new(app.clone())));
 
     http_server.add_middleware(controllers);
 
@@ -1,30 +1,46 @@
-use app::AppContext;
+use app::{
+    AppContext, GRPC_PROTOCOL, MQTT_PROTOCOL, REPLICATION_PROTOCOL, TCP_PROTOCOL, WS_PROTOCOL,
+};
 
 use background::{
-    DeadSubscribersKickerTimer, GcTimer, ImmediatelyPersistEventLoop, MetricsTimer,
-    PersistTopicsAndQueuesTimer,
+    start_settings_reload_signal_listener, ClusterAssignmentTimer, DeadSubscribersKickerTimer,
+    GcTimer, ImmediatelyPersistEventLoop, MemoryBudgetTimer, MetricsTimer,
+    PersistTopicsAndQueuesTimer, TlsCertificatesTimer,
 };
 use my_tcp_sockets::TcpServer;
 use rust_extensions::MyTimer;
-use tcp::socket_events::TcpServerEvents;
+use settings::ReplicationRole;
+use tcp::{
+    socket_events::TcpServerEvents,
+    tls::{TlsPeersList, TlsProxy, TlsServerConfig},
+};
 
 use std::time::Duration;
 use std::{net::SocketAddr, sync::Arc};
 
 mod app;
+mod auth;
 mod avg_value;
+mod cluster;
 mod errors;
 mod grpc_client;
+mod grpc_server;
 mod http;
 mod messages_page;
 mod metric_data;
+mod mqtt;
 mod operations;
 mod queue_subscribers;
 mod queues;
+mod replication;
 mod sessions;
 mod settings;
+mod settings_layers;
 mod tcp;
+mod telemetry;
 mod utils;
+mod webhooks;
+mod ws;
 
 mod background;
 mod topics;
@@ -32,34 +48,149 @@ pub mod persistence_grpc {
     tonic::include_proto!("persistence");
 }
 
+pub mod service_bus_grpc {
+    tonic::include_proto!("service_bus");
+}
+
 #[global_allocator]
 static ALLOC: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
+const INTERNAL_LISTENER_BIND_ATTEMPTS: usize = 5;
+
 #[tokio::main]
 async fn main() {
     let settings = settings::SettingsModel::read().await;
 
     let app = Arc::new(AppContext::new(settings).await);
 
+    my_logger::LOGGER.register_reader(app.logs.clone());
+
+    app.webhooks.start();
+
     app.immediately_persist_event_loop
         .register_event_loop(Arc::new(ImmediatelyPersistEventLoop::new(app.clone())))
         .await;
 
-    tokio::task::spawn(crate::operations::initialization::init(app.clone()));
+    match app.settings.replication_role {
+        // Passive node is initialized by replication when it is promoted
+        Some(ReplicationRole::Passive) => crate::replication::start_passive_node(app.clone()),
+        _ => {
+            tokio::task::spawn(crate::operations::initialization::init(app.clone()));
+        }
+    }
 
-    let tcp_server = TcpServer::new(
-        "MySbTcpServer".to_string(),
-        SocketAddr::from(([0, 0, 0, 0], 6421)),
-    );
+    if let Some(listener) = app
+        .listeners
+        .get(REPLICATION_PROTOCOL, REPLICATION_PROTOCOL)
+    {
+        crate::replication::start_active_node(app.clone(), listener);
+    }
 
-    tcp_server
-        .start(
-            Arc::new(my_service_bus::tcp_contracts::MySbSerializerFactory),
-            Arc::new(TcpServerEvents::new(app.clone())),
-            app.states.clone(),
-            my_logger::LOGGER.clone(),
-        )
-        .await;
+    let mut tcp_threads_statistics = Vec::with_capacity(app.settings.tcp_listeners.len());
+    let mut tls_configs = Vec::new();
+
+    for tcp_listener in &app.settings.tcp_listeners {
+        let listener = app
+            .listeners
+            .get(TCP_PROTOCOL, tcp_listener.name.as_str())
+            .unwrap();
+
+        let tcp_server_name = format!("MySbTcpServer-{}", tcp_listener.name);
+
+        // TLS listener terminates TLS and forwards connections to loopback plain listener
+        let (tcp_server, tcp_server_addr) = match &tcp_listener.tls {
+            Some(tls_settings) => {
+                let tls_config =
+                    TlsServerConfig::new(tcp_listener.name.to_string(), tls_settings.clone())
+                        .unwrap_or_else(|err| {
+                            panic!("Tcp listener {}. {}", tcp_listener.name, err)
+                        });
+
+                let tls_config = Arc::new(tls_config);
+                let tls_peers = Arc::new(TlsPeersList::new());
+                let events = Arc::new(TcpServerEvents::new(
+                    app.clone(),
+                    listener,
+                    Some(tls_peers.clone()),
+                ));
+
+                let mut attempt = 1;
+
+                let (tcp_server, internal_addr) = loop {
+                    let internal_addr = crate::tcp::tls::get_internal_addr();
+
+                    match start_tcp_server(
+                        &app,
+                        tcp_server_name.clone(),
+                        internal_addr,
+                        events.clone(),
+                    )
+                    .await
+                    {
+                        Some(tcp_server) => break (tcp_server, internal_addr),
+                        None if attempt < INTERNAL_LISTENER_BIND_ATTEMPTS => {
+                            println!(
+                                "Tcp listener {}. Loopback port {} is taken. Trying another one",
+                                tcp_listener.name, internal_addr
+                            );
+                            attempt += 1;
+                        }
+                        None => panic!(
+                            "Tcp listener {}. Can not bind loopback port after {} attempts",
+                            tcp_listener.name, attempt
+                        ),
+                    }
+                };
+
+                // Proxy is started only when internal listener is bound, so it never forwards to foreign port
+                TlsProxy {
+                    public_addr: tcp_listener.address,
+                    internal_addr,
+                    config: tls_config.clone(),
+                    peers: tls_peers,
+                }
+                .start(app.clone());
+
+                tls_configs.push(tls_config);
+
+                (tcp_server, internal_addr)
+            }
+            None => {
+                let events = Arc::new(TcpServerEvents::new(app.clone(), listener, None));
+
+                let tcp_server =
+                    start_tcp_server(&app, tcp_server_name, tcp_listener.address, events)
+                        .await
+                        .unwrap_or_else(|| {
+                            panic!(
+                                "Can not start tcp listener {} at {}",
+                                tcp_listener.name, tcp_listener.address
+                            )
+                        });
+
+                (tcp_server, tcp_listener.address)
+            }
+        };
+
+        println!(
+            "Tcp listener {} is started at {}",
+            tcp_listener.name, tcp_server_addr
+        );
+
+        tcp_threads_statistics.push(tcp_server.threads_statistics);
+    }
+
+    if let Some(listener) = app.listeners.get(WS_PROTOCOL, WS_PROTOCOL) {
+        crate::ws::start(app.clone(), listener);
+    }
+
+    if let Some(listener) = app.listeners.get(GRPC_PROTOCOL, GRPC_PROTOCOL) {
+        crate::grpc_server::start(app.clone(), listener);
+    }
+
+    if let Some(listener) = app.listeners.get(MQTT_PROTOCOL, MQTT_PROTOCOL) {
+        crate::mqtt::start(app.clone(), listener);
+    }
 
     let http_connections_counter = crate::http::start_up::setup_server(&app);
 
@@ -69,7 +200,7 @@ async fn main() {
         Arc::new(MetricsTimer::new(
             app.clone(),
             http_connections_counter,
-            tcp_server.threads_statistics,
+            tcp_threads_statistics,
         )),
     );
 
@@ -86,22 +217,83 @@ async fn main() {
         Arc::new(DeadSubscribersKickerTimer::new(app.clone())),
     );
 
+    if let Some(memory_budget) = app.settings.memory_budget {
+        gc_timer.register_timer(
+            "MemoryBudget",
+            Arc::new(MemoryBudgetTimer::new(app.clone(), memory_budget)),
+        );
+    }
+
+    let mut tls_timer = MyTimer::new(Duration::from_secs(10));
+    let has_tls_listeners = !tls_configs.is_empty();
+    tls_timer.register_timer(
+        "TlsCertificates",
+        Arc::new(TlsCertificatesTimer::new(tls_configs)),
+    );
+
+    let mut cluster_timer = MyTimer::new(Duration::from_secs(5));
+    cluster_timer.register_timer(
+        "ClusterAssignment",
+        Arc::new(ClusterAssignmentTimer::new(app.clone())),
+    );
+
     metrics_timer.start(app.clone(), my_logger::LOGGER.clone());
     persist_timer.start(app.clone(), my_logger::LOGGER.clone());
     gc_timer.start(app.clone(), my_logger::LOGGER.clone());
+
+    if app.cluster.is_some() {
+        cluster_timer.start(app.clone(), my_logger::LOGGER.clone());
+    }
+
+    if has_tls_listeners {
+        tls_timer.start(app.clone(), my_logger::LOGGER.clone());
+    }
+
     app.immediately_persist_event_loop.start(app.clone()).await;
 
+    start_settings_reload_signal_listener(app.clone());
+
     app.states.wait_until_shutdown().await;
 
     shut_down_task(app).await;
 }
 
+// Tcp server panics if it can not bind the address. It is started as separate task to catch it
+async fn start_tcp_server(
+    app: &Arc<AppContext>,
+    name: String,
+    addr: SocketAddr,
+    events: Arc<TcpServerEvents>,
+) -> Option<TcpServer> {
+    let app = app.clone();
+
+    tokio::spawn(async move {
+        let tcp_server = TcpServer::new(name, addr);
+
+        tcp_server
+            .start(
+                Arc::new(my_service_bus::tcp_contracts::MySbSerializerFactory),
+                events,
+                app.states.clone(),
+                my_logger::LOGGER.clone(),
+            )
+            .await;
+
+        tcp_server
+    })
+    .await
+    .ok()
+}
+
 async fn shut_down_task(app: Arc<AppContext>) {
     app.states.wait_until_shutdown().await;
 
-    println!("Shut down detected. Waiting for 1 second to deliver all messages");
-    let duration = Duration::from_secs(1);
-    tokio::time::sleep(duration).await;
+    println!(
+        "Shut down detected. New sessions and publishes are rejected. Draining messages on delivery for up to {:?}",
+        app.settings.shutdown_drain_timeout
+    );
+
+    crate::app::shutdown::execute(app.clone()).await;
 
-    crate::app::shutdown::execute(app).await;
+    app.telemetry.shutdown();
 }
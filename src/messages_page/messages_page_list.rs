@@ -1,20 +1,32 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use my_service_bus::abstractions::queue_with_intervals::QueueWithIntervals;
 use my_service_bus::abstractions::MessageId;
 use my_service_bus::shared::{page_id::PageId, sub_page::SubPageId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_extensions::sorted_vec::{GetMutOrCreateEntry, SortedVec};
 
-use super::{ActiveSubPages, MySbMessageContent, PageSizeMetrics, SubPage, SubPageInner};
+use super::{
+    ActiveSubPages, GetMessageResult, MySbMessageContent, PageSizeMetrics, SubPage, SubPageInner,
+};
+
+pub struct SubPageEvictionCandidate {
+    pub sub_page_id: SubPageId,
+    pub last_accessed: DateTimeAsMicroseconds,
+    pub size: usize,
+}
 
 pub struct MessagesPageList {
     pub sub_pages: SortedVec<i64, SubPage>,
+    // Evicted sub pages have all messages persisted, so failed load of them must not make them missing
+    evicted_sub_pages: BTreeSet<i64>,
 }
 
 impl MessagesPageList {
     pub fn new() -> Self {
         Self {
             sub_pages: SortedVec::new(),
+            evicted_sub_pages: BTreeSet::new(),
         }
     }
 
@@ -32,10 +44,32 @@ impl MessagesPageList {
         self.sub_pages.get(sub_page_id.as_ref())
     }
 
+    // None if sub page of the message is not loaded or the message is missing
+    pub fn get_message_time(&self, message_id: MessageId) -> Option<DateTimeAsMicroseconds> {
+        let sub_page_id: SubPageId = message_id.into();
+        let sub_page = self.sub_pages.get(sub_page_id.as_ref())?;
+
+        match sub_page.get_message(message_id) {
+            GetMessageResult::Message(message) => Some(message.time),
+            _ => None,
+        }
+    }
+
     pub fn restore_sub_page(&mut self, sub_page: SubPage) {
+        self.evicted_sub_pages.remove(sub_page.get_id().as_ref());
         self.sub_pages.insert_or_replace(sub_page);
     }
 
+    pub fn is_evicted(&self, sub_page_id: SubPageId) -> bool {
+        self.evicted_sub_pages.contains(sub_page_id.as_ref())
+    }
+
+    fn gc_evicted_sub_pages(&mut self, min_message_id: MessageId) {
+        let min_message_sub_page_id: SubPageId = min_message_id.into();
+        self.evicted_sub_pages
+            .retain(|sub_page_id| *sub_page_id >= min_message_sub_page_id.get_value());
+    }
+
     pub fn delete_sub_page(&mut self, sub_page_id: SubPageId) {
         self.sub_pages.remove(sub_page_id.as_ref());
     }
@@ -52,6 +86,8 @@ impl MessagesPageList {
         for page_id in pages_to_gc {
             self.sub_pages.remove(page_id.as_ref());
         }
+
+        self.gc_evicted_sub_pages(min_message_id);
     }
 
     pub fn gc_messages(&mut self, min_message_id: MessageId, active_sub_pages: &ActiveSubPages) {
@@ -70,6 +106,8 @@ impl MessagesPageList {
         for sub_page_id in pages_to_gc {
             self.sub_pages.remove(sub_page_id.as_ref());
         }
+
+        self.gc_evicted_sub_pages(min_message_id);
     }
 
     pub fn get_sub_pages_to_gc(
@@ -91,6 +129,48 @@ impl MessagesPageList {
         result
     }
 
+    pub fn get_eviction_candidates(
+        &self,
+        active_pages: &ActiveSubPages,
+    ) -> Vec<SubPageEvictionCandidate> {
+        let mut result = Vec::new();
+
+        for sub_page in self.sub_pages.iter() {
+            let sub_page_id = sub_page.get_id();
+            if active_pages.has_sub_page(sub_page_id) {
+                continue;
+            }
+
+            if let Some(size) = sub_page.get_size_to_evict() {
+                result.push(SubPageEvictionCandidate {
+                    sub_page_id,
+                    last_accessed: sub_page.get_last_accessed(),
+                    size,
+                });
+            }
+        }
+
+        result
+    }
+
+    // Sub page is not evicted if it was accessed or got messages to persist after it became a candidate
+    pub fn evict_sub_page(
+        &mut self,
+        sub_page_id: SubPageId,
+        last_accessed: DateTimeAsMicroseconds,
+    ) -> Option<usize> {
+        let sub_page = self.sub_pages.get(sub_page_id.as_ref())?;
+
+        if sub_page.get_last_accessed().unix_microseconds != last_accessed.unix_microseconds {
+            return None;
+        }
+
+        let size = sub_page.get_size_to_evict()?;
+        self.sub_pages.remove(sub_page_id.as_ref());
+        self.evicted_sub_pages.insert(sub_page_id.get_value());
+        Some(size)
+    }
+
     pub fn get_messages_to_persist<TResult>(
         &self,
         result: &mut Vec<(SubPageId, Vec<TResult>)>,
@@ -101,6 +181,16 @@ impl MessagesPageList {
         }
     }
 
+    pub fn get_min_message_id_to_persist(&self) -> Option<MessageId> {
+        for sub_page in self.sub_pages.iter() {
+            if let Some(message_id) = sub_page.get_min_message_id_to_persist() {
+                return Some(message_id);
+            }
+        }
+
+        None
+    }
+
     pub fn get_page_size_metrics(&self) -> BTreeMap<i64, PageSizeMetrics> {
         let mut result: BTreeMap<i64, PageSizeMetrics> = BTreeMap::new();
 
@@ -122,3 +212,87 @@ impl MessagesPageList {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::SbMessageHeaders;
+
+    use super::*;
+
+    fn create_message(id: i64) -> MySbMessageContent {
+        MySbMessageContent {
+            id: id.into(),
+            content: vec![0u8; 10],
+            time: DateTimeAsMicroseconds::now(),
+            headers: SbMessageHeaders::new(),
+        }
+    }
+
+    // Topics which are not persisted are skipped by TopicInner::get_eviction_candidates.
+    // Here false means the message has nothing to persist, true means it is not persisted yet
+    #[test]
+    fn test_only_persisted_not_active_sub_pages_are_evicted() {
+        let mut pages = MessagesPageList::new();
+
+        pages
+            .get_or_create_mut(SubPageId::new(0))
+            .add_message(create_message(0), false);
+        pages
+            .get_or_create_mut(SubPageId::new(1))
+            .add_message(create_message(1000), true);
+        pages
+            .get_or_create_mut(SubPageId::new(2))
+            .add_message(create_message(2000), false);
+
+        let mut active_pages = ActiveSubPages::new();
+        active_pages.add_if_not_exists(SubPageId::new(2));
+
+        let candidates = pages.get_eviction_candidates(&active_pages);
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].sub_page_id.get_value(), 0);
+        assert_eq!(candidates[0].size, 10);
+
+        let last_accessed = candidates[0].last_accessed;
+        pages
+            .get(SubPageId::new(0))
+            .unwrap()
+            .update_last_accessed(DateTimeAsMicroseconds::new(
+                last_accessed.unix_microseconds + 1,
+            ));
+
+        assert!(pages
+            .evict_sub_page(SubPageId::new(0), last_accessed)
+            .is_none());
+
+        let last_accessed = pages.get(SubPageId::new(0)).unwrap().get_last_accessed();
+
+        assert_eq!(
+            pages.evict_sub_page(SubPageId::new(0), last_accessed),
+            Some(10)
+        );
+        assert!(pages.get(SubPageId::new(0)).is_none());
+        assert!(pages.is_evicted(SubPageId::new(0)));
+
+        pages.restore_sub_page(SubPage::create_as_missing(SubPageId::new(0)));
+        assert!(!pages.is_evicted(SubPageId::new(0)));
+    }
+
+    #[test]
+    fn test_evicted_sub_pages_are_gced() {
+        let mut pages = MessagesPageList::new();
+
+        pages
+            .get_or_create_mut(SubPageId::new(0))
+            .add_message(create_message(0), false);
+
+        let last_accessed = pages.get(SubPageId::new(0)).unwrap().get_last_accessed();
+        pages.evict_sub_page(SubPageId::new(0), last_accessed);
+
+        pages.gc_pages(&ActiveSubPages::new(), 999.into());
+        assert!(pages.is_evicted(SubPageId::new(0)));
+
+        pages.gc_pages(&ActiveSubPages::new(), 1000.into());
+        assert!(!pages.is_evicted(SubPageId::new(0)));
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::date_time::{AtomicDateTimeAsMicroseconds, DateTimeAsMicroseconds};
 
@@ -5,6 +7,7 @@ pub struct MissingSubPageInner {
     pub sub_page_id: SubPageId,
     pub created: DateTimeAsMicroseconds,
     pub last_accessed: AtomicDateTimeAsMicroseconds,
+    prefetched: AtomicBool,
 }
 
 impl MissingSubPageInner {
@@ -14,10 +17,19 @@ impl MissingSubPageInner {
             sub_page_id,
             created,
             last_accessed: AtomicDateTimeAsMicroseconds::new(created.unix_microseconds),
+            prefetched: AtomicBool::new(false),
         }
     }
 
     pub fn update_last_accessed(&self, now: DateTimeAsMicroseconds) {
         self.last_accessed.update(now);
     }
+
+    pub fn mark_as_prefetched(&self) {
+        self.prefetched.store(true, Ordering::Relaxed);
+    }
+
+    pub fn take_prefetched(&self) -> bool {
+        self.prefetched.swap(false, Ordering::Relaxed)
+    }
 }
@@ -11,7 +11,7 @@ mod sub_page_inner;
 
 pub use active_sub_pages::*;
 pub use message_content::*;
-pub use messages_page_list::MessagesPageList;
+pub use messages_page_list::{MessagesPageList, SubPageEvictionCandidate};
 pub use messages_to_persist_bucket::MessagesToPersistBucket;
 pub use missing_sub_page_inner::*;
 pub use my_sb_cached_message::*;
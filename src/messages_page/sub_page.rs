@@ -38,6 +38,42 @@ impl SubPage {
             SubPage::AllMessagesMissing(inner) => inner.update_last_accessed(now),
         }
     }
+
+    pub fn mark_as_prefetched(&self) {
+        match self {
+            SubPage::SubPage(inner) => inner.mark_as_prefetched(),
+            SubPage::AllMessagesMissing(inner) => inner.mark_as_prefetched(),
+        }
+    }
+
+    pub fn take_prefetched(&self) -> bool {
+        match self {
+            SubPage::SubPage(inner) => inner.take_prefetched(),
+            SubPage::AllMessagesMissing(inner) => inner.take_prefetched(),
+        }
+    }
+
+    pub fn get_last_accessed(&self) -> DateTimeAsMicroseconds {
+        match self {
+            SubPage::SubPage(inner) => inner.last_accessed.as_date_time(),
+            SubPage::AllMessagesMissing(inner) => inner.last_accessed.as_date_time(),
+        }
+    }
+
+    // Only sub page with all messages persisted can be evicted. It is loaded back from persistence when needed
+    pub fn get_size_to_evict(&self) -> Option<usize> {
+        match self {
+            SubPage::SubPage(inner) => {
+                if inner.has_messages_to_persist() || inner.messages.len() == 0 {
+                    return None;
+                }
+
+                Some(inner.get_size_metrics().data_size)
+            }
+            SubPage::AllMessagesMissing(_) => None,
+        }
+    }
+
     pub fn add_message(&mut self, msg: MySbMessageContent, persist: bool) {
         match self {
             SubPage::SubPage(inner) => {
@@ -80,6 +116,13 @@ impl SubPage {
         }
     }
 
+    pub fn get_min_message_id_to_persist(&self) -> Option<MessageId> {
+        match self {
+            SubPage::SubPage(inner) => inner.get_min_message_id_to_persist(),
+            SubPage::AllMessagesMissing(_) => None,
+        }
+    }
+
     pub fn gc_messages(&mut self, min_message_id: MessageId) {
         match self {
             SubPage::SubPage(inner) => inner.gc_messages(min_message_id),
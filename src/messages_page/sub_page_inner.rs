@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use my_service_bus::abstractions::{queue_with_intervals::QueueWithIntervals, MessageId};
 use my_service_bus::shared::sub_page::{SizeAndAmount, SubPageId};
@@ -35,6 +36,7 @@ pub struct SubPageInner {
     pub messages: SortedVec<MessageId, MySbCachedMessage>,
     pub created: DateTimeAsMicroseconds,
     pub last_accessed: AtomicDateTimeAsMicroseconds,
+    prefetched: AtomicBool,
     size_and_amount: SizeAndAmount,
     to_persist: QueueWithIntervals,
 }
@@ -49,6 +51,7 @@ impl SubPageInner {
             size_and_amount: SizeAndAmount::new(),
             to_persist: QueueWithIntervals::new(),
             last_accessed: AtomicDateTimeAsMicroseconds::new(created.unix_microseconds),
+            prefetched: AtomicBool::new(false),
         }
     }
 
@@ -73,6 +76,7 @@ impl SubPageInner {
             size_and_amount,
             to_persist: QueueWithIntervals::new(),
             last_accessed: AtomicDateTimeAsMicroseconds::new(created.unix_microseconds),
+            prefetched: AtomicBool::new(false),
         }
     }
 
@@ -93,6 +97,15 @@ impl SubPageInner {
         self.last_accessed.update(now);
     }
 
+    pub fn mark_as_prefetched(&self) {
+        self.prefetched.store(true, Ordering::Relaxed);
+    }
+
+    // Returns true only once - on the first delivery from the prefetched sub page
+    pub fn take_prefetched(&self) -> bool {
+        self.prefetched.swap(false, Ordering::Relaxed)
+    }
+
     #[cfg(test)]
     pub fn get_all_messages_as_vec(&self) -> Vec<MySbMessageContent> {
         let mut result = Vec::new();
@@ -209,6 +222,10 @@ impl SubPageInner {
         }
     }
 
+    pub fn get_min_message_id_to_persist(&self) -> Option<MessageId> {
+        MessageId::from_opt_i64(self.to_persist.get_min_id())
+    }
+
     pub fn has_messages_to_persist(&self) -> bool {
         self.to_persist.queue_size() > 0
     }
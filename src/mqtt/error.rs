@@ -0,0 +1,22 @@
+use crate::operations::OperationFailResult;
+
+#[derive(Debug)]
+pub enum MqttError {
+    IoError(std::io::Error),
+    MalformedPacket(String),
+    UnsupportedPacket(u8),
+    ConnectIsNotDone,
+    OperationFailResult(OperationFailResult),
+}
+
+impl From<std::io::Error> for MqttError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
+
+impl From<OperationFailResult> for MqttError {
+    fn from(src: OperationFailResult) -> Self {
+        Self::OperationFailResult(src)
+    }
+}
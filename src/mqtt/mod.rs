@@ -0,0 +1,5 @@
+mod error;
+pub use error::*;
+mod mqtt_server;
+pub use mqtt_server::start;
+pub mod packets;
@@ -0,0 +1,403 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use my_logger::LogEventCtx;
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, subscriber::TopicQueueType, SbMessageHeaders,
+};
+use tokio::{
+    io::AsyncWriteExt,
+    net::{tcp::OwnedWriteHalf, TcpListener, TcpStream},
+    sync::mpsc::UnboundedReceiver,
+};
+
+use crate::{
+    app::{AppContext, ListenerStatus},
+    operations,
+    sessions::{
+        mqtt::{MqttOutgoing, MyServiceBusMqttSession},
+        MyServiceBusSession,
+    },
+};
+
+use super::{packets::*, MqttError};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+pub fn start(app: Arc<AppContext>, listener_status: Arc<ListenerStatus>) {
+    tokio::spawn(accept_loop(app, listener_status));
+}
+
+async fn accept_loop(app: Arc<AppContext>, listener_status: Arc<ListenerStatus>) {
+    let addr = listener_status.address;
+
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            panic!("Can not start MQTT server at {}. Err: {:?}", addr, err);
+        }
+    };
+
+    println!("MQTT server is started at {}", addr);
+
+    while !app.states.is_shutting_down() {
+        match listener.accept().await {
+            Ok((stream, peer_addr)) => {
+                let app = app.clone();
+                let listener_status = listener_status.clone();
+
+                tokio::spawn(async move {
+                    listener_status.connected();
+                    handle_connection(app, listener_status.clone(), stream, peer_addr).await;
+                    listener_status.disconnected();
+                });
+            }
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "MQTT Accept".to_string(),
+                    format!("{:?}", err),
+                    LogEventCtx::new(),
+                );
+
+                tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+            }
+        }
+    }
+}
+
+async fn handle_connection(
+    app: Arc<AppContext>,
+    listener_status: Arc<ListenerStatus>,
+    stream: TcpStream,
+    peer_addr: SocketAddr,
+) {
+    let (mut read, mut write) = stream.into_split();
+
+    let (client_id, keep_alive, identity) =
+        match tokio::time::timeout(CONNECT_TIMEOUT, read_packet(&mut read)).await {
+            Ok(Ok(MqttPacket::Connect {
+                protocol_level,
+                client_id,
+                clean_session,
+                keep_alive,
+                user_name,
+                password,
+            })) => {
+                if protocol_level != 3 && protocol_level != 4 {
+                    let _ = write
+                        .write_all(&compile_connack(CONNACK_UNACCEPTABLE_PROTOCOL_VERSION))
+                        .await;
+                    return;
+                }
+
+                if app.states.is_shutting_down() {
+                    let _ = write
+                        .write_all(&compile_connack(CONNACK_SERVER_UNAVAILABLE))
+                        .await;
+                    return;
+                }
+
+                if client_id.is_empty() && !clean_session {
+                    let _ = write
+                        .write_all(&compile_connack(CONNACK_IDENTIFIER_REJECTED))
+                        .await;
+                    return;
+                }
+
+                // Password is the credential. User name is the name application reports about itself
+                let identity = match operations::authenticate_session(
+                    app.as_ref(),
+                    listener_status.as_ref(),
+                    user_name.as_deref().unwrap_or(client_id.as_str()),
+                    password.as_deref(),
+                    peer_addr.to_string().as_str(),
+                )
+                .await
+                {
+                    Ok(identity) => identity,
+                    Err(_) => {
+                        let _ = write
+                            .write_all(&compile_connack(CONNACK_BAD_USER_NAME_OR_PASSWORD))
+                            .await;
+                        return;
+                    }
+                };
+
+                if client_id.is_empty() {
+                    (uuid::Uuid::new_v4().to_string(), keep_alive, identity)
+                } else {
+                    (client_id, keep_alive, identity)
+                }
+            }
+            Ok(Ok(_)) => {
+                write_connection_error(&peer_addr, MqttError::ConnectIsNotDone);
+                return;
+            }
+            Ok(Err(err)) => {
+                write_connection_error(&peer_addr, err);
+                return;
+            }
+            Err(_) => {
+                println!(
+                    "MQTT connection {} has not sent CONNECT packet within {:?}. Disconnecting",
+                    peer_addr, CONNECT_TIMEOUT
+                );
+                return;
+            }
+        };
+
+    let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    tokio::spawn(write_loop(app.clone(), write, receiver));
+
+    let session = app
+        .sessions
+        .add_mqtt(client_id, peer_addr.to_string(), sender, identity)
+        .await;
+
+    session.send_packet(compile_connack(CONNACK_ACCEPTED));
+
+    let read_timeout = if keep_alive > 0 {
+        Some(Duration::from_millis(keep_alive as u64 * 1500))
+    } else {
+        None
+    };
+
+    loop {
+        let packet = match read_timeout {
+            Some(read_timeout) => {
+                match tokio::time::timeout(read_timeout, read_packet(&mut read)).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        println!(
+                            "MQTT connection {} has no incoming traffic for {:?}. Disconnecting",
+                            peer_addr, read_timeout
+                        );
+                        break;
+                    }
+                }
+            }
+            None => read_packet(&mut read).await,
+        };
+
+        let packet = match packet {
+            Ok(packet) => packet,
+            Err(MqttError::IoError(_)) => break,
+            Err(err) => {
+                write_connection_error(&peer_addr, err);
+                break;
+            }
+        };
+
+        if let MqttPacket::Disconnect = &packet {
+            break;
+        }
+
+        if let Err(err) = handle_packet(&app, &session, packet).await {
+            write_connection_error(&peer_addr, err);
+            break;
+        }
+    }
+
+    session.disconnect().await;
+
+    if let Some(session) = app.sessions.remove_by_session_id(session.session_id).await {
+        crate::operations::sessions::disconnect(app.as_ref(), session).await;
+    }
+}
+
+async fn handle_packet(
+    app: &Arc<AppContext>,
+    session: &Arc<MyServiceBusMqttSession>,
+    packet: MqttPacket,
+) -> Result<(), MqttError> {
+    match packet {
+        MqttPacket::Connect { .. } => {
+            return Err(MqttError::MalformedPacket(
+                "CONNECT packet is already received".to_string(),
+            ));
+        }
+        MqttPacket::Publish {
+            topic_name,
+            qos,
+            packet_id,
+            payload,
+        } => {
+            session.update_read_amount(payload.len());
+
+            let message = MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: payload,
+            };
+
+            let delay = operations::publisher::publish_with_rate_limit(
+                app,
+                session.session_id,
+                session
+                    .identity
+                    .as_deref()
+                    .unwrap_or(session.client_id.as_str()),
+                topic_name.as_str(),
+                vec![message],
+                false,
+            )
+            .await?;
+
+            // Acknowledgements must be sent in order, so throttled publisher waits before next packet is read
+            if let Some(delay) = delay {
+                tokio::time::sleep(delay).await;
+            }
+
+            match (qos, packet_id) {
+                (1, Some(packet_id)) => session.send_packet(compile_puback(packet_id)),
+                (2, Some(packet_id)) => session.send_packet(compile_pubrec(packet_id)),
+                _ => {}
+            }
+        }
+        MqttPacket::PubAck { packet_id } => {
+            if let Some(confirmed) = session.on_puback(packet_id) {
+                operations::delivery_confirmation::all_confirmed(
+                    app,
+                    confirmed.topic_id.as_str(),
+                    confirmed.queue_id.as_str(),
+                    confirmed.subscriber_id,
+                    session.session_id,
+                )
+                .await?;
+            }
+        }
+        // Outgoing deliveries are granted QoS 1 at most, so there is no QoS 2 flow to confirm
+        MqttPacket::PubRec { .. } | MqttPacket::PubComp { .. } => {}
+        MqttPacket::PubRel { packet_id } => {
+            session.send_packet(compile_pubcomp(packet_id));
+        }
+        MqttPacket::Subscribe { packet_id, topics } => {
+            let mut return_codes = Vec::with_capacity(topics.len());
+
+            for (topic_filter, qos) in topics {
+                if topic_filter.contains('#') || topic_filter.contains('+') {
+                    return_codes.push(SUBACK_FAILURE);
+                    continue;
+                }
+
+                let queue_type = if qos == 0 {
+                    TopicQueueType::DeleteOnDisconnect
+                } else {
+                    TopicQueueType::Permanent
+                };
+
+                let result = operations::subscriber::subscribe_to_queue(
+                    app,
+                    topic_filter.to_string(),
+                    session.client_id.to_string(),
+                    queue_type,
+                    session.clone(),
+                )
+                .await;
+
+                match result {
+                    Ok(_) => {
+                        let granted_qos = qos.min(1);
+                        session.set_subscription(topic_filter.as_str(), granted_qos);
+                        return_codes.push(granted_qos);
+                    }
+                    Err(err) => {
+                        my_logger::LOGGER.write_error(
+                            "MQTT Subscribe".to_string(),
+                            format!("{:?}", err),
+                            LogEventCtx::new()
+                                .add("topicId", topic_filter)
+                                .add("clientId", session.client_id.to_string()),
+                        );
+                        return_codes.push(SUBACK_FAILURE);
+                    }
+                }
+            }
+
+            session.send_packet(compile_suback(packet_id, &return_codes));
+        }
+        MqttPacket::Unsubscribe { packet_id, topics } => {
+            for topic_filter in topics {
+                session.remove_subscription(topic_filter.as_str());
+
+                if let Err(err) = operations::subscriber::unsubscribe_from_queue(
+                    app,
+                    topic_filter.as_str(),
+                    session.client_id.as_str(),
+                    session.session_id,
+                )
+                .await
+                {
+                    my_logger::LOGGER.write_error(
+                        "MQTT Unsubscribe".to_string(),
+                        format!("{:?}", err),
+                        LogEventCtx::new()
+                            .add("topicId", topic_filter)
+                            .add("clientId", session.client_id.to_string()),
+                    );
+                }
+            }
+
+            session.send_packet(compile_unsuback(packet_id));
+        }
+        MqttPacket::PingReq => {
+            session.send_packet(compile_pingresp());
+        }
+        MqttPacket::Disconnect => {}
+    }
+
+    Ok(())
+}
+
+async fn write_loop(
+    app: Arc<AppContext>,
+    mut write: OwnedWriteHalf,
+    mut receiver: UnboundedReceiver<MqttOutgoing>,
+) {
+    while let Some(outgoing) = receiver.recv().await {
+        match outgoing {
+            MqttOutgoing::Packet(packet) => {
+                if write.write_all(&packet).await.is_err() {
+                    break;
+                }
+            }
+            MqttOutgoing::ConfirmDelivered {
+                topic_id,
+                queue_id,
+                subscriber_id,
+                session_id,
+            } => {
+                if let Err(err) = operations::delivery_confirmation::all_confirmed(
+                    &app,
+                    topic_id.as_str(),
+                    queue_id.as_str(),
+                    subscriber_id,
+                    session_id,
+                )
+                .await
+                {
+                    my_logger::LOGGER.write_error(
+                        "MQTT Confirm Delivery".to_string(),
+                        format!("{:?}", err),
+                        LogEventCtx::new()
+                            .add("topicId", topic_id)
+                            .add("queueId", queue_id),
+                    );
+                }
+            }
+            MqttOutgoing::Disconnect => {
+                break;
+            }
+        }
+    }
+
+    let _ = write.shutdown().await;
+}
+
+fn write_connection_error(peer_addr: &SocketAddr, err: MqttError) {
+    my_logger::LOGGER.write_error(
+        "Handle MQTT Packet".to_string(),
+        format!("{:?}", err),
+        LogEventCtx::new().add("ip", peer_addr.to_string()),
+    );
+}
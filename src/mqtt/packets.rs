@@ -0,0 +1,418 @@
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::MqttError;
+
+pub const CONNECT: u8 = 1;
+pub const CONNACK: u8 = 2;
+pub const PUBLISH: u8 = 3;
+pub const PUBACK: u8 = 4;
+pub const PUBREC: u8 = 5;
+pub const PUBREL: u8 = 6;
+pub const PUBCOMP: u8 = 7;
+pub const SUBSCRIBE: u8 = 8;
+pub const SUBACK: u8 = 9;
+pub const UNSUBSCRIBE: u8 = 10;
+pub const UNSUBACK: u8 = 11;
+pub const PINGREQ: u8 = 12;
+pub const PINGRESP: u8 = 13;
+pub const DISCONNECT: u8 = 14;
+
+pub const CONNACK_ACCEPTED: u8 = 0;
+pub const CONNACK_UNACCEPTABLE_PROTOCOL_VERSION: u8 = 1;
+pub const CONNACK_IDENTIFIER_REJECTED: u8 = 2;
+pub const CONNACK_SERVER_UNAVAILABLE: u8 = 3;
+pub const CONNACK_BAD_USER_NAME_OR_PASSWORD: u8 = 4;
+
+const CONNECT_FLAG_WILL: u8 = 0x04;
+const CONNECT_FLAG_PASSWORD: u8 = 0x40;
+const CONNECT_FLAG_USER_NAME: u8 = 0x80;
+
+pub const SUBACK_FAILURE: u8 = 0x80;
+
+const MAX_PACKET_SIZE: usize = 1024 * 1024 * 4;
+
+pub enum MqttPacket {
+    Connect {
+        protocol_level: u8,
+        client_id: String,
+        clean_session: bool,
+        keep_alive: u16,
+        user_name: Option<String>,
+        password: Option<String>,
+    },
+    Publish {
+        topic_name: String,
+        qos: u8,
+        packet_id: Option<u16>,
+        payload: Vec<u8>,
+    },
+    PubAck {
+        packet_id: u16,
+    },
+    PubRec {
+        packet_id: u16,
+    },
+    PubRel {
+        packet_id: u16,
+    },
+    PubComp {
+        packet_id: u16,
+    },
+    Subscribe {
+        packet_id: u16,
+        topics: Vec<(String, u8)>,
+    },
+    Unsubscribe {
+        packet_id: u16,
+        topics: Vec<String>,
+    },
+    PingReq,
+    Disconnect,
+}
+
+struct BodyReader {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+impl BodyReader {
+    fn new(data: Vec<u8>) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn has_more(&self) -> bool {
+        self.pos < self.data.len()
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&[u8], MqttError> {
+        if self.pos + len > self.data.len() {
+            return Err(MqttError::MalformedPacket(format!(
+                "Packet is too short. Need {} bytes at position {}. Packet size: {}",
+                len,
+                self.pos,
+                self.data.len()
+            )));
+        }
+
+        let result = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(result)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, MqttError> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, MqttError> {
+        let slice = self.read_slice(2)?;
+        Ok(u16::from_be_bytes([slice[0], slice[1]]))
+    }
+
+    fn read_string(&mut self) -> Result<String, MqttError> {
+        let len = self.read_u16()? as usize;
+        let slice = self.read_slice(len)?;
+        match std::str::from_utf8(slice) {
+            Ok(result) => Ok(result.to_string()),
+            Err(err) => Err(MqttError::MalformedPacket(format!(
+                "Invalid utf8 string. Err: {}",
+                err
+            ))),
+        }
+    }
+
+    fn skip_binary(&mut self) -> Result<(), MqttError> {
+        let len = self.read_u16()? as usize;
+        self.read_slice(len)?;
+        Ok(())
+    }
+
+    fn read_to_end(&mut self) -> Vec<u8> {
+        let result = self.data[self.pos..].to_vec();
+        self.pos = self.data.len();
+        result
+    }
+}
+
+pub async fn read_packet<TReader: AsyncRead + Unpin>(
+    reader: &mut TReader,
+) -> Result<MqttPacket, MqttError> {
+    let header = reader.read_u8().await?;
+
+    let mut remaining_length: usize = 0;
+    let mut multiplier: usize = 1;
+
+    loop {
+        let encoded_byte = reader.read_u8().await?;
+        remaining_length += (encoded_byte & 0x7F) as usize * multiplier;
+
+        if encoded_byte & 0x80 == 0 {
+            break;
+        }
+
+        multiplier *= 128;
+
+        if multiplier > 128 * 128 * 128 {
+            return Err(MqttError::MalformedPacket(
+                "Malformed remaining length".to_string(),
+            ));
+        }
+    }
+
+    if remaining_length > MAX_PACKET_SIZE {
+        return Err(MqttError::MalformedPacket(format!(
+            "Packet size {} exceeds max packet size {}",
+            remaining_length, MAX_PACKET_SIZE
+        )));
+    }
+
+    let mut body = vec![0u8; remaining_length];
+    reader.read_exact(&mut body).await?;
+
+    parse_packet(header, body)
+}
+
+fn parse_packet(header: u8, body: Vec<u8>) -> Result<MqttPacket, MqttError> {
+    let packet_type = header >> 4;
+    let flags = header & 0x0F;
+
+    let mut reader = BodyReader::new(body);
+
+    let result = match packet_type {
+        CONNECT => {
+            let _protocol_name = reader.read_string()?;
+            let protocol_level = reader.read_u8()?;
+            let connect_flags = reader.read_u8()?;
+            let keep_alive = reader.read_u16()?;
+            let client_id = reader.read_string()?;
+
+            // Will topic and will message are not supported, so they are skipped
+            if connect_flags & CONNECT_FLAG_WILL > 0 {
+                reader.skip_binary()?;
+                reader.skip_binary()?;
+            }
+
+            let user_name = if connect_flags & CONNECT_FLAG_USER_NAME > 0 {
+                Some(reader.read_string()?)
+            } else {
+                None
+            };
+
+            let password = if connect_flags & CONNECT_FLAG_PASSWORD > 0 {
+                Some(reader.read_string()?)
+            } else {
+                None
+            };
+
+            MqttPacket::Connect {
+                protocol_level,
+                client_id,
+                clean_session: connect_flags & 0x02 > 0,
+                keep_alive,
+                user_name,
+                password,
+            }
+        }
+        PUBLISH => {
+            let qos = (flags >> 1) & 0x03;
+            let topic_name = reader.read_string()?;
+
+            let packet_id = if qos > 0 {
+                Some(reader.read_u16()?)
+            } else {
+                None
+            };
+
+            MqttPacket::Publish {
+                topic_name,
+                qos,
+                packet_id,
+                payload: reader.read_to_end(),
+            }
+        }
+        PUBACK => MqttPacket::PubAck {
+            packet_id: reader.read_u16()?,
+        },
+        PUBREC => MqttPacket::PubRec {
+            packet_id: reader.read_u16()?,
+        },
+        PUBREL => MqttPacket::PubRel {
+            packet_id: reader.read_u16()?,
+        },
+        PUBCOMP => MqttPacket::PubComp {
+            packet_id: reader.read_u16()?,
+        },
+        SUBSCRIBE => {
+            let packet_id = reader.read_u16()?;
+            let mut topics = Vec::new();
+
+            while reader.has_more() {
+                let topic_filter = reader.read_string()?;
+                let qos = reader.read_u8()? & 0x03;
+                topics.push((topic_filter, qos));
+            }
+
+            MqttPacket::Subscribe { packet_id, topics }
+        }
+        UNSUBSCRIBE => {
+            let packet_id = reader.read_u16()?;
+            let mut topics = Vec::new();
+
+            while reader.has_more() {
+                topics.push(reader.read_string()?);
+            }
+
+            MqttPacket::Unsubscribe { packet_id, topics }
+        }
+        PINGREQ => MqttPacket::PingReq,
+        DISCONNECT => MqttPacket::Disconnect,
+        _ => {
+            return Err(MqttError::UnsupportedPacket(packet_type));
+        }
+    };
+
+    Ok(result)
+}
+
+fn write_remaining_length(dest: &mut Vec<u8>, mut len: usize) {
+    loop {
+        let mut encoded_byte = (len % 128) as u8;
+        len /= 128;
+
+        if len > 0 {
+            encoded_byte |= 0x80;
+        }
+
+        dest.push(encoded_byte);
+
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+fn compile_packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(body.len() + 5);
+    result.push(header);
+    write_remaining_length(&mut result, body.len());
+    result.extend_from_slice(body);
+    result
+}
+
+pub fn compile_connack(return_code: u8) -> Vec<u8> {
+    compile_packet(CONNACK << 4, &[0, return_code])
+}
+
+pub fn compile_publish(
+    topic_name: &str,
+    qos: u8,
+    packet_id: Option<u16>,
+    payload: &[u8],
+) -> Vec<u8> {
+    let mut body = Vec::with_capacity(topic_name.len() + payload.len() + 4);
+    body.extend_from_slice(&(topic_name.len() as u16).to_be_bytes());
+    body.extend_from_slice(topic_name.as_bytes());
+
+    if let Some(packet_id) = packet_id {
+        body.extend_from_slice(&packet_id.to_be_bytes());
+    }
+
+    body.extend_from_slice(payload);
+
+    compile_packet((PUBLISH << 4) | (qos << 1), &body)
+}
+
+pub fn compile_puback(packet_id: u16) -> Vec<u8> {
+    compile_packet(PUBACK << 4, &packet_id.to_be_bytes())
+}
+
+pub fn compile_pubrec(packet_id: u16) -> Vec<u8> {
+    compile_packet(PUBREC << 4, &packet_id.to_be_bytes())
+}
+
+pub fn compile_pubcomp(packet_id: u16) -> Vec<u8> {
+    compile_packet(PUBCOMP << 4, &packet_id.to_be_bytes())
+}
+
+pub fn compile_suback(packet_id: u16, return_codes: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(return_codes.len() + 2);
+    body.extend_from_slice(&packet_id.to_be_bytes());
+    body.extend_from_slice(return_codes);
+    compile_packet(SUBACK << 4, &body)
+}
+
+pub fn compile_unsuback(packet_id: u16) -> Vec<u8> {
+    compile_packet(UNSUBACK << 4, &packet_id.to_be_bytes())
+}
+
+pub fn compile_pingresp() -> Vec<u8> {
+    compile_packet(PINGRESP << 4, &[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_string(dest: &mut Vec<u8>, value: &str) {
+        dest.extend_from_slice(&(value.len() as u16).to_be_bytes());
+        dest.extend_from_slice(value.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_publish_round_trip() {
+        let packet = compile_publish("test-topic", 1, Some(5), &[1, 2, 3]);
+
+        let mut reader = packet.as_slice();
+
+        match read_packet(&mut reader).await.unwrap() {
+            MqttPacket::Publish {
+                topic_name,
+                qos,
+                packet_id,
+                payload,
+            } => {
+                assert_eq!("test-topic", topic_name);
+                assert_eq!(1, qos);
+                assert_eq!(Some(5), packet_id);
+                assert_eq!(vec![1u8, 2, 3], payload);
+            }
+            _ => panic!("Publish packet is expected"),
+        }
+    }
+
+    #[test]
+    fn test_connect_with_will_user_name_and_password() {
+        let mut body = Vec::new();
+        write_string(&mut body, "MQTT");
+        body.push(4);
+        body.push(0x02 | CONNECT_FLAG_WILL | CONNECT_FLAG_USER_NAME | CONNECT_FLAG_PASSWORD);
+        body.extend_from_slice(&60u16.to_be_bytes());
+        write_string(&mut body, "test-queue");
+        write_string(&mut body, "will-topic");
+        write_string(&mut body, "will-message");
+        write_string(&mut body, "test-app");
+        write_string(&mut body, "secret-key");
+
+        match parse_packet(CONNECT << 4, body).unwrap() {
+            MqttPacket::Connect {
+                client_id,
+                keep_alive,
+                user_name,
+                password,
+                ..
+            } => {
+                assert_eq!("test-queue", client_id);
+                assert_eq!(60, keep_alive);
+                assert_eq!(Some("test-app".to_string()), user_name);
+                assert_eq!(Some("secret-key".to_string()), password);
+            }
+            _ => panic!("Connect packet is expected"),
+        }
+    }
+
+    #[test]
+    fn test_remaining_length_encoding() {
+        let mut result = Vec::new();
+        write_remaining_length(&mut result, 321);
+        assert_eq!(vec![0xC1, 0x02], result);
+    }
+}
@@ -0,0 +1,47 @@
+use my_logger::LogEventCtx;
+
+use crate::{
+    app::{AppContext, ListenerStatus},
+    auth::AuthFailReason,
+};
+
+// Returns identity of api key if listener requires authentication
+pub async fn authenticate_session(
+    app: &AppContext,
+    listener: &ListenerStatus,
+    reported_name: &str,
+    credential: Option<&str>,
+    ip: &str,
+) -> Result<Option<String>, AuthFailReason> {
+    let auth = match &app.auth {
+        Some(auth) if listener.require_auth => auth,
+        _ => return Ok(None),
+    };
+
+    match auth.authenticate(credential).await {
+        Ok(identity) => {
+            my_logger::LOGGER.write_info(
+                "Authentication",
+                format!("Application is authenticated as {}", identity),
+                LogEventCtx::new()
+                    .add("reportedName", reported_name)
+                    .add("listener", listener.name.as_str())
+                    .add("ip", ip),
+            );
+
+            Ok(Some(identity))
+        }
+        Err(err) => {
+            my_logger::LOGGER.write_error(
+                "Authentication",
+                format!("Application is not authenticated. {:?}", err),
+                LogEventCtx::new()
+                    .add("reportedName", reported_name)
+                    .add("listener", listener.name.as_str())
+                    .add("ip", ip),
+            );
+
+            Err(err)
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use my_logger::LogEventCtx;
+
+use crate::{
+    app::AppContext,
+    auth::{AclPermission, ANONYMOUS_ACL_IDENTITY},
+    sessions::SessionId,
+};
+
+use super::OperationFailResult;
+
+pub async fn check_session_permission(
+    app: &AppContext,
+    session_id: SessionId,
+    permission: AclPermission,
+    name: &str,
+) -> Result<(), OperationFailResult> {
+    if app.topic_acl.is_none() {
+        return Ok(());
+    }
+
+    let identity = app.sessions.get_identity(session_id).await;
+
+    check_permission(app, session_id, identity.as_deref(), permission, name)
+}
+
+// Sessions without identity come through listeners which do not require authentication.
+// They have only permissions of anonymous entry, so they are denied if it is not listed
+pub fn check_permission(
+    app: &AppContext,
+    session_id: SessionId,
+    identity: Option<&str>,
+    permission: AclPermission,
+    name: &str,
+) -> Result<(), OperationFailResult> {
+    let topic_acl = match &app.topic_acl {
+        Some(topic_acl) => topic_acl,
+        None => return Ok(()),
+    };
+
+    let identity = identity.unwrap_or(ANONYMOUS_ACL_IDENTITY);
+
+    if topic_acl.is_allowed(identity, permission, name) {
+        return Ok(());
+    }
+
+    my_logger::LOGGER.write_error(
+        "Authorization",
+        format!(
+            "Identity {} has no {} permission for {}",
+            identity,
+            permission.as_str(),
+            name
+        ),
+        LogEventCtx::new()
+            .add("sessionId", session_id.get_value().to_string())
+            .add("identity", identity)
+            .add("permission", permission.as_str())
+            .add("name", name),
+    );
+
+    app.prometheus
+        .mark_acl_denied(identity, permission.as_str());
+
+    Err(OperationFailResult::AccessDenied {
+        identity: identity.to_string(),
+        permission,
+        name: name.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::AppContext,
+        auth::{AclPermission, ANONYMOUS_ACL_IDENTITY},
+        sessions::SessionId,
+        settings::{SettingsModel, TopicAclSettings},
+    };
+
+    fn create_acl_item(identity: &str, publish: &str) -> TopicAclSettings {
+        TopicAclSettings {
+            identity: identity.to_string(),
+            publish: Some(vec![publish.to_string()]),
+            subscribe: None,
+            create_queues: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_without_identity_is_denied() {
+        let mut settings = SettingsModel::create_test_settings(16);
+        settings.topic_acl = Some(vec![create_acl_item("orders-service", "orders")]);
+
+        let app = AppContext::new(settings).await;
+
+        let result = super::check_permission(
+            &app,
+            SessionId::new(1),
+            None,
+            AclPermission::Publish,
+            "orders",
+        );
+        assert!(result.is_err());
+
+        let result = super::check_permission(
+            &app,
+            SessionId::new(1),
+            Some("orders-service"),
+            AclPermission::Publish,
+            "orders",
+        );
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_session_without_identity_gets_anonymous_permissions() {
+        let mut settings = SettingsModel::create_test_settings(16);
+        settings.topic_acl = Some(vec![create_acl_item(ANONYMOUS_ACL_IDENTITY, "metrics-*")]);
+
+        let app = AppContext::new(settings).await;
+
+        let result = super::check_permission(
+            &app,
+            SessionId::new(1),
+            None,
+            AclPermission::Publish,
+            "metrics-cpu",
+        );
+        assert!(result.is_ok());
+
+        let result = super::check_permission(
+            &app,
+            SessionId::new(1),
+            None,
+            AclPermission::Publish,
+            "orders",
+        );
+        assert!(result.is_err());
+    }
+}
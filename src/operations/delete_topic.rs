@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::app::AppContext;
+use crate::{app::AppContext, webhooks::WebhookEvent};
 
 use super::OperationFailResult;
 
@@ -25,6 +25,15 @@ pub async fn delete_topic(
 
     app.topic_list.delete_topic(topic_id).await;
 
+    app.webhooks.publish(WebhookEvent::TopicDeleted {
+        topic_id: topic_id.to_string(),
+    });
+
+    if let Some(messages_wal) = &app.messages_wal {
+        // Messages of deleted topic are not going to be persisted
+        messages_wal.confirm_persisted(topic_id, i64::MAX).await;
+    }
+
     let mut reusable_topics = crate::topics::ReusableTopicsList::new();
     crate::operations::persist_topics_and_queues(app, &mut reusable_topics).await;
 
@@ -1,4 +1,4 @@
-use my_service_bus::abstractions::AsMessageId;
+use my_service_bus::abstractions::{AsMessageId, MessageId};
 use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::{date_time::DateTimeAsMicroseconds, StopWatch};
 
@@ -21,12 +21,25 @@ pub fn try_to_deliver_to_subscribers(
     topic: &Arc<Topic>,
     topic_data: &mut TopicInner,
 ) {
+    // Shutdown drains messages which are already on delivery and does not start new deliveries
+    if app.states.is_shutting_down() {
+        return;
+    }
+
     let mut sw = StopWatch::new();
     sw.start();
     let mut to_send = Vec::new();
+    let topic_message_id = topic_data.message_id;
 
     for topic_queue in topic_data.queues.get_all_mut() {
-        compile_packages(app, topic, &mut to_send, topic_queue, &topic_data.pages);
+        compile_packages(
+            app,
+            topic,
+            &mut to_send,
+            topic_queue,
+            &topic_data.pages,
+            topic_message_id,
+        );
     }
 
     sw.pause();
@@ -34,6 +47,7 @@ pub fn try_to_deliver_to_subscribers(
     if to_send.len() > 0 {
         for package_builder in to_send {
             crate::operations::send_package::send_new_messages_to_deliver(
+                app,
                 package_builder,
                 topic_data,
                 sw.duration(),
@@ -48,12 +62,25 @@ pub async fn try_to_deliver_to_subscribers(
     topic: &Arc<Topic>,
     topic_data: &mut TopicInner,
 ) {
+    // Shutdown drains messages which are already on delivery and does not start new deliveries
+    if app.states.is_shutting_down() {
+        return;
+    }
+
     let mut sw = StopWatch::new();
     sw.start();
     let mut to_send = Vec::new();
+    let topic_message_id = topic_data.message_id;
 
     for topic_queue in topic_data.queues.get_all_mut() {
-        compile_packages(app, topic, &mut to_send, topic_queue, &topic_data.pages);
+        compile_packages(
+            app,
+            topic,
+            &mut to_send,
+            topic_queue,
+            &topic_data.pages,
+            topic_message_id,
+        );
     }
 
     sw.pause();
@@ -61,6 +88,7 @@ pub async fn try_to_deliver_to_subscribers(
     if to_send.len() > 0 {
         for package_builder in to_send {
             crate::operations::send_package::send_new_messages_to_deliver(
+                app,
                 package_builder,
                 topic_data,
                 sw.duration(),
@@ -76,6 +104,7 @@ fn compile_packages(
     to_send: &mut Vec<SubscriberPackageBuilder>,
     topic_queue: &mut TopicQueue,
     pages: &MessagesPageList,
+    topic_message_id: MessageId,
 ) {
     let mut not_engaged_topics = Vec::new();
 
@@ -90,9 +119,15 @@ fn compile_packages(
 
         let (subscriber_id, session) = subscriber.unwrap();
 
-        if let Some(package_builder) =
-            compile_package(app, topic, topic_queue, pages, subscriber_id, &session)
-        {
+        if let Some(package_builder) = compile_package(
+            app,
+            topic,
+            topic_queue,
+            pages,
+            topic_message_id,
+            subscriber_id,
+            &session,
+        ) {
             to_send.push(package_builder);
         } else {
             not_engaged_topics.push(subscriber_id);
@@ -109,6 +144,7 @@ fn compile_package(
     topic: &Arc<Topic>,
     topic_queue: &mut TopicQueue,
     pages: &MessagesPageList,
+    topic_message_id: MessageId,
     subscriber_id: SubscriberId,
     session: &Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
 ) -> Option<SubscriberPackageBuilder> {
@@ -133,12 +169,16 @@ fn compile_package(
         let sub_page = pages.get(sub_page_id);
 
         if sub_page.is_none() {
-            crate::operations::load_page_and_try_to_deliver_again(
+            // Miss is counted once per load. Attempts which wait for the load in flight are not counted
+            if crate::operations::load_page_and_try_to_deliver_again(
                 app,
                 topic.clone(),
                 sub_page_id,
                 false,
-            );
+            ) {
+                app.prometheus
+                    .mark_sub_page_prefetch_miss(topic.topic_id.as_str());
+            }
 
             return package_builder;
         }
@@ -146,12 +186,27 @@ fn compile_package(
         let sub_page = sub_page.unwrap();
         sub_page.update_last_accessed(DateTimeAsMicroseconds::now());
 
+        if sub_page.take_prefetched() {
+            app.prometheus
+                .mark_sub_page_prefetch_hit(topic.topic_id.as_str());
+        }
+
         topic_queue.queue.dequeue();
 
         match sub_page.get_message(message_id.as_message_id()) {
             GetMessageResult::Message(message_content) => {
                 let attempt_no = topic_queue.delivery_attempts.get(message_content.id);
 
+                if topic_queue
+                    .delivery_attempts
+                    .is_redelivery(message_content.id)
+                {
+                    app.prometheus.mark_queue_redelivered_message(
+                        topic.topic_id.as_str(),
+                        topic_queue.queue_id.as_str(),
+                    );
+                }
+
                 if package_builder.is_none() {
                     package_builder = Some(SubscriberPackageBuilder::new(
                         session.clone(),
@@ -173,6 +228,14 @@ fn compile_package(
                     .as_mut()
                     .unwrap()
                     .add_message(message_content, attempt_no);
+
+                app.telemetry.record_delivery_span(
+                    topic.topic_id.as_str(),
+                    topic_queue.queue_id.as_str(),
+                    subscriber_id,
+                    message_content,
+                    attempt_no,
+                );
             }
             GetMessageResult::Missing => {}
             GetMessageResult::GarbageCollected => {
@@ -192,6 +255,16 @@ fn compile_package(
         };
     }
 
+    if let Some(queue_head) = topic_queue.queue.peek() {
+        crate::operations::prefetch_next_sub_page_if_needed(
+            app,
+            topic,
+            pages,
+            queue_head.as_message_id(),
+            topic_message_id,
+        );
+    }
+
     package_builder
 }
 
@@ -14,3 +14,9 @@ pub use subscriber_tcp_package_builder::*;
 //pub use delivery_abstractions::*;
 mod subscriber_http_package_builder;
 pub use subscriber_http_package_builder::*;
+mod subscriber_ws_package_builder;
+pub use subscriber_ws_package_builder::*;
+mod subscriber_grpc_package_builder;
+pub use subscriber_grpc_package_builder::*;
+mod subscriber_mqtt_package_builder;
+pub use subscriber_mqtt_package_builder::*;
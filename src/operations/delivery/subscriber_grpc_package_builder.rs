@@ -0,0 +1,43 @@
+use crate::messages_page::MySbMessageContent;
+use crate::service_bus_grpc::{MessageHeaderGrpcModel, MessageToDeliverGrpcModel};
+
+pub struct SubscriberGrpcPackageBuilder {
+    messages: Vec<MessageToDeliverGrpcModel>,
+    data_size: usize,
+}
+
+impl SubscriberGrpcPackageBuilder {
+    pub fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+            data_size: 0,
+        }
+    }
+
+    pub fn get_data_size(&self) -> usize {
+        self.data_size
+    }
+
+    pub fn add_message(&mut self, msg: &MySbMessageContent, attempt_no: i32) {
+        let msg_to_insert = MessageToDeliverGrpcModel {
+            message_id: msg.id.get_value(),
+            attempt_no,
+            headers: msg
+                .headers
+                .iter()
+                .map(|(k, v)| MessageHeaderGrpcModel {
+                    key: k.to_string(),
+                    value: v.to_string(),
+                })
+                .collect(),
+            content: msg.content.clone(),
+        };
+
+        self.data_size += msg_to_insert.content.len();
+        self.messages.push(msg_to_insert);
+    }
+
+    pub fn get_result(self) -> Vec<MessageToDeliverGrpcModel> {
+        self.messages
+    }
+}
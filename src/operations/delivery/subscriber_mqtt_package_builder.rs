@@ -0,0 +1,38 @@
+use crate::messages_page::MySbMessageContent;
+
+pub struct MqttMessageToDeliver {
+    pub id: i64,
+    pub content: Vec<u8>,
+}
+
+pub struct SubscriberMqttPackageBuilder {
+    messages: Vec<MqttMessageToDeliver>,
+    data_size: usize,
+}
+
+impl SubscriberMqttPackageBuilder {
+    pub fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+            data_size: 0,
+        }
+    }
+
+    pub fn get_data_size(&self) -> usize {
+        self.data_size
+    }
+
+    pub fn add_message(&mut self, msg: &MySbMessageContent, _attempt_no: i32) {
+        let msg_to_insert = MqttMessageToDeliver {
+            id: msg.id.get_value(),
+            content: msg.content.clone(),
+        };
+
+        self.data_size += msg_to_insert.content.len();
+        self.messages.push(msg_to_insert);
+    }
+
+    pub fn get_result(self) -> Vec<MqttMessageToDeliver> {
+        self.messages
+    }
+}
@@ -5,17 +5,25 @@ use my_service_bus::tcp_contracts::{MySbTcpContract, PacketProtVer};
 
 use crate::http::controllers::MessageToDeliverHttpContract;
 use crate::queues::QueueId;
+use crate::service_bus_grpc::MessageToDeliverGrpcModel;
 use crate::sessions::SessionType;
+use crate::ws::WsMessageToDeliver;
 use crate::{
     messages_page::MySbMessageContent, queue_subscribers::SubscriberId,
     sessions::MyServiceBusSession, topics::Topic,
 };
 
-use super::{SubscriberHttpPackageBuilder, SubscriberTcpPackageBuilder};
+use super::{
+    MqttMessageToDeliver, SubscriberGrpcPackageBuilder, SubscriberHttpPackageBuilder,
+    SubscriberMqttPackageBuilder, SubscriberTcpPackageBuilder, SubscriberWsPackageBuilder,
+};
 
 pub enum SubscriberPackageBuilderInner {
     Tcp(Option<SubscriberTcpPackageBuilder>),
     Http(Option<SubscriberHttpPackageBuilder>),
+    Ws(Option<SubscriberWsPackageBuilder>),
+    Grpc(Option<SubscriberGrpcPackageBuilder>),
+    Mqtt(Option<SubscriberMqttPackageBuilder>),
 }
 
 pub struct SubscriberPackageBuilder {
@@ -41,6 +49,15 @@ impl SubscriberPackageBuilder {
             SessionType::Http => {
                 SubscriberPackageBuilder::create_http(topic, queue_id, subscriber_id, session)
             }
+            SessionType::Ws => {
+                SubscriberPackageBuilder::create_ws(topic, queue_id, subscriber_id, session)
+            }
+            SessionType::Grpc => {
+                SubscriberPackageBuilder::create_grpc(topic, queue_id, subscriber_id, session)
+            }
+            SessionType::Mqtt => {
+                SubscriberPackageBuilder::create_mqtt(topic, queue_id, subscriber_id, session)
+            }
             #[cfg(test)]
             SessionType::Test => {
                 SubscriberPackageBuilder::create_http(topic, queue_id, subscriber_id, session)
@@ -90,6 +107,60 @@ impl SubscriberPackageBuilder {
         }
     }
 
+    pub fn create_ws(
+        topic: Arc<Topic>,
+        queue_id: QueueId,
+        subscriber_id: SubscriberId,
+        session: Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
+    ) -> Self {
+        let inner = SubscriberPackageBuilderInner::Ws(Some(SubscriberWsPackageBuilder::new()));
+
+        Self {
+            topic,
+            queue_id,
+            subscriber_id,
+            inner,
+            session: Some(session),
+            messages_on_delivery: QueueWithIntervals::new(),
+        }
+    }
+
+    pub fn create_grpc(
+        topic: Arc<Topic>,
+        queue_id: QueueId,
+        subscriber_id: SubscriberId,
+        session: Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
+    ) -> Self {
+        let inner = SubscriberPackageBuilderInner::Grpc(Some(SubscriberGrpcPackageBuilder::new()));
+
+        Self {
+            topic,
+            queue_id,
+            subscriber_id,
+            inner,
+            session: Some(session),
+            messages_on_delivery: QueueWithIntervals::new(),
+        }
+    }
+
+    pub fn create_mqtt(
+        topic: Arc<Topic>,
+        queue_id: QueueId,
+        subscriber_id: SubscriberId,
+        session: Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
+    ) -> Self {
+        let inner = SubscriberPackageBuilderInner::Mqtt(Some(SubscriberMqttPackageBuilder::new()));
+
+        Self {
+            topic,
+            queue_id,
+            subscriber_id,
+            inner,
+            session: Some(session),
+            messages_on_delivery: QueueWithIntervals::new(),
+        }
+    }
+
     pub fn get_data_size(&self) -> usize {
         match &self.inner {
             SubscriberPackageBuilderInner::Tcp(builder) => {
@@ -98,6 +169,13 @@ impl SubscriberPackageBuilder {
             SubscriberPackageBuilderInner::Http(builder) => {
                 builder.as_ref().unwrap().get_data_size()
             }
+            SubscriberPackageBuilderInner::Ws(builder) => builder.as_ref().unwrap().get_data_size(),
+            SubscriberPackageBuilderInner::Grpc(builder) => {
+                builder.as_ref().unwrap().get_data_size()
+            }
+            SubscriberPackageBuilderInner::Mqtt(builder) => {
+                builder.as_ref().unwrap().get_data_size()
+            }
         }
     }
 
@@ -111,6 +189,15 @@ impl SubscriberPackageBuilder {
             SubscriberPackageBuilderInner::Http(builder) => {
                 builder.as_mut().unwrap().add_message(msg, attempt_no);
             }
+            SubscriberPackageBuilderInner::Ws(builder) => {
+                builder.as_mut().unwrap().add_message(msg, attempt_no);
+            }
+            SubscriberPackageBuilderInner::Grpc(builder) => {
+                builder.as_mut().unwrap().add_message(msg, attempt_no);
+            }
+            SubscriberPackageBuilderInner::Mqtt(builder) => {
+                builder.as_mut().unwrap().add_message(msg, attempt_no);
+            }
         }
 
         self.messages_on_delivery.enqueue(message_id);
@@ -129,6 +216,15 @@ impl SubscriberPackageBuilder {
             SubscriberPackageBuilderInner::Http(_) => {
                 panic!("Cannot get tcp result from http package builder");
             }
+            SubscriberPackageBuilderInner::Ws(_) => {
+                panic!("Cannot get tcp result from websocket package builder");
+            }
+            SubscriberPackageBuilderInner::Grpc(_) => {
+                panic!("Cannot get tcp result from grpc package builder");
+            }
+            SubscriberPackageBuilderInner::Mqtt(_) => {
+                panic!("Cannot get tcp result from mqtt package builder");
+            }
         }
     }
 
@@ -141,6 +237,51 @@ impl SubscriberPackageBuilder {
                 let builder = builder.take().unwrap();
                 builder.get_result()
             }
+            SubscriberPackageBuilderInner::Ws(_) => {
+                panic!("Cannot get http result from websocket package builder");
+            }
+            SubscriberPackageBuilderInner::Grpc(_) => {
+                panic!("Cannot get http result from grpc package builder");
+            }
+            SubscriberPackageBuilderInner::Mqtt(_) => {
+                panic!("Cannot get http result from mqtt package builder");
+            }
+        }
+    }
+
+    pub fn get_ws_result(&mut self) -> Vec<WsMessageToDeliver> {
+        match &mut self.inner {
+            SubscriberPackageBuilderInner::Ws(builder) => {
+                let builder = builder.take().unwrap();
+                builder.get_result()
+            }
+            _ => {
+                panic!("Cannot get websocket result from non websocket package builder");
+            }
+        }
+    }
+
+    pub fn get_grpc_result(&mut self) -> Vec<MessageToDeliverGrpcModel> {
+        match &mut self.inner {
+            SubscriberPackageBuilderInner::Grpc(builder) => {
+                let builder = builder.take().unwrap();
+                builder.get_result()
+            }
+            _ => {
+                panic!("Cannot get grpc result from non grpc package builder");
+            }
+        }
+    }
+
+    pub fn get_mqtt_result(&mut self) -> Vec<MqttMessageToDeliver> {
+        match &mut self.inner {
+            SubscriberPackageBuilderInner::Mqtt(builder) => {
+                let builder = builder.take().unwrap();
+                builder.get_result()
+            }
+            _ => {
+                panic!("Cannot get mqtt result from non mqtt package builder");
+            }
         }
     }
 
@@ -0,0 +1,40 @@
+use crate::messages_page::MySbMessageContent;
+use crate::ws::WsMessageToDeliver;
+
+pub struct SubscriberWsPackageBuilder {
+    messages: Vec<WsMessageToDeliver>,
+    data_size: usize,
+}
+
+impl SubscriberWsPackageBuilder {
+    pub fn new() -> Self {
+        Self {
+            messages: Vec::new(),
+            data_size: 0,
+        }
+    }
+
+    pub fn get_data_size(&self) -> usize {
+        self.data_size
+    }
+
+    pub fn add_message(&mut self, msg: &MySbMessageContent, attempt_no: i32) {
+        let msg_to_insert = WsMessageToDeliver {
+            id: msg.id.get_value(),
+            attempt_no,
+            headers: msg
+                .headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            content: msg.content.clone(),
+        };
+
+        self.data_size += msg_to_insert.content.len();
+        self.messages.push(msg_to_insert);
+    }
+
+    pub fn get_result(self) -> Vec<WsMessageToDeliver> {
+        self.messages
+    }
+}
@@ -6,6 +6,7 @@ use crate::{
     app::AppContext,
     queue_subscribers::SubscriberId,
     queues::{DeliveryBucket, TopicQueue},
+    sessions::{MyServiceBusSession, SessionId},
 };
 
 use super::OperationFailResult;
@@ -15,6 +16,7 @@ pub async fn all_confirmed(
     topic_id: &str,
     queue_id: &str,
     subscriber_id: SubscriberId,
+    session_id: SessionId,
 ) -> Result<(), OperationFailResult> {
     let topic = app
         .topic_list
@@ -35,8 +37,23 @@ pub async fn all_confirmed(
                     queue_id: queue_id.to_string(),
                 })?;
 
-        if let Some(delivery_bucket) = get_delivery_bucket(topic_queue, subscriber_id, true) {
+        check_subscriber_session(topic_queue, subscriber_id, session_id)?;
+
+        if let Some(delivery_bucket) = get_delivery_bucket(app, topic_queue, subscriber_id, true) {
+            app.prometheus.mark_queue_delivered_messages(
+                topic_id,
+                queue_id,
+                delivery_bucket.ids.queue_size(),
+            );
             topic_queue.confirm_delivered(&delivery_bucket.ids);
+
+            app.telemetry.record_confirmation_spans(
+                &topic_access,
+                queue_id,
+                &delivery_bucket.ids,
+                delivery_bucket.started,
+                true,
+            );
         }
 
         if !topic_access.persist {
@@ -58,6 +75,7 @@ pub async fn all_fail(
     topic_id: &str,
     queue_id: &str,
     subscriber_id: SubscriberId,
+    session_id: SessionId,
 ) -> Result<(), OperationFailResult> {
     let topic = app
         .topic_list
@@ -78,8 +96,23 @@ pub async fn all_fail(
                     queue_id: queue_id.to_string(),
                 })?;
 
-        if let Some(delivery_bucket) = get_delivery_bucket(topic_queue, subscriber_id, false) {
+        check_subscriber_session(topic_queue, subscriber_id, session_id)?;
+
+        if let Some(delivery_bucket) = get_delivery_bucket(app, topic_queue, subscriber_id, false) {
+            app.prometheus.mark_queue_failed_messages(
+                topic_id,
+                queue_id,
+                delivery_bucket.ids.queue_size(),
+            );
             topic_queue.confirm_non_delivered(&delivery_bucket.ids);
+
+            app.telemetry.record_confirmation_spans(
+                &topic_data,
+                queue_id,
+                &delivery_bucket.ids,
+                delivery_bucket.started,
+                false,
+            );
         }
     }
 
@@ -96,6 +129,7 @@ pub async fn intermediary_confirm(
     topic_id: &str,
     queue_id: &str,
     subscriber_id: SubscriberId,
+    session_id: SessionId,
     confirmed: QueueWithIntervals,
 ) -> Result<(), OperationFailResult> {
     let topic = app
@@ -117,15 +151,33 @@ pub async fn intermediary_confirm(
                     queue_id: queue_id.to_string(),
                 })?;
 
+        check_subscriber_session(topic_queue, subscriber_id, session_id)?;
+
+        let mut delivery_started = None;
+
         {
             let subscriber = topic_queue.subscribers.get_by_id_mut(subscriber_id);
 
             if let Some(subscriber) = subscriber {
+                delivery_started = Some(subscriber.metrics.start_delivery_time);
                 subscriber.intermediary_confirmed(&confirmed)
             }
         }
 
+        app.prometheus
+            .mark_queue_delivered_messages(topic_id, queue_id, confirmed.queue_size());
+
         topic_queue.confirm_delivered(&confirmed);
+
+        if let Some(delivery_started) = delivery_started {
+            app.telemetry.record_confirmation_spans(
+                &topic_data,
+                queue_id,
+                &confirmed,
+                delivery_started,
+                true,
+            );
+        }
     }
 
     #[cfg(test)]
@@ -141,6 +193,7 @@ pub async fn some_messages_are_confirmed(
     topic_id: &str,
     queue_id: &str,
     subscriber_id: SubscriberId,
+    session_id: SessionId,
     confirmed_messages: QueueWithIntervals,
 ) -> Result<(), OperationFailResult> {
     let topic = app
@@ -161,9 +214,37 @@ pub async fn some_messages_are_confirmed(
                     queue_id: queue_id.to_string(),
                 })?;
 
-        if let Some(mut delivery_bucket) = get_delivery_bucket(topic_queue, subscriber_id, false) {
+        check_subscriber_session(topic_queue, subscriber_id, session_id)?;
+
+        if let Some(mut delivery_bucket) =
+            get_delivery_bucket(app, topic_queue, subscriber_id, false)
+        {
+            let on_delivery = delivery_bucket.ids.queue_size();
             delivery_bucket.confirmed(&confirmed_messages);
+
+            let failed = delivery_bucket.ids.queue_size();
+            app.prometheus
+                .mark_queue_delivered_messages(topic_id, queue_id, on_delivery - failed);
+            app.prometheus
+                .mark_queue_failed_messages(topic_id, queue_id, failed);
+
             topic_queue.confirm_non_delivered(&delivery_bucket.ids);
+
+            app.telemetry.record_confirmation_spans(
+                &topic_data,
+                queue_id,
+                &confirmed_messages,
+                delivery_bucket.started,
+                true,
+            );
+
+            app.telemetry.record_confirmation_spans(
+                &topic_data,
+                queue_id,
+                &delivery_bucket.ids,
+                delivery_bucket.started,
+                false,
+            );
         }
     }
 
@@ -175,7 +256,24 @@ pub async fn some_messages_are_confirmed(
     Ok(())
 }
 
+// Delivery can be confirmed only by the session of the subscriber.
+// Subscriber which is already gone is skipped by the confirmation
+fn check_subscriber_session(
+    topic_queue: &TopicQueue,
+    subscriber_id: SubscriberId,
+    session_id: SessionId,
+) -> Result<(), OperationFailResult> {
+    if let Some(subscriber) = topic_queue.subscribers.get_by_id(subscriber_id) {
+        if subscriber.session.get_session_id() != session_id {
+            return Err(OperationFailResult::SubscriberNotFound { id: subscriber_id });
+        }
+    }
+
+    Ok(())
+}
+
 fn get_delivery_bucket(
+    app: &AppContext,
     topic_queue: &mut TopicQueue,
     subscriber_id: SubscriberId,
     positive: bool,
@@ -201,7 +299,13 @@ fn get_delivery_bucket(
     if let Some(delivery_bucket) = &mut delivery_bucket {
         let delivery_amount = delivery_bucket.ids.queue_size();
         if delivery_amount > 0 {
-            subscriber.update_delivery_time(delivery_amount, positive);
+            let confirmation_duration = subscriber.update_delivery_time(delivery_amount, positive);
+
+            app.prometheus.observe_confirmation_duration(
+                topic_queue.topic_id.as_str(),
+                topic_queue.queue_id.as_str(),
+                confirmation_duration,
+            );
         } else {
             println!(
                 "{}/{} No messages on delivery at subscriber {}",
@@ -224,3 +328,155 @@ fn get_delivery_bucket(
 
     delivery_bucket
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use my_service_bus::abstractions::{
+        publisher::MessageToPublish, queue_with_intervals::QueueWithIntervals,
+        subscriber::TopicQueueType, SbMessageHeaders,
+    };
+
+    use crate::{
+        app::AppContext, queue_subscribers::SubscriberId, sessions::SessionId,
+        settings::SettingsModel,
+    };
+
+    const TOPIC_NAME: &str = "test-topic";
+    const QUEUE_NAME: &str = "test-queue";
+
+    async fn publish(app: &Arc<AppContext>, session_id: SessionId, amount: usize) {
+        let messages = (0..amount)
+            .map(|i| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![i as u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(app, TOPIC_NAME, messages, false, session_id)
+            .await
+            .unwrap();
+    }
+
+    async fn get_messages_on_delivery(
+        app: &Arc<AppContext>,
+        subscriber_id: SubscriberId,
+    ) -> QueueWithIntervals {
+        let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+        let topic_data = topic.get_access().await;
+        let queue = topic_data.queues.get(QUEUE_NAME).unwrap();
+
+        queue
+            .subscribers
+            .get_by_id(subscriber_id)
+            .unwrap()
+            .get_messages_on_delivery()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_confirmations_update_delivered_and_failed_messages() {
+        let settings = SettingsModel::create_test_settings(16);
+
+        let app = Arc::new(AppContext::new(settings).await);
+
+        let session = app.sessions.add_test("127.0.0.1").await;
+
+        crate::operations::publisher::create_topic_if_not_exists(
+            &app,
+            Some(session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        let subscriber_id = crate::operations::subscriber::subscribe_to_queue(
+            &app,
+            TOPIC_NAME.to_string(),
+            QUEUE_NAME.to_string(),
+            TopicQueueType::PermanentWithSingleConnection,
+            session.clone(),
+        )
+        .await
+        .unwrap();
+
+        publish(&app, session.session_id, 3).await;
+
+        let other_session = app.sessions.add_test("127.0.0.1").await;
+
+        assert!(super::all_confirmed(
+            &app,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_id,
+            other_session.session_id
+        )
+        .await
+        .is_err());
+
+        assert_eq!(
+            app.prometheus
+                .get_queue_delivered_and_failed_messages(TOPIC_NAME, QUEUE_NAME),
+            (0, 0)
+        );
+
+        super::all_confirmed(
+            &app,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_id,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            app.prometheus
+                .get_queue_delivered_and_failed_messages(TOPIC_NAME, QUEUE_NAME),
+            (3, 0)
+        );
+
+        publish(&app, session.session_id, 2).await;
+
+        super::all_fail(
+            &app,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_id,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            app.prometheus
+                .get_queue_delivered_and_failed_messages(TOPIC_NAME, QUEUE_NAME),
+            (3, 2)
+        );
+
+        // Failed messages are delivered again
+        let mut on_delivery = get_messages_on_delivery(&app, subscriber_id).await;
+        assert_eq!(on_delivery.queue_size(), 2);
+
+        let mut confirmed = QueueWithIntervals::new();
+        confirmed.enqueue(on_delivery.dequeue().unwrap());
+
+        super::some_messages_are_confirmed(
+            &app,
+            TOPIC_NAME,
+            QUEUE_NAME,
+            subscriber_id,
+            session.session_id,
+            confirmed,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            app.prometheus
+                .get_queue_delivered_and_failed_messages(TOPIC_NAME, QUEUE_NAME),
+            (4, 3)
+        );
+    }
+}
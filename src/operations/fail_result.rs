@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use my_service_bus::shared::validators::InvalidTopicName;
 
-use crate::queue_subscribers::SubscriberId;
+use crate::{auth::AclPermission, queue_subscribers::SubscriberId};
 
 #[derive(Debug)]
 pub enum OperationFailResult {
@@ -13,7 +15,23 @@ pub enum OperationFailResult {
     TonicError(tonic::Status),
     Other(String),
     ShuttingDown,
+    NodeIsPassive,
+    TopicIsOwnedByOtherNode {
+        topic_id: String,
+        node_id: String,
+        tcp_address: String,
+        http_url: String,
+    },
     TopicOrQueueValidationError(InvalidTopicName),
+    PublishRateLimitIsExceeded {
+        topic_id: String,
+        retry_after: Duration,
+    },
+    AccessDenied {
+        identity: String,
+        permission: AclPermission,
+        name: String,
+    },
 }
 
 impl From<InvalidTopicName> for OperationFailResult {
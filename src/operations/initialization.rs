@@ -2,12 +2,20 @@ use std::{sync::Arc, time::Duration};
 
 use my_logger::LogEventCtx;
 use my_service_bus::abstractions::queue_with_intervals::QueueWithIntervals;
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
 use rust_extensions::StopWatch;
 
+use crate::grpc_client::MessagesWal;
 use crate::topics::TopicSnapshot;
 
 use crate::app::AppContext;
 
+const ATTEMPTS_BEFORE_LOCAL_MIRROR: usize = 3;
+
+const REPLAY_FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+const REPLAY_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
 pub async fn init(app: Arc<AppContext>) {
     let mut sw = StopWatch::new();
     sw.start();
@@ -39,6 +47,10 @@ pub async fn init(app: Arc<AppContext>) {
         }
     }
 
+    if let Some(messages_wal) = &app.messages_wal {
+        replay_messages_wal(&app, messages_wal).await;
+    }
+
     /*
        for topic in app.topic_list.get_all().await {
            restore_topic_pages(app.clone(), topic.clone()).await;
@@ -61,12 +73,9 @@ pub async fn init(app: Arc<AppContext>) {
 async fn restore_topic_pages(app: Arc<AppContext>, topic: Arc<Topic>) {
     let sub_page_id = topic.get_current_sub_page().await;
 
-    let sub_page = crate::operations::page_loader::load_page_to_cache(
-        &topic,
-        app.messages_pages_repo.clone(),
-        sub_page_id,
-    )
-    .await;
+    let sub_page =
+        crate::operations::page_loader::load_page_to_cache(app.as_ref(), &topic, sub_page_id)
+            .await;
 
     if let Some(sub_page) = sub_page {
         let mut topic_data = topic.get_access().await;
@@ -74,6 +83,104 @@ async fn restore_topic_pages(app: Arc<AppContext>, topic: Arc<Topic>) {
     }
 }
  */
+// Messages which were published but were not persisted before the stop.
+// They are saved to persistence in background, so startup is not blocked if persistence is down.
+// Spool keeps them until they are saved.
+async fn replay_messages_wal(app: &Arc<AppContext>, messages_wal: &MessagesWal) {
+    let not_persisted = match messages_wal.read_not_persisted().await {
+        Ok(result) => result,
+        Err(err) => {
+            panic!("Can not read write-ahead spool. Err: {:?}", err);
+        }
+    };
+
+    let mut to_replay = Vec::with_capacity(not_persisted.len());
+
+    for (topic_id, messages) in not_persisted {
+        let max_message_id = match messages
+            .iter()
+            .map(|itm| itm.get_message_id().get_value())
+            .max()
+        {
+            Some(max_message_id) => max_message_id,
+            None => continue,
+        };
+
+        // Snapshot of topics could be saved before the last messages were published
+        match app.topic_list.get(topic_id.as_str()).await {
+            Some(topic) => {
+                let mut topic_data = topic.get_access().await;
+
+                if topic_data.message_id.get_value() <= max_message_id {
+                    topic_data.message_id = MessageId::new(max_message_id + 1);
+                }
+            }
+            None => {
+                app.topic_list
+                    .restore(topic_id.as_str(), MessageId::new(max_message_id + 1), true)
+                    .await;
+            }
+        }
+
+        messages_wal.set_replay_pending(topic_id.as_str()).await;
+
+        to_replay.push((topic_id, max_message_id, messages));
+    }
+
+    if !to_replay.is_empty() {
+        tokio::spawn(replay_messages_to_persistence(app.clone(), to_replay));
+    }
+}
+
+async fn replay_messages_to_persistence(
+    app: Arc<AppContext>,
+    to_replay: Vec<(String, i64, Vec<MessageProtobufModel>)>,
+) {
+    let messages_wal = app.messages_wal.as_ref().unwrap();
+
+    for (topic_id, max_message_id, messages) in to_replay {
+        let messages_count = messages.len();
+
+        let mut delay = REPLAY_FIRST_RETRY_DELAY;
+        let mut attempt = 0;
+
+        // Circuit breaker is respected by save_with_retries
+        while let Err(err) =
+            crate::operations::save_with_retries(&app, topic_id.as_str(), messages.clone()).await
+        {
+            attempt += 1;
+
+            my_logger::LOGGER.write_error(
+                "replay_messages_wal",
+                format!(
+                    "Can not replay messages to persistence. Retrying in {:?}. Err: {:?}",
+                    delay, err
+                ),
+                LogEventCtx::new()
+                    .add("topicId", topic_id.as_str())
+                    .add("attemptNo", attempt.to_string()),
+            );
+
+            tokio::time::sleep(delay).await;
+
+            delay = (delay * 2).min(REPLAY_MAX_RETRY_DELAY);
+        }
+
+        messages_wal
+            .confirm_replayed(topic_id.as_str(), max_message_id + 1)
+            .await;
+
+        my_logger::LOGGER.write_info(
+            "replay_messages_wal",
+            format!(
+                "{} messages are replayed from write-ahead spool",
+                messages_count
+            ),
+            LogEventCtx::new().add("topicId", topic_id.as_str()),
+        );
+    }
+}
+
 async fn restore_topics_and_queues(app: &AppContext) -> Vec<TopicSnapshot> {
     let mut attempt = 0;
     loop {
@@ -99,6 +206,35 @@ async fn restore_topics_and_queues(app: &AppContext) -> Vec<TopicSnapshot> {
             LogEventCtx::new().add("attemptNo", attempt.to_string()),
         );
 
+        if attempt >= ATTEMPTS_BEFORE_LOCAL_MIRROR {
+            if let Some(result) = app.topics_and_queues_repo.load_local_mirror().await {
+                match result {
+                    Ok(result) => {
+                        my_logger::LOGGER.write_info(
+                            "restore_topics_and_queues",
+                            format!(
+                                "Persistence is unreachable. Topics and queues are restored from local mirror. Topics: {}",
+                                result.len()
+                            ),
+                            LogEventCtx::new().add("attemptNo", attempt.to_string()),
+                        );
+
+                        return result;
+                    }
+                    Err(err) => {
+                        my_logger::LOGGER.write_error(
+                            "restore_topics_and_queues",
+                            format!(
+                                "Can not restore topics and queues from local mirror. Err: {:?}",
+                                err
+                            ),
+                            LogEventCtx::new().add("attemptNo", attempt.to_string()),
+                        );
+                    }
+                }
+            }
+        }
+
         tokio::time::sleep(Duration::from_secs(5)).await;
     }
 }
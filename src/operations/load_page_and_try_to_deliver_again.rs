@@ -1,15 +1,47 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use my_service_bus::shared::sub_page::SubPageId;
 
 use crate::app::AppContext;
 
+// Evicted sub page which failed to load is loaded again after this delay, so idle queue does not stall
+const FAILED_LOAD_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+// Returns false if the sub page is already being loaded
 pub fn load_page_and_try_to_deliver_again(
     app: &Arc<AppContext>,
     topic: Arc<crate::topics::Topic>,
     sub_page_id: SubPageId,
     delete_page: bool,
+) -> bool {
+    start_page_load(app, topic, sub_page_id, delete_page, false)
+}
+
+pub fn prefetch_page(
+    app: &Arc<AppContext>,
+    topic: Arc<crate::topics::Topic>,
+    sub_page_id: SubPageId,
 ) {
+    start_page_load(app, topic, sub_page_id, false, true);
+}
+
+// Sub page which is already being loaded is not loaded again. Delivery is done for all queues
+// of the topic when the sub page is loaded. Delete request which comes during the load makes
+// the sub page to be deleted and loaded again after it
+fn start_page_load(
+    app: &Arc<AppContext>,
+    topic: Arc<crate::topics::Topic>,
+    sub_page_id: SubPageId,
+    delete_page: bool,
+    prefetch: bool,
+) -> bool {
+    if !app
+        .page_loads
+        .try_start(topic.topic_id.as_str(), sub_page_id, delete_page)
+    {
+        return false;
+    }
+
     let app = app.clone();
 
     tokio::spawn(async move {
@@ -19,18 +51,37 @@ pub fn load_page_and_try_to_deliver_again(
         }
 
         let sub_page = crate::operations::page_loader::load_page_to_cache(
+            app.as_ref(),
             &topic,
-            app.messages_pages_repo.clone(),
             sub_page_id,
+            prefetch,
         )
         .await;
 
         let mut topic_data = topic.get_access().await;
 
+        let load_failed = sub_page.is_none()
+            && !prefetch
+            && topic_data.pages.get(sub_page_id).is_none()
+            && topic_data.pages.is_evicted(sub_page_id);
+
         if let Some(sub_page) = sub_page {
+            if prefetch {
+                sub_page.mark_as_prefetched();
+            }
+
             topic_data.pages.restore_sub_page(sub_page);
         }
 
+        if app.page_loads.finish(topic.topic_id.as_str(), sub_page_id) {
+            start_page_load(&app, topic.clone(), sub_page_id, true, false);
+            return;
+        }
+
+        if load_failed {
+            retry_failed_page_load(&app, topic.clone(), sub_page_id);
+        }
+
         #[cfg(test)]
         crate::operations::delivery::try_to_deliver_to_subscribers(&app, &topic, &mut topic_data)
             .await;
@@ -38,4 +89,130 @@ pub fn load_page_and_try_to_deliver_again(
         #[cfg(not(test))]
         crate::operations::delivery::try_to_deliver_to_subscribers(&app, &topic, &mut topic_data);
     });
+
+    true
+}
+
+fn retry_failed_page_load(
+    app: &Arc<AppContext>,
+    topic: Arc<crate::topics::Topic>,
+    sub_page_id: SubPageId,
+) {
+    let app = app.clone();
+
+    tokio::spawn(async move {
+        tokio::time::sleep(FAILED_LOAD_RETRY_DELAY).await;
+
+        if app.topic_list.get(topic.topic_id.as_str()).await.is_none() {
+            return;
+        }
+
+        // Sub page is loaded by delivery or garbage collected meanwhile
+        if !topic.get_access().await.pages.is_evicted(sub_page_id) {
+            return;
+        }
+
+        load_page_and_try_to_deliver_again(&app, topic, sub_page_id, false);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use my_service_bus::{
+        abstractions::{publisher::MessageToPublish, SbMessageHeaders},
+        shared::sub_page::SubPageId,
+    };
+
+    use crate::{app::AppContext, settings::SettingsModel, topics::Topic};
+
+    const TOPIC_NAME: &str = "test-topic";
+
+    async fn is_loaded(topic: &Topic) -> bool {
+        let topic_data = topic.get_access().await;
+        topic_data.pages.get(SubPageId::new(0)).is_some()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_evicted_sub_page_is_loaded_again_after_failed_load() {
+        let app = Arc::new(AppContext::new(SettingsModel::create_test_settings(16)).await);
+        let session = app.sessions.add_test("127.0.0.1").await;
+
+        crate::operations::publisher::create_topic_if_not_exists(
+            &app,
+            Some(session.session_id),
+            TOPIC_NAME,
+        )
+        .await
+        .unwrap();
+
+        let messages = (0..2)
+            .map(|i| MessageToPublish {
+                headers: SbMessageHeaders::new(),
+                content: vec![i as u8],
+            })
+            .collect();
+
+        crate::operations::publisher::publish(
+            &app,
+            TOPIC_NAME,
+            messages,
+            false,
+            session.session_id,
+        )
+        .await
+        .unwrap();
+
+        let topic = app.topic_list.get(TOPIC_NAME).await.unwrap();
+
+        crate::operations::persist_topic_messages(&app, &topic).await;
+
+        {
+            let mut topic_data = topic.get_access().await;
+            let last_accessed = topic_data
+                .pages
+                .get(SubPageId::new(0))
+                .unwrap()
+                .get_last_accessed();
+
+            assert!(topic_data
+                .pages
+                .evict_sub_page(SubPageId::new(0), last_accessed)
+                .is_some());
+        }
+
+        // Every attempt of the first load fails
+        app.messages_pages_repo.unwrap_mock().fail_next_loads(8);
+
+        assert!(super::load_page_and_try_to_deliver_again(
+            &app,
+            topic.clone(),
+            SubPageId::new(0),
+            false,
+        ));
+
+        tokio::time::sleep(Duration::from_secs(30)).await;
+
+        assert!(!is_loaded(&topic).await);
+        assert!(topic.get_access().await.pages.is_evicted(SubPageId::new(0)));
+
+        tokio::time::sleep(super::FAILED_LOAD_RETRY_DELAY).await;
+
+        assert!(is_loaded(&topic).await);
+
+        let topic_data = topic.get_access().await;
+        assert!(!topic_data.pages.is_evicted(SubPageId::new(0)));
+        assert_eq!(
+            vec![vec![0u8], vec![1u8]],
+            topic_data
+                .pages
+                .get(SubPageId::new(0))
+                .unwrap()
+                .unwrap_all_messages_with_content()
+                .into_iter()
+                .map(|itm| itm.content)
+                .collect::<Vec<_>>()
+        );
+    }
 }
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{app::AppContext, messages_page::SubPageEvictionCandidate, topics::Topic};
+
+// Memory is freed down to this percent of the budget so eviction does not run on every tick
+const EVICTION_TARGET_PERCENT: usize = 90;
+
+struct TopicSubPageToEvict {
+    topic: Arc<Topic>,
+    candidate: SubPageEvictionCandidate,
+}
+
+pub fn get_allocated_memory() -> Option<usize> {
+    if let Err(err) = tikv_jemalloc_ctl::epoch::advance() {
+        println!("Can not refresh jemalloc stats. Err: {:?}", err);
+        return None;
+    }
+
+    match tikv_jemalloc_ctl::stats::allocated::read() {
+        Ok(allocated) => Some(allocated),
+        Err(err) => {
+            println!("Can not read jemalloc allocated stat. Err: {:?}", err);
+            None
+        }
+    }
+}
+
+// Evicts persisted sub pages of persisted topics least recently accessed first.
+// Queues load evicted sub pages back with load_page_and_try_to_deliver_again
+pub async fn evict_sub_pages_over_memory_budget(app: &AppContext, memory_budget: usize) {
+    let allocated = match get_allocated_memory() {
+        Some(allocated) => allocated,
+        None => return,
+    };
+
+    app.prometheus.update_allocated_memory(allocated);
+
+    if allocated <= memory_budget {
+        return;
+    }
+
+    // Evicted sub page can not be loaded back while persistence is not available
+    if app.persistence_circuit_breaker.is_degraded() {
+        return;
+    }
+
+    let started = DateTimeAsMicroseconds::now();
+
+    let size_to_free = allocated - memory_budget / 100 * EVICTION_TARGET_PERCENT;
+
+    let mut sub_pages_to_evict = Vec::new();
+
+    for topic in app.topic_list.get_all().await {
+        let candidates = topic.get_access().await.get_eviction_candidates();
+
+        for candidate in candidates {
+            sub_pages_to_evict.push(TopicSubPageToEvict {
+                topic: topic.clone(),
+                candidate,
+            });
+        }
+    }
+
+    sub_pages_to_evict.sort_by_key(|itm| itm.candidate.last_accessed.unix_microseconds);
+
+    let mut freed = 0;
+    let mut evicted = 0;
+
+    for itm in sub_pages_to_evict {
+        if freed >= size_to_free {
+            break;
+        }
+
+        let mut topic_data = itm.topic.get_access().await;
+
+        if let Some(size) = topic_data
+            .pages
+            .evict_sub_page(itm.candidate.sub_page_id, itm.candidate.last_accessed)
+        {
+            freed += size;
+            evicted += 1;
+        }
+    }
+
+    app.prometheus.mark_evicted_sub_pages(evicted);
+
+    my_logger::LOGGER.write_info(
+        "MemoryBudget".to_string(),
+        format!(
+            "Memory budget is exceeded. Evicted {} sub pages with {} bytes of messages in {:?}",
+            evicted,
+            freed,
+            DateTimeAsMicroseconds::now()
+                .duration_since(started)
+                .as_positive_or_zero()
+        ),
+        my_logger::LogEventCtx::new()
+            .add("allocated", allocated.to_string())
+            .add("memoryBudget", memory_budget.to_string()),
+    );
+}
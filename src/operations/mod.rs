@@ -1,28 +1,39 @@
+mod authentication;
+mod authorization;
 mod delete_topic;
 pub mod delivery;
 mod fail_result;
+mod gc_grpc_connections;
 mod gc_http_connections;
 
 pub mod initialization;
 mod load_page_and_try_to_deliver_again;
+mod memory_budget;
 pub mod page_loader;
 mod persist_topic_messages;
 mod persist_topics_and_queues;
+mod prefetch_sub_page;
 mod send_package;
 
 pub mod delivery_confirmation;
 pub mod publisher;
 pub mod queues;
+pub mod reload_settings;
 pub mod sessions;
 pub mod subscriber;
 
+pub use authentication::*;
+pub use authorization::*;
 pub use delete_topic::*;
 pub use fail_result::*;
+pub use gc_grpc_connections::gc_grpc_connections;
 pub use gc_http_connections::gc_http_connections;
 
-pub use load_page_and_try_to_deliver_again::load_page_and_try_to_deliver_again;
+pub use load_page_and_try_to_deliver_again::{load_page_and_try_to_deliver_again, prefetch_page};
+pub use memory_budget::*;
 pub use persist_topic_messages::*;
 pub use persist_topics_and_queues::persist_topics_and_queues;
+pub use prefetch_sub_page::*;
 mod restore_topic;
 pub use restore_topic::*;
 mod update_topic_persist;
@@ -1,15 +1,16 @@
 use std::sync::Arc;
 
+use my_logger::LogEventCtx;
 use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::{grpc_client::MessagesPagesRepo, messages_page::SubPage, topics::Topic};
+use crate::{app::AppContext, messages_page::SubPage, topics::Topic};
 
 pub async fn load_page_to_cache(
+    app: &AppContext,
     topic: &Arc<Topic>,
-    messages_pages_repo: Arc<MessagesPagesRepo>,
-
     sub_page_id: SubPageId,
+    prefetch: bool,
 ) -> Option<SubPage> {
     let mut dt = topic.restore_page_lock.lock().await;
 
@@ -20,10 +21,34 @@ pub async fn load_page_to_cache(
         }
     }
 
-    let sub_page =
-        super::operations::load_page(topic.as_ref(), &messages_pages_repo, sub_page_id).await;
+    let sub_page = super::operations::load_page(app, topic.as_ref(), sub_page_id).await;
 
     *dt = DateTimeAsMicroseconds::now();
 
-    Some(sub_page)
+    if sub_page.is_some() {
+        return sub_page;
+    }
+
+    // Failed prefetch leaves sub page not loaded, so delivery loads it when it reaches the sub page
+    if prefetch {
+        return None;
+    }
+
+    let topic_data = topic.get_access().await;
+
+    // Evicted sub page stays not loaded, so it is loaded again later
+    if topic_data.pages.is_evicted(sub_page_id) {
+        my_logger::LOGGER.write_error(
+            "load_page_to_cache",
+            format!(
+                "Can not load evicted sub_page #{} from persistence storage. Will be loaded again later",
+                sub_page_id.get_value(),
+            ),
+            LogEventCtx::new().add("topicId", topic.topic_id.as_str()),
+        );
+
+        return None;
+    }
+
+    Some(SubPage::create_as_missing(sub_page_id))
 }
@@ -1,33 +1,54 @@
-use std::{
-    collections::{BTreeMap, HashMap},
-    sync::Arc,
-    time::Duration,
-};
+use std::{collections::BTreeMap, time::Duration};
 
 use my_logger::LogEventCtx;
 use my_service_bus::shared::sub_page::SubPageId;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::{
-    grpc_client::{MessagesPagesRepo, PersistenceError},
+    app::AppContext,
+    grpc_client::PersistenceError,
     messages_page::{MySbCachedMessage, SubPage, SubPageInner},
     topics::Topic,
 };
 
-pub async fn load_page(
-    topic: &Topic,
-    messages_pages_repo: &Arc<MessagesPagesRepo>,
-    sub_page_id: SubPageId,
-) -> SubPage {
+const LOAD_PAGE_ATTEMPTS: usize = 8;
+const LOAD_PAGE_FIRST_RETRY_DELAY: Duration = Duration::from_millis(250);
+const LOAD_PAGE_MAX_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+// None if persistence storage is not available after all attempts
+pub async fn load_page(app: &AppContext, topic: &Topic, sub_page_id: SubPageId) -> Option<SubPage> {
     let mut attempt_no = 0;
     loop {
-        let result = messages_pages_repo
-            .load_page(
-                topic.topic_id.as_str(),
-                sub_page_id.into(),
-                sub_page_id.get_first_message_id(),
-                sub_page_id.get_last_message_id(),
-            )
-            .await;
+        let result = {
+            let _permit = app.page_loads.acquire_permit().await;
+
+            let started = DateTimeAsMicroseconds::now();
+
+            let result = app
+                .messages_pages_repo
+                .load_page(
+                    topic.topic_id.as_str(),
+                    sub_page_id.into(),
+                    sub_page_id.get_first_message_id(),
+                    sub_page_id.get_last_message_id(),
+                )
+                .await;
+
+            let duration = DateTimeAsMicroseconds::now()
+                .duration_since(started)
+                .as_positive_or_zero();
+
+            match &result {
+                Ok(_) => app
+                    .prometheus
+                    .observe_page_load_duration(topic.topic_id.as_str(), duration),
+                Err(_) => app
+                    .prometheus
+                    .mark_page_load_failure(topic.topic_id.as_str()),
+            }
+
+            result
+        };
 
         if let Ok(result) = result {
             match result {
@@ -42,9 +63,9 @@ pub async fn load_page(
                         }
                     }
 
-                    return SubPageInner::restore(sub_page_id, result).into();
+                    return Some(SubPageInner::restore(sub_page_id, result).into());
                 }
-                None => return SubPage::create_as_missing(sub_page_id),
+                None => return Some(SubPage::create_as_missing(sub_page_id)),
             }
         }
 
@@ -63,13 +84,9 @@ pub async fn load_page(
                         .add("attemptNo", attempt_no.to_string()),
                 );
 
-                return SubPage::create_as_missing(sub_page_id);
+                return Some(SubPage::create_as_missing(sub_page_id));
             }
             _ => {
-                let mut ctx = HashMap::new();
-                ctx.insert("subPageId".to_string(), sub_page_id.get_value().to_string());
-                ctx.insert("attemptNo".to_string(), attempt_no.to_string());
-
                 my_logger::LOGGER.write_error(
                     "load_page",
                     format!(
@@ -86,9 +103,36 @@ pub async fn load_page(
 
         attempt_no += 1;
 
-        if attempt_no == 5 {
-            return SubPage::create_as_missing(sub_page_id);
+        if attempt_no == LOAD_PAGE_ATTEMPTS {
+            return None;
         }
-        tokio::time::sleep(Duration::from_secs(1)).await
+
+        tokio::time::sleep(get_retry_delay(attempt_no)).await
+    }
+}
+
+// Exponential backoff: 250ms, 500ms, 1s, 2s, 4s, then 5s. About 18s between first and last attempt
+fn get_retry_delay(attempt_no: usize) -> Duration {
+    let delay = LOAD_PAGE_FIRST_RETRY_DELAY * 2u32.pow(attempt_no.saturating_sub(1).min(16) as u32);
+
+    if delay > LOAD_PAGE_MAX_RETRY_DELAY {
+        return LOAD_PAGE_MAX_RETRY_DELAY;
+    }
+
+    delay
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    #[test]
+    fn test_retry_delay_is_exponential_and_capped() {
+        assert_eq!(super::get_retry_delay(1), Duration::from_millis(250));
+        assert_eq!(super::get_retry_delay(2), Duration::from_millis(500));
+        assert_eq!(super::get_retry_delay(3), Duration::from_secs(1));
+        assert_eq!(super::get_retry_delay(5), Duration::from_secs(4));
+        assert_eq!(super::get_retry_delay(6), Duration::from_secs(5));
+        assert_eq!(super::get_retry_delay(100), Duration::from_secs(5));
     }
 }
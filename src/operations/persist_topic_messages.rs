@@ -1,11 +1,19 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
+use my_logger::LogEventCtx;
 use my_service_bus::shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
-use crate::{app::AppContext, messages_page::MessagesToPersistBucket, topics::Topic};
+use crate::{
+    app::AppContext, grpc_client::PersistenceError, messages_page::MessagesToPersistBucket,
+    topics::Topic, webhooks::WebhookEvent,
+};
 
 //pub const PERSIST_PAYLOAD_MAX_SIZE: usize = 1024 * 1024 * 4;
 
+const MAX_ATTEMPTS: usize = 3;
+const FIRST_RETRY_DELAY: Duration = Duration::from_millis(200);
+
 pub async fn persist_topic_messages(app: &Arc<AppContext>, topic: &Arc<Topic>) {
     let messages_to_persist: Vec<(SubPageId, Vec<MessageProtobufModel>)> =
         topic.get_messages_to_persist(|itm| itm.into()).await;
@@ -17,18 +25,113 @@ pub async fn persist_topic_messages(app: &Arc<AppContext>, topic: &Arc<Topic>) {
             bucket.add(msg);
         }
 
-        if app.settings.persist_compressed {
+        let messages = bucket.get();
+
+        let trace_parents = app.telemetry.get_persist_trace_parents(&messages);
+
+        let result = save_with_retries(app, topic.topic_id.as_str(), messages).await;
+
+        app.telemetry.record_persist_spans(
+            topic.topic_id.as_str(),
+            trace_parents,
+            result.as_ref().err().map(|err| format!("{:?}", err)),
+        );
+
+        if let Err(err) = result {
+            // Messages stay in the persist queue and are going to be saved on the next attempt
+            my_logger::LOGGER.write_error(
+                "persist_topic_messages",
+                format!("Can not persist messages. Err: {:?}", err),
+                LogEventCtx::new()
+                    .add("topicId", topic.topic_id.as_str())
+                    .add("subPageId", sub_page_id.get_value().to_string()),
+            );
+
+            break;
+        }
+
+        topic.mark_messages_as_persisted(&bucket).await;
+    }
+
+    if let Some(messages_wal) = &app.messages_wal {
+        let persisted_below = topic.get_min_not_persisted_message_id().await;
+
+        messages_wal
+            .confirm_persisted(topic.topic_id.as_str(), persisted_below.get_value())
+            .await;
+    }
+}
+
+pub async fn save_with_retries(
+    app: &Arc<AppContext>,
+    topic_id: &str,
+    messages: Vec<MessageProtobufModel>,
+) -> Result<(), PersistenceError> {
+    let mut delay = FIRST_RETRY_DELAY;
+    let mut attempt_no = 0;
+
+    loop {
+        attempt_no += 1;
+
+        if !app
+            .persistence_circuit_breaker
+            .is_call_allowed(DateTimeAsMicroseconds::now())
+        {
+            return Err(PersistenceError::CircuitIsOpen);
+        }
+
+        let result = if app.settings.persist_compressed {
             app.messages_pages_repo
-                .save_messages(topic.topic_id.as_str(), bucket.get())
+                .save_messages(topic_id, messages.clone())
                 .await
-                .unwrap();
         } else {
             app.messages_pages_repo
-                .save_messages_uncompressed(topic.topic_id.as_str(), bucket.get())
+                .save_messages_uncompressed(topic_id, messages.clone())
                 .await
-                .unwrap();
-        }
+        };
 
-        topic.mark_messages_as_persisted(&bucket).await;
+        match result {
+            Ok(_) => {
+                if app.persistence_circuit_breaker.register_success().await {
+                    app.webhooks.publish(WebhookEvent::PersistenceRecovered {});
+                }
+
+                app.prometheus
+                    .update_persistence_state(&app.persistence_circuit_breaker);
+                return Ok(());
+            }
+            Err(err) => {
+                if app
+                    .persistence_circuit_breaker
+                    .register_failure(DateTimeAsMicroseconds::now(), format!("{:?}", err))
+                    .await
+                {
+                    app.webhooks.publish(WebhookEvent::PersistenceDegraded {
+                        error: format!("{:?}", err),
+                    });
+                }
+
+                app.prometheus
+                    .update_persistence_state(&app.persistence_circuit_breaker);
+
+                if attempt_no >= MAX_ATTEMPTS {
+                    return Err(err);
+                }
+
+                my_logger::LOGGER.write_info(
+                    "persist_topic_messages",
+                    format!(
+                        "Can not persist messages. Retrying in {:?}. Err: {:?}",
+                        delay, err
+                    ),
+                    LogEventCtx::new()
+                        .add("topicId", topic_id)
+                        .add("attemptNo", attempt_no.to_string()),
+                );
+
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+        }
     }
 }
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use my_service_bus::abstractions::MessageId;
+use my_service_bus::shared::sub_page::SubPageId;
+
+use crate::{app::AppContext, messages_page::MessagesPageList, topics::Topic};
+
+// Loads the next sub page in background when queue head is close to the end of the current one,
+// so delivery does not stop to wait for the page to be loaded
+pub fn prefetch_next_sub_page_if_needed(
+    app: &Arc<AppContext>,
+    topic: &Arc<Topic>,
+    pages: &MessagesPageList,
+    queue_head: MessageId,
+    topic_message_id: MessageId,
+) {
+    let next_sub_page_id = match get_sub_page_to_prefetch(
+        app.settings.prefetch_distance as i64,
+        queue_head,
+        topic_message_id,
+    ) {
+        Some(next_sub_page_id) => next_sub_page_id,
+        None => return,
+    };
+
+    if pages.get(next_sub_page_id).is_some() {
+        return;
+    }
+
+    crate::operations::prefetch_page(app, topic.clone(), next_sub_page_id);
+}
+
+fn get_sub_page_to_prefetch(
+    prefetch_distance: i64,
+    queue_head: MessageId,
+    topic_message_id: MessageId,
+) -> Option<SubPageId> {
+    if prefetch_distance == 0 {
+        return None;
+    }
+
+    let sub_page_id: SubPageId = queue_head.into();
+
+    if sub_page_id.get_last_message_id().get_value() - queue_head.get_value() >= prefetch_distance {
+        return None;
+    }
+
+    let next_sub_page_id = SubPageId::new(sub_page_id.get_value() + 1);
+
+    // Messages of the next sub page are not published yet
+    if next_sub_page_id.get_first_message_id().get_value() >= topic_message_id.get_value() {
+        return None;
+    }
+
+    Some(next_sub_page_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_next(prefetch_distance: i64, queue_head: i64, topic_message_id: i64) -> Option<i64> {
+        get_sub_page_to_prefetch(
+            prefetch_distance,
+            queue_head.into(),
+            topic_message_id.into(),
+        )
+        .map(|sub_page_id| sub_page_id.get_value())
+    }
+
+    #[test]
+    fn test_next_sub_page_is_prefetched_within_distance() {
+        assert_eq!(get_next(100, 500, 5000), None);
+        assert_eq!(get_next(100, 899, 5000), None);
+        assert_eq!(get_next(100, 900, 5000), Some(1));
+        assert_eq!(get_next(100, 999, 5000), Some(1));
+        assert_eq!(get_next(100, 1950, 5000), Some(2));
+    }
+
+    #[test]
+    fn test_not_published_or_disabled_sub_page_is_not_prefetched() {
+        assert_eq!(get_next(100, 950, 1000), None);
+        assert_eq!(get_next(100, 950, 1001), Some(1));
+        assert_eq!(get_next(0, 999, 5000), None);
+    }
+}
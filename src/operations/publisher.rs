@@ -1,8 +1,17 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use my_service_bus::abstractions::publisher::MessageToPublish;
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
 
-use crate::{app::AppContext, sessions::SessionId, topics::Topic};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    app::{AppContext, PublishRateLimitResult},
+    auth::AclPermission,
+    sessions::SessionId,
+    topics::Topic,
+    webhooks::WebhookEvent,
+};
 
 use super::OperationFailResult;
 
@@ -11,7 +20,21 @@ pub async fn create_topic_if_not_exists(
     session_id: Option<SessionId>,
     topic_id: &str,
 ) -> Result<Arc<Topic>, OperationFailResult> {
-    let topic = app.topic_list.add_if_not_exists(topic_id).await?;
+    if let Some(cluster) = &app.cluster {
+        cluster.check_topic_owner(topic_id).await?;
+    }
+
+    if let Some(session_id) = session_id {
+        super::check_session_permission(app, session_id, AclPermission::Publish, topic_id).await?;
+    }
+
+    let (topic, created) = app.topic_list.add_if_not_exists(topic_id).await?;
+
+    if created {
+        app.webhooks.publish(WebhookEvent::TopicCreated {
+            topic_id: topic_id.to_string(),
+        });
+    }
 
     let mut reusable_topics = crate::topics::ReusableTopicsList::new();
     crate::operations::persist_topics_and_queues(&app, &mut reusable_topics).await;
@@ -26,22 +49,96 @@ pub async fn create_topic_if_not_exists(
     return Ok(topic);
 }
 
-pub async fn publish(
+// Rate limit tokens are taken before publish and are given back if publish fails.
+// Returns delay the publish response has to be sent with to slow down the publisher
+pub async fn publish_with_rate_limit(
     app: &Arc<AppContext>,
+    session_id: SessionId,
+    app_name: &str,
     topic_id: &str,
     messages: Vec<MessageToPublish>,
     persist_immediately: bool,
+) -> Result<Option<Duration>, OperationFailResult> {
+    let delay = check_publish_rate_limit(app, session_id, app_name, topic_id, &messages)?;
+
+    let messages_count = messages.len();
+    let bytes = messages.iter().map(|itm| itm.content.len()).sum();
+
+    if let Err(err) = publish(app, topic_id, messages, persist_immediately, session_id).await {
+        app.publish_rate_limiter
+            .refund(session_id, app_name, topic_id, messages_count, bytes);
+        return Err(err);
+    }
+
+    Ok(delay)
+}
+
+fn check_publish_rate_limit(
+    app: &AppContext,
+    session_id: SessionId,
+    app_name: &str,
+    topic_id: &str,
+    messages: &[MessageToPublish],
+) -> Result<Option<Duration>, OperationFailResult> {
+    if !app.publish_rate_limiter.is_enabled() {
+        return Ok(None);
+    }
+
+    let bytes = messages.iter().map(|itm| itm.content.len()).sum();
+
+    match app.publish_rate_limiter.acquire(
+        session_id,
+        app_name,
+        topic_id,
+        messages.len(),
+        bytes,
+        DateTimeAsMicroseconds::now(),
+    ) {
+        PublishRateLimitResult::Allowed => Ok(None),
+        PublishRateLimitResult::Delayed(delay) => Ok(Some(delay)),
+        PublishRateLimitResult::Rejected(retry_after) => {
+            Err(OperationFailResult::PublishRateLimitIsExceeded {
+                topic_id: topic_id.to_string(),
+                retry_after,
+            })
+        }
+    }
+}
+
+pub async fn publish(
+    app: &Arc<AppContext>,
+    topic_id: &str,
+    mut messages: Vec<MessageToPublish>,
+    persist_immediately: bool,
     session_id: SessionId,
 ) -> Result<(), OperationFailResult> {
     if app.states.is_shutting_down() {
         return Err(OperationFailResult::ShuttingDown);
     }
 
+    if app.replication.is_passive() {
+        return Err(OperationFailResult::NodeIsPassive);
+    }
+
+    if let Some(cluster) = &app.cluster {
+        cluster.check_topic_owner(topic_id).await?;
+    }
+
+    super::check_session_permission(app, session_id, AclPermission::Publish, topic_id).await?;
+
     let mut topic = app.topic_list.get(topic_id).await;
 
     if topic.is_none() {
-        if app.settings.auto_create_topic_on_publish {
-            topic = Some(app.topic_list.add_if_not_exists(topic_id).await?);
+        if app.runtime_settings.auto_create_topic_on_publish() {
+            let (created_topic, created) = app.topic_list.add_if_not_exists(topic_id).await?;
+
+            if created {
+                app.webhooks.publish(WebhookEvent::TopicCreated {
+                    topic_id: topic_id.to_string(),
+                });
+            }
+
+            topic = Some(created_topic);
         } else {
             return Err(OperationFailResult::TopicNotFound {
                 topic_id: topic_id.to_string(),
@@ -51,11 +148,44 @@ pub async fn publish(
 
     let topic = topic.unwrap();
 
+    let publish_spans = app
+        .telemetry
+        .start_publish_spans(topic_id, messages.as_mut_slice());
+
     let mut topic_data = topic.get_access().await;
 
+    let messages = topic_data.prepare_messages(messages);
+
+    // Publish is confirmed only after messages are fsynced to the write-ahead spool.
+    // Messages are spooled before they become visible, so rejected publish is never delivered.
+    // Topic lock is not held during the disk I/O, ids of the messages are reserved instead
+    if let Some(messages_wal) = &app.messages_wal {
+        if topic_data.persist {
+            let messages_to_spool: Vec<MessageProtobufModel> =
+                messages.iter().map(|itm| itm.into()).collect();
+
+            topic_data.reserve_prepared_messages(&messages);
+            drop(topic_data);
+
+            let result = messages_wal
+                .append(topic.topic_id.as_str(), messages_to_spool)
+                .await;
+
+            topic_data = topic.get_access().await;
+            topic_data.release_reserved_messages(&messages);
+
+            if let Err(err) = result {
+                drop(topic_data);
+                app.telemetry
+                    .end_publish_spans(publish_spans, Some(err.clone()));
+                return Err(OperationFailResult::PersistenceError(err));
+            }
+        }
+    }
+
     let messages_count = messages.len();
 
-    topic_data.publish_messages(session_id, messages);
+    topic_data.add_prepared_messages(session_id, messages);
 
     topic_data.statistics.update_messages_count(messages_count);
 
@@ -73,5 +203,10 @@ pub async fn publish(
     crate::operations::delivery::try_to_deliver_to_subscribers(&app, &topic, &mut topic_data).await;
     #[cfg(not(test))]
     crate::operations::delivery::try_to_deliver_to_subscribers(&app, &topic, &mut topic_data);
+
+    drop(topic_data);
+
+    app.telemetry.end_publish_spans(publish_spans, None);
+
     Ok(())
 }
@@ -1,6 +1,6 @@
 use my_service_bus::abstractions::MessageId;
 
-use crate::app::AppContext;
+use crate::{app::AppContext, webhooks::WebhookEvent};
 
 use super::OperationFailResult;
 
@@ -50,7 +50,12 @@ pub async fn delete_queue(
 
     let mut topic_data = topic.get_access().await;
 
-    topic_data.queues.remove(queue_id);
+    if topic_data.queues.remove(queue_id).is_some() {
+        app.webhooks.publish(WebhookEvent::QueueDeleted {
+            topic_id: topic_id.to_string(),
+            queue_id: queue_id.to_string(),
+        });
+    }
 
     app.prometheus.queue_is_deleted(topic_id, queue_id);
 
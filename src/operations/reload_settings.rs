@@ -0,0 +1,148 @@
+use my_logger::LogEventCtx;
+
+use crate::{
+    app::{AppContext, RuntimeSettingsValues, HOT_RELOADABLE_SETTINGS},
+    settings::SettingsModelYaml,
+};
+
+pub struct SettingChange {
+    pub name: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+pub struct SettingsReloadResult {
+    pub applied: Vec<SettingChange>,
+    // Settings which are changed in the file, but are applied only after restart
+    pub requires_restart: Vec<String>,
+}
+
+pub async fn reload_settings(app: &AppContext) -> Result<SettingsReloadResult, String> {
+    let yaml = match SettingsModelYaml::read().await {
+        Ok(yaml) => yaml,
+        Err(err) => {
+            write_reload_error(err.as_str());
+            return Err(err);
+        }
+    };
+
+    let new_values = match RuntimeSettingsValues::from_yaml(&yaml) {
+        Ok(new_values) => new_values,
+        Err(err) => {
+            write_reload_error(err.as_str());
+            return Err(err);
+        }
+    };
+
+    let requires_restart = get_settings_requiring_restart(app, &yaml);
+
+    if let Some(auth) = &app.auth {
+        if auth.has_api_keys_file() {
+            match auth.reload_api_keys_file().await {
+                Ok(api_keys_amount) => {
+                    my_logger::LOGGER.write_info(
+                        "Reload Settings",
+                        format!("Api keys are reloaded. {} api keys", api_keys_amount),
+                        LogEventCtx::new(),
+                    );
+                }
+                Err(err) => {
+                    write_reload_error(err.as_str());
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    let applied: Vec<SettingChange> = app
+        .runtime_settings
+        .get_values()
+        .get_changes(&new_values)
+        .into_iter()
+        .map(|(name, old_value, new_value)| SettingChange {
+            name: name.to_string(),
+            old_value,
+            new_value,
+        })
+        .collect();
+
+    app.runtime_settings.apply(&new_values);
+
+    for change in &applied {
+        my_logger::LOGGER.write_info(
+            "Reload Settings",
+            format!("Setting {} is changed", change.name),
+            LogEventCtx::new()
+                .add("oldValue", change.old_value.as_str())
+                .add("newValue", change.new_value.as_str()),
+        );
+    }
+
+    for name in &requires_restart {
+        my_logger::LOGGER.write_info(
+            "Reload Settings",
+            format!(
+                "Setting {} is changed but can not be applied without restart",
+                name
+            ),
+            LogEventCtx::new(),
+        );
+    }
+
+    if applied.is_empty() && requires_restart.is_empty() {
+        my_logger::LOGGER.write_info(
+            "Reload Settings",
+            "Settings are reloaded. Nothing is changed".to_string(),
+            LogEventCtx::new(),
+        );
+    }
+
+    Ok(SettingsReloadResult {
+        applied,
+        requires_restart,
+    })
+}
+
+fn get_settings_requiring_restart(app: &AppContext, yaml: &SettingsModelYaml) -> Vec<String> {
+    let mut result = Vec::new();
+
+    let startup_yaml = match &app.settings.yaml {
+        Some(startup_yaml) => startup_yaml,
+        None => return result,
+    };
+
+    let new_yaml = match serde_yaml::to_value(yaml) {
+        Ok(new_yaml) => new_yaml,
+        Err(_) => return result,
+    };
+
+    let (startup_yaml, new_yaml) = match (startup_yaml.as_mapping(), new_yaml.as_mapping()) {
+        (Some(startup_yaml), Some(new_yaml)) => (startup_yaml, new_yaml),
+        _ => return result,
+    };
+
+    for (key, new_value) in new_yaml {
+        let name = match key.as_str() {
+            Some(name) => name,
+            None => continue,
+        };
+
+        if HOT_RELOADABLE_SETTINGS.contains(&name) {
+            continue;
+        }
+
+        if startup_yaml.get(key) != Some(new_value) {
+            result.push(name.to_string());
+        }
+    }
+
+    result
+}
+
+fn write_reload_error(err: &str) {
+    my_logger::LOGGER.write_error(
+        "Reload Settings",
+        format!("Settings are not reloaded. {}", err),
+        LogEventCtx::new(),
+    );
+}
@@ -1,9 +1,10 @@
-use crate::topics::TopicInner;
+use crate::{app::AppContext, topics::TopicInner};
 
 use super::delivery::SubscriberPackageBuilder;
 
 #[cfg(not(test))]
 pub fn send_new_messages_to_deliver(
+    app: &AppContext,
     builder: SubscriberPackageBuilder,
     topic_data: &mut TopicInner,
     compilation_duration: std::time::Duration,
@@ -20,6 +21,12 @@ pub fn send_new_messages_to_deliver(
 
                 subscriber.metrics.set_started_delivery();
 
+                app.prometheus.observe_delivery_compilation_duration(
+                    queue.topic_id.as_str(),
+                    queue.queue_id.as_str(),
+                    subscriber.delivery_compilation_duration,
+                );
+
                 builder.send_messages_to_connection();
             } else {
                 subscriber.cancel_the_rent();
@@ -30,6 +37,7 @@ pub fn send_new_messages_to_deliver(
 
 #[cfg(test)]
 pub async fn send_new_messages_to_deliver(
+    app: &AppContext,
     builder: SubscriberPackageBuilder,
     topic_data: &mut TopicInner,
     compilation_duration: std::time::Duration,
@@ -46,6 +54,12 @@ pub async fn send_new_messages_to_deliver(
 
                 subscriber.metrics.set_started_delivery();
 
+                app.prometheus.observe_delivery_compilation_duration(
+                    queue.topic_id.as_str(),
+                    queue.queue_id.as_str(),
+                    subscriber.delivery_compilation_duration,
+                );
+
                 builder.send_messages_to_connection().await;
             } else {
                 subscriber.cancel_the_rent();
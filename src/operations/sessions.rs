@@ -1,11 +1,21 @@
 use std::sync::Arc;
 
-use crate::{app::AppContext, sessions::MyServiceBusSession};
+use crate::{app::AppContext, sessions::MyServiceBusSession, webhooks::WebhookEvent};
 
 pub async fn disconnect(
     app: &AppContext,
     disconnected_session: Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
 ) {
+    app.publish_rate_limiter
+        .remove_session(disconnected_session.get_session_id());
+
+    app.webhooks.publish(WebhookEvent::SessionDisconnected {
+        session_id: disconnected_session.get_session_id().get_value(),
+        name: disconnected_session.get_name_and_version().name,
+        ip: disconnected_session.get_metrics().ip,
+        session_type: disconnected_session.get_session_type().as_str().to_string(),
+    });
+
     let topics = app.topic_list.get_all().await;
 
     for topic in &topics {
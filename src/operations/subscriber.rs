@@ -5,9 +5,11 @@ use my_service_bus::abstractions::subscriber::TopicQueueType;
 
 use crate::{
     app::AppContext,
+    auth::AclPermission,
     queue_subscribers::{QueueSubscriber, SubscriberId},
     queues::TopicQueue,
-    sessions::MyServiceBusSession,
+    sessions::{MyServiceBusSession, SessionId},
+    webhooks::WebhookEvent,
 };
 
 use super::OperationFailResult;
@@ -19,14 +21,39 @@ pub async fn subscribe_to_queue(
     queue_type: TopicQueueType,
     session: Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
 ) -> Result<SubscriberId, OperationFailResult> {
+    if app.replication.is_passive() {
+        return Err(OperationFailResult::NodeIsPassive);
+    }
+
+    if let Some(cluster) = &app.cluster {
+        cluster.check_topic_owner(topic_id.as_str()).await?;
+    }
+
+    super::check_permission(
+        app,
+        session.get_session_id(),
+        session.get_identity(),
+        AclPermission::Subscribe,
+        topic_id.as_str(),
+    )?;
+
     let topic = {
         let topic = app.topic_list.get(topic_id.as_str()).await;
 
         match topic {
             Some(result) => result,
             None => {
-                if app.settings.auto_create_topic_on_subscribe {
-                    app.topic_list.add_if_not_exists(topic_id.as_str()).await?
+                if app.runtime_settings.auto_create_topic_on_subscribe() {
+                    let (topic, created) =
+                        app.topic_list.add_if_not_exists(topic_id.as_str()).await?;
+
+                    if created {
+                        app.webhooks.publish(WebhookEvent::TopicCreated {
+                            topic_id: topic_id.clone(),
+                        });
+                    }
+
+                    topic
                 } else {
                     return Err(OperationFailResult::TopicNotFound { topic_id });
                 }
@@ -36,12 +63,31 @@ pub async fn subscribe_to_queue(
 
     let mut topic_data = topic.get_access().await;
 
+    let queue_is_created = topic_data.queues.get(queue_id.as_str()).is_none();
+
+    if queue_is_created {
+        super::check_permission(
+            app,
+            session.get_session_id(),
+            session.get_identity(),
+            AclPermission::CreateQueue,
+            queue_id.as_str(),
+        )?;
+    }
+
     let topic_queue = topic_data.queues.add_queue_if_not_exists(
         topic.topic_id.clone(),
         queue_id,
         queue_type.clone(),
     );
 
+    if queue_is_created {
+        app.webhooks.publish(WebhookEvent::QueueCreated {
+            topic_id: topic.topic_id.as_str().to_string(),
+            queue_id: topic_queue.queue_id.as_str().to_string(),
+        });
+    }
+
     let subscriber_id = app.subscriber_id_generator.get_next_subscriber_id();
 
     topic_queue.update_queue_type(queue_type);
@@ -97,6 +143,54 @@ pub async fn subscribe_to_queue(
     Ok(subscriber_id)
 }
 
+pub async fn unsubscribe_from_queue(
+    app: &Arc<AppContext>,
+    topic_id: &str,
+    queue_id: &str,
+    session_id: SessionId,
+) -> Result<(), OperationFailResult> {
+    let topic = app
+        .topic_list
+        .get(topic_id)
+        .await
+        .ok_or(OperationFailResult::TopicNotFound {
+            topic_id: topic_id.to_string(),
+        })?;
+
+    let mut topic_data = topic.get_access().await;
+
+    {
+        let topic_queue =
+            topic_data
+                .queues
+                .get_mut(queue_id)
+                .ok_or(OperationFailResult::QueueNotFound {
+                    queue_id: queue_id.to_string(),
+                })?;
+
+        if let Some(subscriber) = topic_queue.subscribers.remove_by_session_id(session_id) {
+            my_logger::LOGGER.write_info(
+                "unsubscribe_from_queue",
+                "Unsubscribed.",
+                LogEventCtx::new()
+                    .add("topicId", topic_id)
+                    .add("queueId", queue_id)
+                    .add("subscriberId", subscriber.id.get_value().to_string())
+                    .add("sessionId", session_id.get_value().to_string()),
+            );
+
+            remove_subscriber(topic_queue, subscriber);
+        }
+    }
+
+    #[cfg(test)]
+    crate::operations::delivery::try_to_deliver_to_subscribers(app, &topic, &mut topic_data).await;
+    #[cfg(not(test))]
+    crate::operations::delivery::try_to_deliver_to_subscribers(app, &topic, &mut topic_data);
+
+    Ok(())
+}
+
 pub fn remove_subscriber(queue: &mut TopicQueue, mut subscriber: QueueSubscriber) {
     let messages = subscriber.reset_delivery();
 
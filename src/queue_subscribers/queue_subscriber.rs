@@ -183,7 +183,8 @@ impl QueueSubscriber {
     }
 
     //todo!("TechDebt: We does not call it with intermediary confirmed messages");
-    pub fn update_delivery_time(&mut self, amount: usize, positive: bool) {
+    // Returns duration from the start of delivery till the confirmation
+    pub fn update_delivery_time(&mut self, amount: usize, positive: bool) -> Duration {
         let delivery_duration = DateTimeAsMicroseconds::now()
             .duration_since(self.metrics.start_delivery_time)
             .as_positive_or_zero();
@@ -201,6 +202,8 @@ impl QueueSubscriber {
             self.metrics
                 .set_not_delivered_statistic(amount as i32, delivery_duration);
         }
+
+        delivery_duration
     }
 }
 
@@ -4,7 +4,7 @@ use my_service_bus::abstractions::{subscriber::TopicQueueType, MessageId};
 use rust_extensions::{date_time::DateTimeAsMicroseconds, sorted_vec::SortedVec};
 
 use crate::{
-    queues::QueueId,
+    queues::{DeliveryBucket, QueueId},
     sessions::{MyServiceBusSession, SessionId},
     topics::TopicId,
     utils::*,
@@ -19,6 +19,7 @@ pub enum SubscribersData {
 
 pub struct DeadSubscriber {
     pub subscriber_id: SubscriberId,
+    pub queue_id: QueueId,
     pub session: Arc<dyn MyServiceBusSession + Send + Sync + 'static>,
     pub duration: Duration,
 }
@@ -28,6 +29,7 @@ impl DeadSubscriber {
         Self {
             session: subscriber.session.clone(),
             subscriber_id: subscriber.id,
+            queue_id: subscriber.queue_id.clone(),
             duration,
         }
     }
@@ -81,6 +83,29 @@ impl SubscribersList {
         }
     }
 
+    pub fn reset_deliveries(&mut self) -> Vec<DeliveryBucket> {
+        let mut result = Vec::new();
+
+        match &mut self.data {
+            SubscribersData::MultiSubscribers(subscribers) => {
+                for subscriber in subscribers.iter_mut() {
+                    if let Some(bucket) = subscriber.reset_delivery() {
+                        result.push(bucket);
+                    }
+                }
+            }
+            SubscribersData::SingleSubscriber(subscriber) => {
+                if let Some(subscriber) = subscriber {
+                    if let Some(bucket) = subscriber.reset_delivery() {
+                        result.push(bucket);
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
     pub fn get_all(&self) -> Option<Vec<&QueueSubscriber>> {
         match &self.data {
             SubscribersData::MultiSubscribers(hash_map) => {
@@ -31,6 +31,10 @@ impl DeliveryAttempts {
         }
     }
 
+    pub fn is_redelivery(&self, message_id: MessageId) -> bool {
+        self.attempts.get(&message_id).is_some()
+    }
+
     pub fn reset(&mut self, message_id: MessageId) {
         self.attempts.remove(&message_id);
     }
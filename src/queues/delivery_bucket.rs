@@ -1,14 +1,20 @@
 use my_service_bus::abstractions::queue_with_intervals::QueueWithIntervals;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 #[derive(Debug)]
 pub struct DeliveryBucket {
     pub ids: QueueWithIntervals,
     pub confirmed: usize,
+    pub started: DateTimeAsMicroseconds,
 }
 
 impl DeliveryBucket {
     pub fn new(ids: QueueWithIntervals) -> Self {
-        Self { ids, confirmed: 0 }
+        Self {
+            ids,
+            confirmed: 0,
+            started: DateTimeAsMicroseconds::now(),
+        }
     }
 
     pub fn confirmed(&mut self, confirmed: &QueueWithIntervals) {
@@ -8,6 +8,7 @@ use rust_extensions::sorted_vec::EntityWithStrKey;
 use crate::{
     queue_subscribers::{SubscriberId, SubscribersList},
     topics::{TopicId, TopicQueueSnapshot},
+    utils::MinMessageIdCalculator,
 };
 
 use super::{delivery_attempts::DeliveryAttempts, QueueId};
@@ -63,6 +64,14 @@ impl TopicQueue {
         MessageId::from_opt_i64(self.queue.get_min_id())
     }
 
+    // Messages which are on delivery are not confirmed yet, so they are undelivered as well
+    pub fn get_oldest_undelivered_message_id(&self) -> Option<MessageId> {
+        let mut min_message_id = MinMessageIdCalculator::new();
+        min_message_id.add(self.queue.get_min_id());
+        min_message_id.add(self.subscribers.get_min_message_id());
+        min_message_id.get()
+    }
+
     pub fn get_snapshot_to_persist(&self) -> Option<TopicQueueSnapshot> {
         match self.queue_type {
             TopicQueueType::Permanent => {
@@ -108,6 +117,18 @@ impl TopicQueue {
         self.subscribers.get_on_delivery_amount()
     }
 
+    // Returns amount of messages which are put back to the queue
+    pub fn requeue_messages_on_delivery(&mut self) -> usize {
+        let mut result = 0;
+
+        for bucket in self.subscribers.reset_deliveries() {
+            result += bucket.ids.queue_size();
+            self.confirm_non_delivered(&bucket.ids);
+        }
+
+        result
+    }
+
     pub fn one_second_tick(&mut self) {
         self.subscribers.one_second_tick();
     }
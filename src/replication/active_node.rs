@@ -0,0 +1,178 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
+
+use my_logger::LogEventCtx;
+use my_service_bus::shared::{protobuf_models::MessageProtobufModel, sub_page::SubPageId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::{
+    app::{AppContext, ListenerStatus},
+    topics::ReusableTopicsList,
+};
+
+use super::{write_frame, ReplicationFrameContract, ReplicationMessagesContract};
+
+const MAX_MESSAGES_PER_TOPIC_IN_FRAME: i64 = 10_000;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+pub fn start(app: Arc<AppContext>, listener_status: Arc<ListenerStatus>) {
+    tokio::spawn(accept_loop(app, listener_status));
+}
+
+async fn accept_loop(app: Arc<AppContext>, listener_status: Arc<ListenerStatus>) {
+    let addr = listener_status.address;
+
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            panic!(
+                "Can not start replication server at {}. Err: {:?}",
+                addr, err
+            );
+        }
+    };
+
+    println!("Replication server is started at {}", addr);
+
+    while !app.states.is_shutting_down() {
+        match listener.accept().await {
+            Ok((stream, peer_addr)) => {
+                let app = app.clone();
+                let listener_status = listener_status.clone();
+
+                tokio::spawn(async move {
+                    listener_status.connected();
+                    handle_passive_node(app, stream, peer_addr).await;
+                    listener_status.disconnected();
+                });
+            }
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "Replication Accept".to_string(),
+                    format!("{:?}", err),
+                    LogEventCtx::new(),
+                );
+
+                tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+            }
+        }
+    }
+}
+
+async fn handle_passive_node(app: Arc<AppContext>, mut stream: TcpStream, peer_addr: SocketAddr) {
+    let secret = app.settings.replication_secret.as_ref().unwrap();
+
+    let handshake = match tokio::time::timeout(
+        HANDSHAKE_TIMEOUT,
+        super::handshake::authenticate_passive_node(&mut stream, secret.as_str()),
+    )
+    .await
+    {
+        Ok(result) => result,
+        Err(err) => Err(err.into()),
+    };
+
+    if let Err(err) = handshake {
+        my_logger::LOGGER.write_error(
+            "Replication",
+            format!("Passive node is rejected. Err: {:?}", err),
+            LogEventCtx::new().add("ip", peer_addr.to_string()),
+        );
+        return;
+    }
+
+    // Snapshot of not initialized node is empty. Passive node would drop all its topics
+    while !app.states.is_initialized() {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    my_logger::LOGGER.write_info(
+        "Replication",
+        "Passive node is connected".to_string(),
+        LogEventCtx::new().add("ip", peer_addr.to_string()),
+    );
+
+    app.replication.passive_node_connected();
+
+    let mut reusable_topics = ReusableTopicsList::new();
+    let mut next_message_ids: HashMap<String, i64> = HashMap::new();
+
+    while !app.states.is_shutting_down() {
+        let frame = compile_frame(&app, &mut reusable_topics, &mut next_message_ids).await;
+
+        if let Err(err) = write_frame(&mut stream, &frame).await {
+            my_logger::LOGGER.write_info(
+                "Replication",
+                format!("Passive node is disconnected. Err: {:?}", err),
+                LogEventCtx::new().add("ip", peer_addr.to_string()),
+            );
+            break;
+        }
+
+        tokio::time::sleep(app.settings.replication_max_lag).await;
+    }
+
+    app.replication.passive_node_disconnected();
+}
+
+// next_message_ids - id of the first message of each topic which is not sent to the passive node yet
+async fn compile_frame(
+    app: &AppContext,
+    reusable_topics: &mut ReusableTopicsList,
+    next_message_ids: &mut HashMap<String, i64>,
+) -> ReplicationFrameContract {
+    app.topic_list.fill_topics(reusable_topics).await;
+
+    let mut snapshot = Vec::with_capacity(reusable_topics.len());
+    let mut messages = Vec::new();
+
+    for topic in reusable_topics.iter() {
+        snapshot.push(topic.get_topic_snapshot().await);
+
+        let topic_data = topic.get_access().await;
+
+        let to_message_id = topic_data.message_id.get_value();
+
+        // Recently connected passive node gets current sub page as well
+        let from_message_id = match next_message_ids.get(topic.topic_id.as_str()) {
+            Some(next_message_id) => *next_message_id,
+            None => {
+                let sub_page_id: SubPageId = topic_data.message_id.into();
+                sub_page_id.get_first_message_id().get_value()
+            }
+        };
+
+        let to_message_id = to_message_id.min(from_message_id + MAX_MESSAGES_PER_TOPIC_IN_FRAME);
+
+        let topic_messages: Vec<MessageProtobufModel> = if from_message_id < to_message_id {
+            topic_data.get_messages(from_message_id..to_message_id, |itm| itm.into())
+        } else {
+            vec![]
+        };
+
+        // Sent even without new messages, so passive node knows what is persisted already
+        messages.push(ReplicationMessagesContract {
+            topic_id: topic.topic_id.to_string(),
+            messages: topic_messages,
+            persisted_below: topic_data.get_min_not_persisted_message_id().get_value(),
+        });
+
+        next_message_ids.insert(
+            topic.topic_id.to_string(),
+            to_message_id.max(from_message_id),
+        );
+    }
+
+    next_message_ids.retain(|topic_id, _| {
+        reusable_topics
+            .iter()
+            .any(|topic| topic.topic_id.as_str() == topic_id.as_str())
+    });
+
+    ReplicationFrameContract {
+        moment: DateTimeAsMicroseconds::now().unix_microseconds,
+        snapshot: Some(snapshot.into()),
+        messages,
+    }
+}
@@ -0,0 +1,96 @@
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::persistence_grpc::SaveQueueSnapshotGrpcRequest;
+
+use super::ReplicationError;
+
+const FRAME_HEADER_SIZE: usize = 4;
+const MAX_FRAME_SIZE: usize = 1024 * 1024 * 512;
+
+// Frame which active node sends to passive node every ReplicationMaxLag
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplicationFrameContract {
+    #[prost(int64, tag = "1")]
+    pub moment: i64,
+
+    #[prost(message, optional, tag = "2")]
+    pub snapshot: Option<SaveQueueSnapshotGrpcRequest>,
+
+    #[prost(message, repeated, tag = "3")]
+    pub messages: Vec<ReplicationMessagesContract>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReplicationMessagesContract {
+    #[prost(string, tag = "1")]
+    pub topic_id: ::prost::alloc::string::String,
+
+    #[prost(message, repeated, tag = "2")]
+    pub messages: Vec<MessageProtobufModel>,
+
+    // Messages of the topic with id below the value are persisted by active node
+    #[prost(int64, tag = "3")]
+    pub persisted_below: i64,
+}
+
+// Frame is serialized as [payload len: u32 LE][ReplicationFrameContract]
+pub async fn write_frame(
+    write: &mut (impl AsyncWriteExt + Unpin),
+    frame: &ReplicationFrameContract,
+) -> Result<(), ReplicationError> {
+    let mut payload = Vec::new();
+    prost::Message::encode(frame, &mut payload).unwrap();
+
+    let mut result = Vec::with_capacity(payload.len() + FRAME_HEADER_SIZE);
+    result.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    result.extend_from_slice(payload.as_slice());
+
+    write.write_all(result.as_slice()).await?;
+    Ok(())
+}
+
+pub async fn read_frame(
+    read: &mut (impl AsyncReadExt + Unpin),
+) -> Result<ReplicationFrameContract, ReplicationError> {
+    let mut header = [0u8; FRAME_HEADER_SIZE];
+    read.read_exact(&mut header).await?;
+
+    let len = u32::from_le_bytes(header) as usize;
+
+    if len > MAX_FRAME_SIZE {
+        return Err(ReplicationError::FrameIsTooBig(len));
+    }
+
+    let mut payload = vec![0u8; len];
+    read.read_exact(&mut payload).await?;
+
+    Ok(prost::Message::decode(payload.as_slice())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_and_read_frame() {
+        let frame = ReplicationFrameContract {
+            moment: 5,
+            snapshot: Some(SaveQueueSnapshotGrpcRequest {
+                queue_snapshot: vec![],
+            }),
+            messages: vec![ReplicationMessagesContract {
+                topic_id: "test-topic".to_string(),
+                messages: vec![],
+                persisted_below: 3,
+            }],
+        };
+
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, &frame).await.unwrap();
+
+        let result = read_frame(&mut buffer.as_slice()).await.unwrap();
+
+        assert_eq!(frame, result);
+    }
+}
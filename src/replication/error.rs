@@ -0,0 +1,26 @@
+#[derive(Debug)]
+pub enum ReplicationError {
+    IoError(std::io::Error),
+    InvalidProtobufPayload(prost::DecodeError),
+    FrameIsTooBig(usize),
+    AuthenticationFailed,
+    HandshakeTimeout,
+}
+
+impl From<std::io::Error> for ReplicationError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
+
+impl From<tokio::time::error::Elapsed> for ReplicationError {
+    fn from(_: tokio::time::error::Elapsed) -> Self {
+        Self::HandshakeTimeout
+    }
+}
+
+impl From<prost::DecodeError> for ReplicationError {
+    fn from(src: prost::DecodeError) -> Self {
+        Self::InvalidProtobufPayload(src)
+    }
+}
@@ -0,0 +1,131 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use super::ReplicationError;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const NONCE_SIZE: usize = 32;
+const PROOF_SIZE: usize = 32;
+
+const ACTIVE_NODE_ROLE: &[u8] = b"active";
+const PASSIVE_NODE_ROLE: &[u8] = b"passive";
+
+// Both nodes prove they know ReplicationSecret without sending it:
+// active -> passive: [active nonce]
+// passive -> active: [passive nonce][HMAC-SHA256(secret, "passive" + active nonce)]
+// active -> passive: [HMAC-SHA256(secret, "active" + passive nonce)]
+pub async fn authenticate_passive_node(
+    stream: &mut (impl AsyncReadExt + AsyncWriteExt + Unpin),
+    secret: &str,
+) -> Result<(), ReplicationError> {
+    let active_nonce = generate_nonce();
+    stream.write_all(&active_nonce).await?;
+
+    let mut passive_nonce = [0u8; NONCE_SIZE];
+    stream.read_exact(&mut passive_nonce).await?;
+
+    let mut proof = [0u8; PROOF_SIZE];
+    stream.read_exact(&mut proof).await?;
+
+    verify_proof(secret, PASSIVE_NODE_ROLE, &active_nonce, &proof)?;
+
+    stream
+        .write_all(&get_proof(secret, ACTIVE_NODE_ROLE, &passive_nonce))
+        .await?;
+
+    Ok(())
+}
+
+pub async fn authenticate_active_node(
+    stream: &mut (impl AsyncReadExt + AsyncWriteExt + Unpin),
+    secret: &str,
+) -> Result<(), ReplicationError> {
+    let mut active_nonce = [0u8; NONCE_SIZE];
+    stream.read_exact(&mut active_nonce).await?;
+
+    let passive_nonce = generate_nonce();
+
+    let mut payload = Vec::with_capacity(NONCE_SIZE + PROOF_SIZE);
+    payload.extend_from_slice(&passive_nonce);
+    payload.extend_from_slice(&get_proof(secret, PASSIVE_NODE_ROLE, &active_nonce));
+    stream.write_all(payload.as_slice()).await?;
+
+    let mut proof = [0u8; PROOF_SIZE];
+    stream.read_exact(&mut proof).await?;
+
+    verify_proof(secret, ACTIVE_NODE_ROLE, &passive_nonce, &proof)
+}
+
+fn generate_nonce() -> [u8; NONCE_SIZE] {
+    let mut result = [0u8; NONCE_SIZE];
+    result[..16].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    result[16..].copy_from_slice(uuid::Uuid::new_v4().as_bytes());
+    result
+}
+
+fn create_mac(secret: &str, role: &[u8], nonce: &[u8]) -> HmacSha256 {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(role);
+    mac.update(nonce);
+    mac
+}
+
+fn get_proof(secret: &str, role: &[u8], nonce: &[u8]) -> Vec<u8> {
+    create_mac(secret, role, nonce)
+        .finalize()
+        .into_bytes()
+        .to_vec()
+}
+
+fn verify_proof(
+    secret: &str,
+    role: &[u8],
+    nonce: &[u8],
+    proof: &[u8],
+) -> Result<(), ReplicationError> {
+    create_mac(secret, role, nonce)
+        .verify_slice(proof)
+        .map_err(|_| ReplicationError::AuthenticationFailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn run_handshake(
+        active_secret: &'static str,
+        passive_secret: &'static str,
+    ) -> (Result<(), ReplicationError>, Result<(), ReplicationError>) {
+        let (mut active_stream, mut passive_stream) = tokio::io::duplex(1024);
+
+        let active = tokio::spawn(async move {
+            authenticate_passive_node(&mut active_stream, active_secret).await
+        });
+
+        let passive = authenticate_active_node(&mut passive_stream, passive_secret).await;
+
+        (active.await.unwrap(), passive)
+    }
+
+    #[tokio::test]
+    async fn test_nodes_with_the_same_secret_are_authenticated() {
+        let (active, passive) = run_handshake("secret", "secret").await;
+
+        assert!(active.is_ok());
+        assert!(passive.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_passive_node_with_other_secret_is_rejected() {
+        let (active, passive) = run_handshake("secret", "other-secret").await;
+
+        assert!(matches!(
+            active,
+            Err(ReplicationError::AuthenticationFailed)
+        ));
+        assert!(passive.is_err());
+    }
+}
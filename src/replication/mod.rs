@@ -0,0 +1,12 @@
+mod active_node;
+mod contracts;
+mod error;
+mod handshake;
+mod passive_node;
+mod replication_state;
+
+pub use active_node::start as start_active_node;
+pub use contracts::*;
+pub use error::*;
+pub use passive_node::start as start_passive_node;
+pub use replication_state::*;
@@ -0,0 +1,368 @@
+use std::{sync::Arc, time::Duration};
+
+use my_logger::LogEventCtx;
+use my_service_bus::abstractions::{queue_with_intervals::QueueWithIntervals, MessageId};
+use my_service_bus::shared::sub_page::SubPageId;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use tokio::{net::TcpStream, sync::mpsc::UnboundedSender};
+
+use crate::{
+    app::AppContext,
+    messages_page::{GetMessageResult, MySbMessageContent},
+    topics::{ReusableTopicsList, TopicInner, TopicSnapshot},
+};
+
+use super::{read_frame, ReplicationFrameContract, ReplicationMessagesContract};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const CHECK_PROMOTION_INTERVAL: Duration = Duration::from_secs(1);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub fn start(app: Arc<AppContext>) {
+    tokio::spawn(replicate(app));
+}
+
+async fn replicate(app: Arc<AppContext>) {
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    tokio::spawn(read_loop(app.clone(), sender));
+
+    let started = DateTimeAsMicroseconds::now();
+    let mut lease_expiration_is_reported = false;
+
+    while app.replication.is_passive() {
+        if let Ok(Some(frame)) =
+            tokio::time::timeout(CHECK_PROMOTION_INTERVAL, receiver.recv()).await
+        {
+            apply_frame(app.as_ref(), frame).await;
+            app.replication
+                .update_last_frame_received(DateTimeAsMicroseconds::now());
+            lease_expiration_is_reported = false;
+        }
+
+        if let Some(lease_timeout) = app.settings.replication_lease_timeout {
+            let last_frame_received = app.replication.get_last_frame_received().unwrap_or(started);
+
+            let silent_for = DateTimeAsMicroseconds::now()
+                .duration_since(last_frame_received)
+                .as_positive_or_zero();
+
+            if silent_for <= lease_timeout {
+                continue;
+            }
+
+            // Silent active node may still serve clients. Promotion is safe only if it is fenced
+            if !app.settings.replication_auto_promote {
+                if !lease_expiration_is_reported {
+                    my_logger::LOGGER.write_error(
+                        "Replication",
+                        format!(
+                            "Active node is silent for {:?}. Lease is expired. Fence active node and promote this node manually",
+                            silent_for
+                        ),
+                        LogEventCtx::new(),
+                    );
+                    lease_expiration_is_reported = true;
+                }
+
+                continue;
+            }
+
+            if app.replication.promote() {
+                my_logger::LOGGER.write_info(
+                    "Replication",
+                    format!(
+                        "Active node is silent for {:?}. Lease is expired. Promoting to active",
+                        silent_for
+                    ),
+                    LogEventCtx::new(),
+                );
+            }
+        }
+    }
+
+    complete_promotion(app).await;
+}
+
+async fn read_loop(app: Arc<AppContext>, sender: UnboundedSender<ReplicationFrameContract>) {
+    let active_node = app.settings.replication_active_node.as_ref().unwrap();
+    let secret = app.settings.replication_secret.as_ref().unwrap();
+
+    while app.replication.is_passive() {
+        let mut stream = match TcpStream::connect(active_node.as_str()).await {
+            Ok(stream) => stream,
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "Replication",
+                    format!("Can not connect to active node. Err: {:?}", err),
+                    LogEventCtx::new().add("activeNode", active_node.to_string()),
+                );
+
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        let handshake = match tokio::time::timeout(
+            HANDSHAKE_TIMEOUT,
+            super::handshake::authenticate_active_node(&mut stream, secret.as_str()),
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(err) => Err(err.into()),
+        };
+
+        if let Err(err) = handshake {
+            my_logger::LOGGER.write_error(
+                "Replication",
+                format!("Can not authenticate active node. Err: {:?}", err),
+                LogEventCtx::new().add("activeNode", active_node.to_string()),
+            );
+
+            tokio::time::sleep(RECONNECT_DELAY).await;
+            continue;
+        }
+
+        my_logger::LOGGER.write_info(
+            "Replication",
+            "Connected to active node".to_string(),
+            LogEventCtx::new().add("activeNode", active_node.to_string()),
+        );
+
+        loop {
+            match read_frame(&mut stream).await {
+                Ok(frame) => {
+                    if sender.send(frame).is_err() {
+                        // Node is promoted
+                        return;
+                    }
+                }
+                Err(err) => {
+                    my_logger::LOGGER.write_error(
+                        "Replication",
+                        format!("Connection to active node is lost. Err: {:?}", err),
+                        LogEventCtx::new().add("activeNode", active_node.to_string()),
+                    );
+                    break;
+                }
+            }
+        }
+
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn apply_frame(app: &AppContext, frame: ReplicationFrameContract) {
+    if let Some(snapshot) = frame.snapshot {
+        let snapshot: Vec<TopicSnapshot> = snapshot
+            .queue_snapshot
+            .into_iter()
+            .map(|itm| itm.into())
+            .collect();
+
+        for topic in app.topic_list.get_all().await {
+            if !snapshot
+                .iter()
+                .any(|itm| itm.topic_id.as_str() == topic.topic_id.as_str())
+            {
+                app.topic_list.delete_topic(topic.topic_id.as_str()).await;
+            }
+        }
+
+        for topic_snapshot in snapshot {
+            apply_topic_snapshot(app, topic_snapshot).await;
+        }
+    }
+
+    for messages in frame.messages {
+        apply_messages(app, messages).await;
+    }
+}
+
+async fn apply_topic_snapshot(app: &AppContext, topic_snapshot: TopicSnapshot) {
+    let topic = match app.topic_list.get(topic_snapshot.topic_id.as_str()).await {
+        Some(topic) => topic,
+        None => {
+            app.topic_list
+                .restore(
+                    topic_snapshot.topic_id.as_str(),
+                    MessageId::new(topic_snapshot.message_id),
+                    topic_snapshot.persist,
+                )
+                .await
+        }
+    };
+
+    let mut topic_data = topic.get_access().await;
+
+    if topic_data.message_id.get_value() < topic_snapshot.message_id {
+        topic_data.message_id = MessageId::new(topic_snapshot.message_id);
+    }
+
+    topic_data.persist = topic_snapshot.persist;
+
+    let queues_to_remove: Vec<String> = topic_data
+        .queues
+        .get_all()
+        .filter(|queue| {
+            !topic_snapshot
+                .queues
+                .iter()
+                .any(|itm| itm.queue_id.as_str() == queue.queue_id.as_str())
+        })
+        .map(|queue| queue.queue_id.as_str().to_string())
+        .collect();
+
+    for queue_id in queues_to_remove {
+        topic_data.queues.remove(queue_id.as_str());
+    }
+
+    for queue in topic_snapshot.queues {
+        topic_data.queues.restore(
+            topic.topic_id.clone(),
+            queue.queue_id.into(),
+            queue.queue_type,
+            QueueWithIntervals::restore(queue.ranges),
+        );
+    }
+}
+
+async fn apply_messages(app: &AppContext, messages: ReplicationMessagesContract) {
+    let topic = match app.topic_list.get(messages.topic_id.as_str()).await {
+        Some(topic) => topic,
+        None => return,
+    };
+
+    let mut topic_data = topic.get_access().await;
+
+    let persisted_below = messages.persisted_below;
+
+    for message in messages.messages {
+        let message: MySbMessageContent = message.into();
+        let sub_page_id: SubPageId = message.id.into();
+        let persist = topic_data.persist && message.id.get_value() >= persisted_below;
+
+        if topic_data.message_id.get_value() <= message.id.get_value() {
+            topic_data.message_id = MessageId::new(message.id.get_value() + 1);
+        }
+
+        let page = topic_data.pages.get_or_create_mut(sub_page_id);
+
+        let is_new_message = matches!(
+            page.get_message(message.id),
+            GetMessageResult::GarbageCollected
+        );
+
+        if is_new_message {
+            // Messages which are not persisted by active node yet are persisted after promotion
+            page.add_message(message, persist);
+        }
+    }
+
+    mark_messages_as_persisted(&mut topic_data, persisted_below);
+
+    topic_data.gc_messages();
+    topic_data.gc_pages();
+}
+
+// Active node persisted messages which were replicated as not persisted before
+fn mark_messages_as_persisted(topic_data: &mut TopicInner, persisted_below: i64) {
+    let persisted = topic_data.get_messages_to_persist(|itm| itm.id.get_value());
+
+    for (sub_page_id, message_ids) in persisted {
+        let mut ids = QueueWithIntervals::new();
+
+        for message_id in message_ids {
+            if message_id < persisted_below {
+                ids.enqueue(message_id);
+            }
+        }
+
+        if ids.queue_size() > 0 {
+            topic_data.mark_messages_as_persisted(sub_page_id, &ids);
+        }
+    }
+}
+
+async fn complete_promotion(app: Arc<AppContext>) {
+    if app.replication.get_last_frame_received().is_none() {
+        my_logger::LOGGER.write_info(
+            "Replication",
+            "Node is promoted before any data is replicated. Restoring from persistence"
+                .to_string(),
+            LogEventCtx::new(),
+        );
+
+        crate::operations::initialization::init(app).await;
+        return;
+    }
+
+    // Persistence has to reflect queue positions of the new active node
+    let mut reusable_topics = ReusableTopicsList::new();
+    crate::operations::persist_topics_and_queues(&app, &mut reusable_topics).await;
+
+    app.states.set_initialized();
+
+    my_logger::LOGGER.write_info(
+        "Replication",
+        "Node is promoted to active. Clients are accepted".to_string(),
+        LogEventCtx::new(),
+    );
+
+    println!("Node is promoted to active");
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::{MessageId, SbMessageHeaders};
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::{app::AppContext, messages_page::MySbMessageContent, settings::SettingsModel};
+
+    use super::*;
+
+    fn create_contract(message_ids: &[i64], persisted_below: i64) -> ReplicationMessagesContract {
+        ReplicationMessagesContract {
+            topic_id: "test-topic".to_string(),
+            messages: message_ids
+                .iter()
+                .map(|message_id| {
+                    let message = MySbMessageContent::new(
+                        (*message_id).into(),
+                        vec![*message_id as u8],
+                        SbMessageHeaders::new(),
+                        DateTimeAsMicroseconds::now(),
+                    );
+                    (&message).into()
+                })
+                .collect(),
+            persisted_below,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_not_persisted_messages_are_kept_to_persist_after_promotion() {
+        let app = AppContext::new(SettingsModel::create_test_settings(16)).await;
+
+        let topic = app
+            .topic_list
+            .restore("test-topic", MessageId::new(0), true)
+            .await;
+
+        apply_messages(&app, create_contract(&[0, 1, 2, 3], 2)).await;
+
+        assert_eq!(
+            2,
+            topic.get_min_not_persisted_message_id().await.get_value()
+        );
+
+        // Active node persisted the rest of the messages
+        apply_messages(&app, create_contract(&[], 4)).await;
+
+        assert_eq!(
+            4,
+            topic.get_min_not_persisted_message_id().await.get_value()
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use rust_extensions::date_time::{AtomicDateTimeAsMicroseconds, DateTimeAsMicroseconds};
+
+use crate::settings::ReplicationRole;
+
+pub struct ReplicationState {
+    passive: AtomicBool,
+    frame_is_received: AtomicBool,
+    last_frame_received: AtomicDateTimeAsMicroseconds,
+    connected_passive_nodes: AtomicUsize,
+}
+
+impl ReplicationState {
+    pub fn new(role: Option<ReplicationRole>) -> Self {
+        Self {
+            passive: AtomicBool::new(role == Some(ReplicationRole::Passive)),
+            frame_is_received: AtomicBool::new(false),
+            last_frame_received: AtomicDateTimeAsMicroseconds::now(),
+            connected_passive_nodes: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn is_passive(&self) -> bool {
+        self.passive.load(Ordering::SeqCst)
+    }
+
+    // Returns true only for the call which actually promoted the node
+    pub fn promote(&self) -> bool {
+        self.passive
+            .compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst)
+            .is_ok()
+    }
+
+    pub fn update_last_frame_received(&self, now: DateTimeAsMicroseconds) {
+        self.last_frame_received.update(now);
+        self.frame_is_received.store(true, Ordering::SeqCst);
+    }
+
+    pub fn get_last_frame_received(&self) -> Option<DateTimeAsMicroseconds> {
+        if self.frame_is_received.load(Ordering::SeqCst) {
+            Some(self.last_frame_received.as_date_time())
+        } else {
+            None
+        }
+    }
+
+    pub fn passive_node_connected(&self) {
+        self.connected_passive_nodes.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn passive_node_disconnected(&self) {
+        self.connected_passive_nodes.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    pub fn get_connected_passive_nodes(&self) -> usize {
+        self.connected_passive_nodes.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promote_only_once() {
+        let state = ReplicationState::new(Some(ReplicationRole::Passive));
+
+        assert!(state.is_passive());
+        assert!(state.get_last_frame_received().is_none());
+
+        assert!(state.promote());
+        assert!(!state.promote());
+        assert!(!state.is_passive());
+
+        let state = ReplicationState::new(Some(ReplicationRole::Active));
+        assert!(!state.is_passive());
+        assert!(!state.promote());
+    }
+}
@@ -0,0 +1,174 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+use rust_extensions::{
+    date_time::DateTimeAsMicroseconds,
+    sorted_vec::{EntityWithKey, EntityWithStrKey},
+};
+use tokio::sync::mpsc::Sender;
+
+use crate::{
+    operations::delivery::SubscriberPackageBuilder,
+    service_bus_grpc::{
+        subscribe_stream_grpc_response::Payload, NewMessagesGrpcModel, SubscribeStreamGrpcResponse,
+    },
+    sessions::{my_sb_session::*, ConnectionMetrics, MyServiceBusSession, SessionId},
+};
+
+pub type GrpcDeliveryStreamSender = Sender<Result<SubscribeStreamGrpcResponse, tonic::Status>>;
+
+pub struct MyServiceBusGrpcSession {
+    pub session_id: SessionId,
+    pub session_key: String,
+    pub name: String,
+    pub version: String,
+    pub ip: String,
+    pub identity: Option<String>,
+    pub connected_moment: DateTimeAsMicroseconds,
+    connection_metrics: ConnectionMetrics,
+    connected: AtomicBool,
+    delivery_stream: Mutex<Option<GrpcDeliveryStreamSender>>,
+}
+
+impl MyServiceBusGrpcSession {
+    pub fn new(
+        session_id: SessionId,
+        session_key: String,
+        name: String,
+        version: String,
+        ip: String,
+        identity: Option<String>,
+    ) -> Self {
+        Self {
+            session_id,
+            session_key,
+            name,
+            version,
+            ip,
+            identity,
+            connected_moment: DateTimeAsMicroseconds::now(),
+            connection_metrics: ConnectionMetrics::new(),
+            connected: AtomicBool::new(true),
+            delivery_stream: Mutex::new(None),
+        }
+    }
+
+    pub fn ping(&self) {
+        self.connection_metrics.add_written(1);
+        self.connection_metrics.add_read(1);
+    }
+
+    pub fn update_written_amount(&self, amount: usize) {
+        self.connection_metrics.add_written(amount);
+    }
+
+    pub fn one_second_tick(&self) {
+        self.connection_metrics.one_second_tick();
+    }
+
+    pub fn get_last_incoming_moment(&self) -> DateTimeAsMicroseconds {
+        self.connection_metrics.last_incoming_moment.as_date_time()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    pub fn attach_delivery_stream(&self, sender: GrpcDeliveryStreamSender) -> bool {
+        let mut write_access = self.delivery_stream.lock().unwrap();
+
+        if write_access.is_some() {
+            return false;
+        }
+
+        *write_access = Some(sender);
+        true
+    }
+
+    pub fn has_delivery_stream(&self) -> bool {
+        self.delivery_stream.lock().unwrap().is_some()
+    }
+
+    fn get_delivery_stream(&self) -> Option<GrpcDeliveryStreamSender> {
+        self.delivery_stream.lock().unwrap().clone()
+    }
+
+    pub async fn send_to_delivery_stream(&self, payload: Payload) -> bool {
+        let sender = self.get_delivery_stream();
+
+        if let Some(sender) = sender {
+            let response = SubscribeStreamGrpcResponse {
+                payload: Some(payload),
+            };
+
+            return sender.send(Ok(response)).await.is_ok();
+        }
+
+        false
+    }
+}
+
+impl EntityWithStrKey for MyServiceBusGrpcSession {
+    fn get_key(&self) -> &str {
+        self.session_key.as_str()
+    }
+}
+
+impl EntityWithKey<i64> for MyServiceBusGrpcSession {
+    fn get_key(&self) -> &i64 {
+        self.session_id.as_ref()
+    }
+}
+
+#[async_trait::async_trait]
+impl MyServiceBusSession for MyServiceBusGrpcSession {
+    fn get_session_type(&self) -> SessionType {
+        SessionType::Grpc
+    }
+
+    fn get_session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    fn get_name_and_version(&self) -> SessionNameAndVersion {
+        SessionNameAndVersion {
+            name: self.name.to_string(),
+            version: Some(self.version.to_string()),
+        }
+    }
+
+    fn get_metrics(&self) -> SessionMetrics {
+        SessionMetrics {
+            ip: self.ip.to_string(),
+            connected: self.connected_moment,
+            connection_metrics: self.connection_metrics.get_snapshot(),
+            tcp_protocol_version: None,
+            client_certificate_subject: None,
+        }
+    }
+
+    fn get_identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+
+    async fn disconnect(&self) -> bool {
+        let result = self.connected.swap(false, Ordering::SeqCst);
+        self.delivery_stream.lock().unwrap().take();
+        result
+    }
+
+    async fn send_messages_to_connection(&self, mut package_builder: SubscriberPackageBuilder) {
+        let messages = package_builder.get_grpc_result();
+
+        let payload = Payload::NewMessages(NewMessagesGrpcModel {
+            topic_id: package_builder.topic.topic_id.to_string(),
+            queue_id: package_builder.queue_id.to_string(),
+            confirmation_id: package_builder.subscriber_id.get_value(),
+            messages,
+        });
+
+        self.send_to_delivery_stream(payload).await;
+    }
+}
@@ -0,0 +1,4 @@
+mod grpc_session;
+pub use grpc_session::*;
+mod grpc_sessions_list;
+pub use grpc_sessions_list::*;
@@ -25,6 +25,7 @@ pub struct MyServiceBusHttpSession {
     pub name: String,
     pub version: String,
     pub ip: String,
+    pub identity: Option<String>,
     pub connected_moment: DateTimeAsMicroseconds,
     connection_metrics: ConnectionMetrics,
     connected: AtomicBool,
@@ -38,6 +39,7 @@ impl MyServiceBusHttpSession {
         name: String,
         version: String,
         ip: String,
+        identity: Option<String>,
     ) -> Self {
         Self {
             session_id,
@@ -45,6 +47,7 @@ impl MyServiceBusHttpSession {
             name,
             version,
             ip,
+            identity,
             connected: AtomicBool::new(true),
             connection_metrics: ConnectionMetrics::new(),
             connected_moment: DateTimeAsMicroseconds::now(),
@@ -128,9 +131,14 @@ impl MyServiceBusSession for MyServiceBusHttpSession {
             connected: self.connected_moment,
             connection_metrics: self.connection_metrics.get_snapshot(),
             tcp_protocol_version: None,
+            client_certificate_subject: None,
         }
     }
 
+    fn get_identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+
     async fn disconnect(&self) -> bool {
         self.connected.swap(false, Ordering::SeqCst)
     }
@@ -52,6 +52,10 @@ impl HttpSessionsList {
         self.by_session_key.get(session_key).cloned()
     }
 
+    pub fn get_by_session_id(&self, session_id: SessionId) -> Option<Arc<MyServiceBusHttpSession>> {
+        self.by_session_id.get(session_id.as_ref()).cloned()
+    }
+
     pub fn remove_by_session_id(
         &mut self,
         session_id: SessionId,
@@ -14,7 +14,10 @@ pub use connection_metrics::*;
 mod session_id;
 pub use session_id::*;
 
+pub mod grpc;
 pub mod http;
+pub mod mqtt;
 pub mod tcp;
 #[cfg(test)]
 pub mod test;
+pub mod ws;
@@ -0,0 +1,6 @@
+mod mqtt_in_flight;
+pub use mqtt_in_flight::*;
+mod mqtt_session;
+pub use mqtt_session::*;
+mod mqtt_sessions_list;
+pub use mqtt_sessions_list::*;
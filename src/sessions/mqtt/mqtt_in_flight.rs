@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use crate::queue_subscribers::SubscriberId;
+
+struct MqttInFlightBucket {
+    topic_id: String,
+    queue_id: String,
+    remaining: usize,
+}
+
+pub struct MqttConfirmedBucket {
+    pub topic_id: String,
+    pub queue_id: String,
+    pub subscriber_id: SubscriberId,
+}
+
+pub struct MqttInFlight {
+    by_packet_id: HashMap<u16, i64>,
+    buckets: HashMap<i64, MqttInFlightBucket>,
+    next_packet_id: u16,
+}
+
+impl MqttInFlight {
+    pub fn new() -> Self {
+        Self {
+            by_packet_id: HashMap::new(),
+            buckets: HashMap::new(),
+            next_packet_id: 0,
+        }
+    }
+
+    fn get_next_packet_id(&mut self) -> u16 {
+        loop {
+            self.next_packet_id = self.next_packet_id.wrapping_add(1);
+
+            if self.next_packet_id == 0 {
+                continue;
+            }
+
+            if !self.by_packet_id.contains_key(&self.next_packet_id) {
+                return self.next_packet_id;
+            }
+        }
+    }
+
+    pub fn start_bucket(
+        &mut self,
+        topic_id: &str,
+        queue_id: &str,
+        subscriber_id: SubscriberId,
+        messages_amount: usize,
+    ) -> Vec<u16> {
+        let mut result = Vec::with_capacity(messages_amount);
+
+        for _ in 0..messages_amount {
+            let packet_id = self.get_next_packet_id();
+            self.by_packet_id
+                .insert(packet_id, subscriber_id.get_value());
+            result.push(packet_id);
+        }
+
+        self.buckets.insert(
+            subscriber_id.get_value(),
+            MqttInFlightBucket {
+                topic_id: topic_id.to_string(),
+                queue_id: queue_id.to_string(),
+                remaining: messages_amount,
+            },
+        );
+
+        result
+    }
+
+    pub fn confirm(&mut self, packet_id: u16) -> Option<MqttConfirmedBucket> {
+        let subscriber_id = self.by_packet_id.remove(&packet_id)?;
+
+        let bucket = self.buckets.get_mut(&subscriber_id)?;
+
+        bucket.remaining -= 1;
+
+        if bucket.remaining > 0 {
+            return None;
+        }
+
+        let bucket = self.buckets.remove(&subscriber_id)?;
+
+        Some(MqttConfirmedBucket {
+            topic_id: bucket.topic_id,
+            queue_id: bucket.queue_id,
+            subscriber_id: subscriber_id.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_is_confirmed_after_all_pubacks() {
+        let mut in_flight = MqttInFlight::new();
+
+        let packet_ids =
+            in_flight.start_bucket("test-topic", "test-queue", SubscriberId::new(5), 2);
+
+        assert_eq!(vec![1u16, 2], packet_ids);
+
+        assert!(in_flight.confirm(packet_ids[0]).is_none());
+
+        let confirmed = in_flight.confirm(packet_ids[1]).unwrap();
+
+        assert_eq!("test-topic", confirmed.topic_id);
+        assert_eq!("test-queue", confirmed.queue_id);
+        assert_eq!(5, confirmed.subscriber_id.get_value());
+
+        assert!(in_flight.confirm(packet_ids[1]).is_none());
+    }
+}
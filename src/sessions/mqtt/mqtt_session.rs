@@ -0,0 +1,198 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, sorted_vec::EntityWithKey};
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    mqtt::packets,
+    operations::delivery::SubscriberPackageBuilder,
+    queue_subscribers::SubscriberId,
+    sessions::{my_sb_session::*, ConnectionMetrics, MyServiceBusSession, SessionId},
+};
+
+use super::{MqttConfirmedBucket, MqttInFlight};
+
+pub enum MqttOutgoing {
+    Packet(Vec<u8>),
+    ConfirmDelivered {
+        topic_id: String,
+        queue_id: String,
+        subscriber_id: SubscriberId,
+        session_id: SessionId,
+    },
+    Disconnect,
+}
+
+pub struct MyServiceBusMqttSession {
+    pub session_id: SessionId,
+    pub client_id: String,
+    pub ip: String,
+    pub identity: Option<String>,
+    pub connected_moment: DateTimeAsMicroseconds,
+    connection_metrics: ConnectionMetrics,
+    connected: AtomicBool,
+    sender: UnboundedSender<MqttOutgoing>,
+    subscriptions: Mutex<HashMap<String, u8>>,
+    in_flight: Mutex<MqttInFlight>,
+}
+
+impl MyServiceBusMqttSession {
+    pub fn new(
+        session_id: SessionId,
+        client_id: String,
+        ip: String,
+        sender: UnboundedSender<MqttOutgoing>,
+        identity: Option<String>,
+    ) -> Self {
+        Self {
+            session_id,
+            client_id,
+            ip,
+            identity,
+            connected_moment: DateTimeAsMicroseconds::now(),
+            connection_metrics: ConnectionMetrics::new(),
+            connected: AtomicBool::new(true),
+            sender,
+            subscriptions: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(MqttInFlight::new()),
+        }
+    }
+
+    pub fn update_read_amount(&self, amount: usize) {
+        self.connection_metrics.add_read(amount);
+    }
+
+    pub fn one_second_tick(&self) {
+        self.connection_metrics.one_second_tick();
+    }
+
+    pub fn send_packet(&self, packet: Vec<u8>) {
+        if !self.connected.load(Ordering::Relaxed) {
+            return;
+        }
+
+        self.connection_metrics.add_written(packet.len());
+        let _ = self.sender.send(MqttOutgoing::Packet(packet));
+    }
+
+    pub fn set_subscription(&self, topic_id: &str, qos: u8) {
+        let mut write_access = self.subscriptions.lock().unwrap();
+        write_access.insert(topic_id.to_string(), qos);
+    }
+
+    pub fn remove_subscription(&self, topic_id: &str) {
+        let mut write_access = self.subscriptions.lock().unwrap();
+        write_access.remove(topic_id);
+    }
+
+    fn get_subscription_qos(&self, topic_id: &str) -> u8 {
+        let read_access = self.subscriptions.lock().unwrap();
+        read_access.get(topic_id).copied().unwrap_or(0)
+    }
+
+    pub fn on_puback(&self, packet_id: u16) -> Option<MqttConfirmedBucket> {
+        let mut write_access = self.in_flight.lock().unwrap();
+        write_access.confirm(packet_id)
+    }
+}
+
+impl EntityWithKey<i64> for MyServiceBusMqttSession {
+    fn get_key(&self) -> &i64 {
+        self.session_id.as_ref()
+    }
+}
+
+#[async_trait::async_trait]
+impl MyServiceBusSession for MyServiceBusMqttSession {
+    fn get_session_type(&self) -> SessionType {
+        SessionType::Mqtt
+    }
+
+    fn get_session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    fn get_name_and_version(&self) -> SessionNameAndVersion {
+        SessionNameAndVersion {
+            name: self.client_id.to_string(),
+            version: None,
+        }
+    }
+
+    fn get_metrics(&self) -> SessionMetrics {
+        SessionMetrics {
+            ip: self.ip.to_string(),
+            connected: self.connected_moment,
+            connection_metrics: self.connection_metrics.get_snapshot(),
+            tcp_protocol_version: None,
+            client_certificate_subject: None,
+        }
+    }
+
+    fn get_identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+
+    async fn disconnect(&self) -> bool {
+        let result = self.connected.swap(false, Ordering::SeqCst);
+
+        if result {
+            let _ = self.sender.send(MqttOutgoing::Disconnect);
+        }
+
+        result
+    }
+
+    async fn send_messages_to_connection(&self, mut package_builder: SubscriberPackageBuilder) {
+        let messages = package_builder.get_mqtt_result();
+        let topic_id = package_builder.topic.topic_id.as_str();
+        let queue_id = package_builder.queue_id.as_str();
+
+        let qos = self.get_subscription_qos(topic_id);
+
+        if qos == 0 {
+            for msg in &messages {
+                self.send_packet(packets::compile_publish(
+                    topic_id,
+                    0,
+                    None,
+                    msg.content.as_slice(),
+                ));
+            }
+
+            let _ = self.sender.send(MqttOutgoing::ConfirmDelivered {
+                topic_id: topic_id.to_string(),
+                queue_id: queue_id.to_string(),
+                subscriber_id: package_builder.subscriber_id,
+                session_id: self.session_id,
+            });
+
+            return;
+        }
+
+        let packet_ids = {
+            let mut write_access = self.in_flight.lock().unwrap();
+            write_access.start_bucket(
+                topic_id,
+                queue_id,
+                package_builder.subscriber_id,
+                messages.len(),
+            )
+        };
+
+        for (msg, packet_id) in messages.iter().zip(packet_ids) {
+            self.send_packet(packets::compile_publish(
+                topic_id,
+                qos,
+                Some(packet_id),
+                msg.content.as_slice(),
+            ));
+        }
+    }
+}
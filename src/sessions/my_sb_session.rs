@@ -8,6 +8,9 @@ use super::{ConnectionMetricsSnapshot, SessionId};
 pub enum SessionType {
     Tcp(PacketProtVer),
     Http,
+    Ws,
+    Grpc,
+    Mqtt,
     #[cfg(test)]
     Test,
 }
@@ -17,6 +20,9 @@ impl SessionType {
         match self {
             SessionType::Tcp(_) => "tcp",
             SessionType::Http => "http",
+            SessionType::Ws => "ws",
+            SessionType::Grpc => "grpc",
+            SessionType::Mqtt => "mqtt",
             #[cfg(test)]
             SessionType::Test => "test",
         }
@@ -28,6 +34,7 @@ pub struct SessionMetrics {
     pub connection_metrics: ConnectionMetricsSnapshot,
     pub connected: DateTimeAsMicroseconds,
     pub tcp_protocol_version: Option<i32>,
+    pub client_certificate_subject: Option<String>,
 }
 
 pub struct SessionNameAndVersion {
@@ -43,6 +50,11 @@ pub trait MyServiceBusSession {
 
     fn get_metrics(&self) -> SessionMetrics;
 
+    // Identity of api key if session is authenticated
+    fn get_identity(&self) -> Option<&str> {
+        None
+    }
+
     async fn disconnect(&self) -> bool;
 
     async fn send_messages_to_connection(&self, package_builder: SubscriberPackageBuilder);
@@ -2,13 +2,16 @@ use std::{sync::Arc, time::Duration};
 
 use my_service_bus::tcp_contracts::MySbTcpConnection;
 use my_tcp_sockets::ConnectionId;
-use tokio::sync::RwLock;
+use tokio::sync::{mpsc::UnboundedSender, RwLock};
+use tokio_tungstenite::tungstenite::Message;
 
 #[cfg(test)]
 use super::test::*;
 use super::{
-    http::*, sessions_list_inner::SessionsListInner, tcp::*, MyServiceBusSession, SessionId,
+    grpc::*, http::*, mqtt::*, sessions_list_inner::SessionsListInner, tcp::*, ws::*,
+    MyServiceBusSession, SessionId,
 };
+use crate::{tcp::tls::TlsPeer, ws::WsFrameType};
 
 pub struct SessionsList {
     data: RwLock<SessionsListInner>,
@@ -27,26 +30,103 @@ impl SessionsList {
         name: String,
         version: Option<String>,
         protocol_version: i32,
+        tls_peer: Option<TlsPeer>,
+        identity: Option<String>,
     ) {
         let mut write_access = self.data.write().await;
 
         let session_id = write_access.get_next_session_id();
 
-        let session =
-            MyServiceBusTcpSession::new(session_id, connection, name, version, protocol_version);
+        let session = MyServiceBusTcpSession::new(
+            session_id,
+            connection,
+            name,
+            version,
+            protocol_version,
+            tls_peer,
+            identity,
+        );
         write_access.add_tcp(Arc::new(session));
     }
 
-    pub async fn add_http(&self, name: String, version: String, ip: String) -> HttpSessionKey {
+    pub async fn add_http(
+        &self,
+        name: String,
+        version: String,
+        ip: String,
+        identity: Option<String>,
+    ) -> HttpSessionKey {
         let session_key = HttpSessionKey::new();
         let mut write_access = self.data.write().await;
         let session_id = write_access.get_next_session_id();
-        let session =
-            MyServiceBusHttpSession::new(session_id, session_key.clone(), name, version, ip);
+        let session = MyServiceBusHttpSession::new(
+            session_id,
+            session_key.clone(),
+            name,
+            version,
+            ip,
+            identity,
+        );
         write_access.add_http(Arc::new(session));
         session_key
     }
 
+    pub async fn add_grpc(
+        &self,
+        name: String,
+        version: String,
+        ip: String,
+        identity: Option<String>,
+    ) -> Arc<MyServiceBusGrpcSession> {
+        let session_key = uuid::Uuid::new_v4().to_string();
+        let mut write_access = self.data.write().await;
+        let session_id = write_access.get_next_session_id();
+        let session = Arc::new(MyServiceBusGrpcSession::new(
+            session_id,
+            session_key,
+            name,
+            version,
+            ip,
+            identity,
+        ));
+        write_access.add_grpc(session.clone());
+        session
+    }
+
+    pub async fn add_ws(
+        &self,
+        name: String,
+        version: Option<String>,
+        ip: String,
+        frame_type: WsFrameType,
+        sender: UnboundedSender<Message>,
+        identity: Option<String>,
+    ) -> Arc<MyServiceBusWsSession> {
+        let mut write_access = self.data.write().await;
+        let session_id = write_access.get_next_session_id();
+        let session = Arc::new(MyServiceBusWsSession::new(
+            session_id, name, version, ip, frame_type, sender, identity,
+        ));
+        write_access.add_ws(session.clone());
+        session
+    }
+
+    pub async fn add_mqtt(
+        &self,
+        client_id: String,
+        ip: String,
+        sender: UnboundedSender<MqttOutgoing>,
+        identity: Option<String>,
+    ) -> Arc<MyServiceBusMqttSession> {
+        let mut write_access = self.data.write().await;
+        let session_id = write_access.get_next_session_id();
+        let session = Arc::new(MyServiceBusMqttSession::new(
+            session_id, client_id, ip, sender, identity,
+        ));
+        write_access.add_mqtt(session.clone());
+        session
+    }
+
     #[cfg(test)]
     pub async fn add_test(
         &self,
@@ -68,6 +148,16 @@ impl SessionsList {
         read_access.get_http_by_session_key(http_session_key)
     }
 
+    pub async fn get_grpc(&self, session_key: &str) -> Option<Arc<MyServiceBusGrpcSession>> {
+        let read_access = self.data.read().await;
+        read_access.get_grpc_by_session_key(session_key)
+    }
+
+    pub async fn get_grpc_sessions_amount(&self) -> usize {
+        let read_access = self.data.read().await;
+        read_access.get_grpc_sessions().len()
+    }
+
     pub async fn get_tcp_session_by_connection_id(
         &self,
         connection_id: ConnectionId,
@@ -84,6 +174,11 @@ impl SessionsList {
         read_access.get_session_id_by_tcp_connection_id(connection_id)
     }
 
+    pub async fn get_identity(&self, session_id: SessionId) -> Option<String> {
+        let read_access = self.data.read().await;
+        read_access.get_identity(session_id)
+    }
+
     pub async fn remove_tcp(&self, id: ConnectionId) -> Option<Arc<MyServiceBusTcpSession>> {
         let mut write_access = self.data.write().await;
         write_access.remove_tcp(id)
@@ -100,11 +195,28 @@ impl SessionsList {
     }
 
     pub async fn one_second_tick(&self) {
-        let http_sessions = {
+        let (http_sessions, ws_sessions, grpc_sessions, mqtt_sessions) = {
             let read_access = self.data.read().await;
-            read_access.get_http_sessions()
+            (
+                read_access.get_http_sessions(),
+                read_access.get_ws_sessions(),
+                read_access.get_grpc_sessions(),
+                read_access.get_mqtt_sessions(),
+            )
         };
 
+        for ws_session in ws_sessions {
+            ws_session.one_second_tick();
+        }
+
+        for grpc_session in grpc_sessions {
+            grpc_session.one_second_tick();
+        }
+
+        for mqtt_session in mqtt_sessions {
+            mqtt_session.one_second_tick();
+        }
+
         for http_session in http_sessions {
             http_session.one_second_tick().await;
         }
@@ -118,6 +230,14 @@ impl SessionsList {
         write_access.remove_and_disconnect_expired_http_sessions(inactive_timeout)
     }
 
+    pub async fn remove_and_disconnect_expired_grpc_sessions(
+        &self,
+        inactive_timeout: Duration,
+    ) -> Vec<Arc<MyServiceBusGrpcSession>> {
+        let mut write_access = self.data.write().await;
+        write_access.remove_and_disconnect_expired_grpc_sessions(inactive_timeout)
+    }
+
     pub async fn remove_by_session_id(
         &self,
         session_id: SessionId,
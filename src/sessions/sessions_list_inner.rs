@@ -2,7 +2,7 @@ use std::{sync::Arc, time::Duration};
 
 use my_tcp_sockets::ConnectionId;
 
-use super::{http::*, tcp::*, MyServiceBusSession, SessionId};
+use super::{grpc::*, http::*, mqtt::*, tcp::*, ws::*, MyServiceBusSession, SessionId};
 
 #[cfg(test)]
 use super::test::*;
@@ -11,6 +11,9 @@ pub struct SessionsListInner {
     snapshot_id: usize,
     tcp_sessions: TcpSessionsList,
     http_sessions: HttpSessionsList,
+    ws_sessions: WsSessionsList,
+    grpc_sessions: GrpcSessionsList,
+    mqtt_sessions: MqttSessionsList,
 
     #[cfg(test)]
     test_sessions: TestSessionsList,
@@ -26,12 +29,27 @@ impl SessionsListInner {
             #[cfg(test)]
             test_sessions: TestSessionsList::new(),
             http_sessions: HttpSessionsList::new(),
+            ws_sessions: WsSessionsList::new(),
+            grpc_sessions: GrpcSessionsList::new(),
+            mqtt_sessions: MqttSessionsList::new(),
         }
     }
 
     pub fn get_http_sessions(&self) -> Vec<Arc<MyServiceBusHttpSession>> {
         self.http_sessions.get_all()
     }
+    pub fn get_ws_sessions(&self) -> Vec<Arc<MyServiceBusWsSession>> {
+        self.ws_sessions.get_all()
+    }
+
+    pub fn get_grpc_sessions(&self) -> Vec<Arc<MyServiceBusGrpcSession>> {
+        self.grpc_sessions.get_all()
+    }
+
+    pub fn get_mqtt_sessions(&self) -> Vec<Arc<MyServiceBusMqttSession>> {
+        self.mqtt_sessions.get_all()
+    }
+
     pub fn get_next_session_id(&mut self) -> SessionId {
         let result = self.current_session_id;
         self.current_session_id += 1;
@@ -49,6 +67,24 @@ impl SessionsListInner {
 
         self.http_sessions.add(session)
     }
+    pub fn add_ws(&mut self, session: Arc<MyServiceBusWsSession>) {
+        self.snapshot_id += 1;
+
+        self.ws_sessions.add(session)
+    }
+
+    pub fn add_grpc(&mut self, session: Arc<MyServiceBusGrpcSession>) {
+        self.snapshot_id += 1;
+
+        self.grpc_sessions.add(session)
+    }
+
+    pub fn add_mqtt(&mut self, session: Arc<MyServiceBusMqttSession>) {
+        self.snapshot_id += 1;
+
+        self.mqtt_sessions.add(session)
+    }
+
     #[cfg(test)]
     pub fn add_test(&mut self, session: Arc<MyServiceBusTestSession>) {
         self.snapshot_id += 1;
@@ -70,6 +106,30 @@ impl SessionsListInner {
         self.tcp_sessions.get_session_id(connection_id)
     }
 
+    pub fn get_identity(&self, session_id: SessionId) -> Option<String> {
+        if let Some(session) = self.tcp_sessions.get_by_session_id(session_id) {
+            return session.identity.clone();
+        }
+
+        if let Some(session) = self.http_sessions.get_by_session_id(session_id) {
+            return session.identity.clone();
+        }
+
+        if let Some(session) = self.ws_sessions.get_by_session_id(session_id) {
+            return session.identity.clone();
+        }
+
+        if let Some(session) = self.grpc_sessions.get_by_session_id(session_id) {
+            return session.identity.clone();
+        }
+
+        if let Some(session) = self.mqtt_sessions.get_by_session_id(session_id) {
+            return session.identity.clone();
+        }
+
+        None
+    }
+
     pub fn get_http_by_session_key(
         &self,
         session_key: &str,
@@ -77,6 +137,13 @@ impl SessionsListInner {
         self.http_sessions.get_by_session_key(session_key)
     }
 
+    pub fn get_grpc_by_session_key(
+        &self,
+        session_key: &str,
+    ) -> Option<Arc<MyServiceBusGrpcSession>> {
+        self.grpc_sessions.get_by_session_key(session_key)
+    }
+
     pub fn remove_tcp(
         &mut self,
         connection_id: ConnectionId,
@@ -100,6 +167,18 @@ impl SessionsListInner {
             return Some(result);
         }
 
+        if let Some(result) = self.ws_sessions.remove_by_session_id(session_id) {
+            return Some(result);
+        }
+
+        if let Some(result) = self.grpc_sessions.remove_by_session_id(session_id) {
+            return Some(result);
+        }
+
+        if let Some(result) = self.mqtt_sessions.remove_by_session_id(session_id) {
+            return Some(result);
+        }
+
         #[cfg(test)]
         if let Some(result) = self.test_sessions.remove_by_session_id(session_id) {
             return Some(result);
@@ -114,13 +193,24 @@ impl SessionsListInner {
         usize,
         Vec<Arc<dyn MyServiceBusSession + Send + Sync + 'static>>,
     ) {
-        let mut sessions_result =
-            Vec::with_capacity(self.tcp_sessions.len() + self.http_sessions.len());
+        let mut sessions_result = Vec::with_capacity(
+            self.tcp_sessions.len()
+                + self.http_sessions.len()
+                + self.ws_sessions.len()
+                + self.grpc_sessions.len()
+                + self.mqtt_sessions.len(),
+        );
 
         self.tcp_sessions.fill_sessions(&mut sessions_result);
 
         self.http_sessions.fill_sessions(&mut sessions_result);
 
+        self.ws_sessions.fill_sessions(&mut sessions_result);
+
+        self.grpc_sessions.fill_sessions(&mut sessions_result);
+
+        self.mqtt_sessions.fill_sessions(&mut sessions_result);
+
         #[cfg(test)]
         self.test_sessions.fill_sessions(&mut sessions_result);
 
@@ -139,4 +229,18 @@ impl SessionsListInner {
 
         sessions_to_gc
     }
+
+    pub fn remove_and_disconnect_expired_grpc_sessions(
+        &mut self,
+        inactive_timeout: Duration,
+    ) -> Vec<Arc<MyServiceBusGrpcSession>> {
+        let sessions_to_gc = self.grpc_sessions.get_sessions_to_gc(inactive_timeout);
+
+        for session_to_gc in &sessions_to_gc {
+            self.grpc_sessions
+                .remove_by_session_id(session_to_gc.session_id);
+        }
+
+        sessions_to_gc
+    }
 }
@@ -1,14 +1,19 @@
-use std::sync::{
-    atomic::{AtomicI32, AtomicU8, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicI32, AtomicU8, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
 };
 
-use my_service_bus::tcp_contracts::{MySbTcpConnection, PacketProtVer};
+use my_service_bus::tcp_contracts::{MySbTcpConnection, MySbTcpContract, PacketProtVer};
 use rust_extensions::sorted_vec::*;
+use tokio::{sync::mpsc::UnboundedSender, time::Instant};
 
 use crate::{
     operations::delivery::SubscriberPackageBuilder,
     sessions::{my_sb_session::*, ConnectionMetricsSnapshot, MyServiceBusSession, SessionId},
+    tcp::tls::TlsPeer,
 };
 
 pub struct MyServiceBusTcpSession {
@@ -19,6 +24,10 @@ pub struct MyServiceBusTcpSession {
     pub name: String,
     pub version: Option<String>,
     pub logged_send_error_on_disconnected: AtomicI32,
+    pub tls_peer: Option<TlsPeer>,
+    pub identity: Option<String>,
+    // Responses go through the queue since the first of them is delayed, so they keep the order
+    delayed_responses: Mutex<Option<UnboundedSender<(Instant, MySbTcpContract)>>>,
 }
 
 impl MyServiceBusTcpSession {
@@ -28,6 +37,8 @@ impl MyServiceBusTcpSession {
         name: String,
         version: Option<String>,
         protocol_version: i32,
+        tls_peer: Option<TlsPeer>,
+        identity: Option<String>,
     ) -> Self {
         Self {
             session_id,
@@ -37,6 +48,32 @@ impl MyServiceBusTcpSession {
             logged_send_error_on_disconnected: AtomicI32::new(0),
             name,
             version,
+            tls_peer,
+            identity,
+            delayed_responses: Mutex::new(None),
+        }
+    }
+
+    // Delayed response does not let publisher send next batch, while other packets of the connection are still handled
+    pub async fn send_response(&self, contract: MySbTcpContract, delay: Option<Duration>) {
+        let sender = {
+            let mut delayed_responses = self.delayed_responses.lock().unwrap();
+
+            if delayed_responses.is_none() && delay.is_some() {
+                let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+                tokio::spawn(send_delayed_responses(self.connection.clone(), receiver));
+                *delayed_responses = Some(sender);
+            }
+
+            delayed_responses.clone()
+        };
+
+        match sender {
+            Some(sender) => {
+                let send_at = Instant::now() + delay.unwrap_or_default();
+                let _ = sender.send((send_at, contract));
+            }
+            None => self.connection.send(&contract).await,
         }
     }
 
@@ -62,6 +99,16 @@ impl MyServiceBusTcpSession {
     }
 }
 
+async fn send_delayed_responses(
+    connection: Arc<MySbTcpConnection>,
+    mut receiver: tokio::sync::mpsc::UnboundedReceiver<(Instant, MySbTcpContract)>,
+) {
+    while let Some((send_at, contract)) = receiver.recv().await {
+        tokio::time::sleep_until(send_at).await;
+        connection.send(&contract).await;
+    }
+}
+
 #[async_trait::async_trait]
 impl MyServiceBusSession for MyServiceBusTcpSession {
     fn get_session_type(&self) -> SessionType {
@@ -82,7 +129,9 @@ impl MyServiceBusSession for MyServiceBusTcpSession {
     fn get_metrics(&self) -> SessionMetrics {
         let statistics = self.connection.statistics();
         SessionMetrics {
-            ip: if let Some(addr) = &self.connection.addr {
+            ip: if let Some(tls_peer) = &self.tls_peer {
+                tls_peer.client_addr.to_string()
+            } else if let Some(addr) = &self.connection.addr {
                 addr.to_string()
             } else {
                 "???".to_string()
@@ -96,9 +145,17 @@ impl MyServiceBusSession for MyServiceBusTcpSession {
                 last_incoming_moment: statistics.last_receive_moment.as_date_time(),
             },
             tcp_protocol_version: Some(self.protocol_version),
+            client_certificate_subject: self
+                .tls_peer
+                .as_ref()
+                .and_then(|itm| itm.client_certificate_subject.clone()),
         }
     }
 
+    fn get_identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+
     async fn disconnect(&self) -> bool {
         self.connection.disconnect().await
     }
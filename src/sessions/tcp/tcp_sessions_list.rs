@@ -57,6 +57,10 @@ impl TcpSessionsList {
         self.by_connection_id.get(&connection_id).cloned()
     }
 
+    pub fn get_by_session_id(&self, session_id: SessionId) -> Option<Arc<MyServiceBusTcpSession>> {
+        self.by_session_id.get(session_id.as_ref()).cloned()
+    }
+
     pub fn get_session_id(&self, connection_id: ConnectionId) -> Option<SessionId> {
         let result = self.by_connection_id.get(&connection_id)?;
         Some(result.get_session_id())
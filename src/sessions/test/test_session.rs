@@ -71,6 +71,7 @@ impl MyServiceBusSession for MyServiceBusTestSession {
             connected: self.connected_moment,
             connection_metrics: ConnectionMetricsSnapshot::default(),
             tcp_protocol_version: None,
+            client_certificate_subject: None,
         }
     }
 
@@ -0,0 +1,4 @@
+mod ws_session;
+pub use ws_session::*;
+mod ws_sessions_list;
+pub use ws_sessions_list::*;
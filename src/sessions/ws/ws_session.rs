@@ -0,0 +1,128 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rust_extensions::{date_time::DateTimeAsMicroseconds, sorted_vec::EntityWithKey};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    operations::delivery::SubscriberPackageBuilder,
+    sessions::{my_sb_session::*, ConnectionMetrics, MyServiceBusSession, SessionId},
+    ws::{WsContract, WsFrameType},
+};
+
+pub struct MyServiceBusWsSession {
+    pub session_id: SessionId,
+    pub name: String,
+    pub version: Option<String>,
+    pub ip: String,
+    pub frame_type: WsFrameType,
+    pub identity: Option<String>,
+    pub connected_moment: DateTimeAsMicroseconds,
+    connection_metrics: ConnectionMetrics,
+    connected: AtomicBool,
+    sender: UnboundedSender<Message>,
+}
+
+impl MyServiceBusWsSession {
+    pub fn new(
+        session_id: SessionId,
+        name: String,
+        version: Option<String>,
+        ip: String,
+        frame_type: WsFrameType,
+        sender: UnboundedSender<Message>,
+        identity: Option<String>,
+    ) -> Self {
+        Self {
+            session_id,
+            name,
+            version,
+            ip,
+            frame_type,
+            identity,
+            connected_moment: DateTimeAsMicroseconds::now(),
+            connection_metrics: ConnectionMetrics::new(),
+            connected: AtomicBool::new(true),
+            sender,
+        }
+    }
+
+    pub fn update_read_amount(&self, amount: usize) {
+        self.connection_metrics.add_read(amount);
+    }
+
+    pub fn one_second_tick(&self) {
+        self.connection_metrics.one_second_tick();
+    }
+
+    pub fn send(&self, contract: &WsContract) {
+        if !self.connected.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let message = contract.serialize(self.frame_type);
+        self.connection_metrics.add_written(message.len());
+        let _ = self.sender.send(message);
+    }
+}
+
+impl EntityWithKey<i64> for MyServiceBusWsSession {
+    fn get_key(&self) -> &i64 {
+        self.session_id.as_ref()
+    }
+}
+
+#[async_trait::async_trait]
+impl MyServiceBusSession for MyServiceBusWsSession {
+    fn get_session_type(&self) -> SessionType {
+        SessionType::Ws
+    }
+
+    fn get_session_id(&self) -> SessionId {
+        self.session_id
+    }
+
+    fn get_name_and_version(&self) -> SessionNameAndVersion {
+        SessionNameAndVersion {
+            name: self.name.to_string(),
+            version: self.version.clone(),
+        }
+    }
+
+    fn get_metrics(&self) -> SessionMetrics {
+        SessionMetrics {
+            ip: self.ip.to_string(),
+            connected: self.connected_moment,
+            connection_metrics: self.connection_metrics.get_snapshot(),
+            tcp_protocol_version: None,
+            client_certificate_subject: None,
+        }
+    }
+
+    fn get_identity(&self) -> Option<&str> {
+        self.identity.as_deref()
+    }
+
+    async fn disconnect(&self) -> bool {
+        let result = self.connected.swap(false, Ordering::SeqCst);
+
+        if result {
+            let _ = self.sender.send(Message::Close(None));
+        }
+
+        result
+    }
+
+    async fn send_messages_to_connection(&self, mut package_builder: SubscriberPackageBuilder) {
+        let messages = package_builder.get_ws_result();
+
+        let contract = WsContract::NewMessages {
+            topic_id: package_builder.topic.topic_id.to_string(),
+            queue_id: package_builder.queue_id.to_string(),
+            confirmation_id: package_builder.subscriber_id.get_value(),
+            messages,
+        };
+
+        self.send(&contract);
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use rust_extensions::sorted_vec::SortedVecOfArc;
+
+use crate::sessions::{MyServiceBusSession, SessionId};
+
+use super::MyServiceBusWsSession;
+
+pub struct WsSessionsList {
+    by_session_id: SortedVecOfArc<i64, MyServiceBusWsSession>,
+}
+
+impl WsSessionsList {
+    pub fn new() -> Self {
+        WsSessionsList {
+            by_session_id: SortedVecOfArc::new(),
+        }
+    }
+
+    pub fn add(&mut self, session: Arc<MyServiceBusWsSession>) {
+        match self
+            .by_session_id
+            .insert_or_if_not_exists(session.session_id.as_ref())
+        {
+            rust_extensions::sorted_vec::InsertIfNotExists::Insert(entry) => entry.insert(session),
+            rust_extensions::sorted_vec::InsertIfNotExists::Exists(_) => {
+                panic!(
+                    "WebSocket Session already exists with session id: {}",
+                    session.session_id.get_value()
+                );
+            }
+        }
+    }
+
+    pub fn get_by_session_id(&self, session_id: SessionId) -> Option<Arc<MyServiceBusWsSession>> {
+        self.by_session_id.get(session_id.as_ref()).cloned()
+    }
+
+    pub fn remove_by_session_id(
+        &mut self,
+        session_id: SessionId,
+    ) -> Option<Arc<MyServiceBusWsSession>> {
+        self.by_session_id.remove(session_id.as_ref())
+    }
+
+    pub fn get_all(&self) -> Vec<Arc<MyServiceBusWsSession>> {
+        self.by_session_id.iter().map(|itm| itm.clone()).collect()
+    }
+
+    pub fn fill_sessions(
+        &self,
+        dest: &mut Vec<Arc<dyn MyServiceBusSession + Send + Sync + 'static>>,
+    ) {
+        for itm in self.by_session_id.iter() {
+            dest.push(itm.clone());
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_session_id.len()
+    }
+}
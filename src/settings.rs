@@ -1,14 +1,27 @@
-use std::time::Duration;
+use std::{net::SocketAddr, time::Duration};
 
 use rust_extensions::duration_utils::DurationExtensions;
 use serde::{Deserialize, Serialize};
 use tokio::{fs::File, io::AsyncReadExt};
 
-use crate::grpc_client::{MessagesPagesRepo, TopicsAndQueuesSnapshotRepo};
+use crate::{
+    grpc_client::{MessagesPagesRepo, TopicsAndQueuesSnapshotRepo},
+    settings_layers::SettingsSources,
+};
 
 #[cfg(test)]
 const TEST_GRPC_URL: &str = "test";
 
+const DEFAULT_TCP_PORT: u16 = 6421;
+const DEFAULT_HTTP_PORT: u16 = 6123;
+const DEFAULT_TCP_LISTENER_NAME: &str = "default";
+const DEFAULT_SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_PUBLISH_RATE_LIMIT_MAX_DELAY: Duration = Duration::from_secs(1);
+const DEFAULT_PREFETCH_DISTANCE: usize = 100;
+const DEFAULT_MAX_CONCURRENT_PAGE_LOADS: usize = 4;
+const DEFAULT_LOGS_BUFFER_SIZE: usize = 10_000;
+const DEFAULT_WEBHOOKS_QUEUE_SIZE: usize = 1_000;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SettingsModelYaml {
     #[serde(rename = "GrpcUrl")]
@@ -40,6 +53,384 @@ pub struct SettingsModelYaml {
 
     #[serde(rename = "PersistCompressed")]
     pub persist_compressed: bool,
+
+    #[serde(rename = "TcpPort")]
+    pub tcp_port: Option<u16>,
+
+    #[serde(rename = "HttpPort")]
+    pub http_port: Option<u16>,
+
+    #[serde(rename = "TcpListeners")]
+    pub tcp_listeners: Option<Vec<TcpListenerSettings>>,
+
+    #[serde(rename = "HttpAddress")]
+    pub http_address: Option<SocketAddr>,
+
+    #[serde(rename = "WebSocketPort")]
+    pub web_socket_port: Option<u16>,
+
+    #[serde(rename = "GrpcServerPort")]
+    pub grpc_server_port: Option<u16>,
+
+    #[serde(rename = "MqttPort")]
+    pub mqtt_port: Option<u16>,
+
+    #[serde(rename = "WebSocketAddress")]
+    pub web_socket_address: Option<SocketAddr>,
+
+    #[serde(rename = "GrpcServerAddress")]
+    pub grpc_server_address: Option<SocketAddr>,
+
+    #[serde(rename = "MqttAddress")]
+    pub mqtt_address: Option<SocketAddr>,
+
+    #[serde(rename = "LocalStoragePath")]
+    pub local_storage_path: Option<String>,
+
+    #[serde(rename = "LocalSnapshotPath")]
+    pub local_snapshot_path: Option<String>,
+
+    #[serde(rename = "LocalSnapshotMode")]
+    pub local_snapshot_mode: Option<String>,
+
+    #[serde(rename = "WalPath")]
+    pub wal_path: Option<String>,
+
+    #[serde(rename = "ReplicationRole")]
+    pub replication_role: Option<String>,
+
+    #[serde(rename = "ReplicationPort")]
+    pub replication_port: Option<u16>,
+
+    #[serde(rename = "ReplicationAddress")]
+    pub replication_address: Option<SocketAddr>,
+
+    #[serde(rename = "ReplicationActiveNode")]
+    pub replication_active_node: Option<String>,
+
+    #[serde(rename = "ReplicationMaxLag")]
+    pub replication_max_lag: Option<String>,
+
+    #[serde(rename = "ReplicationLeaseTimeout")]
+    pub replication_lease_timeout: Option<String>,
+
+    #[serde(rename = "ReplicationAutoPromote")]
+    pub replication_auto_promote: Option<bool>,
+
+    #[serde(rename = "ReplicationSecret")]
+    pub replication_secret: Option<String>,
+
+    #[serde(rename = "ClusterNodeId")]
+    pub cluster_node_id: Option<String>,
+
+    #[serde(rename = "ClusterNodes")]
+    pub cluster_nodes: Option<Vec<ClusterNodeSettings>>,
+
+    #[serde(rename = "ClusterAssignmentTable")]
+    pub cluster_assignment_table: Option<String>,
+
+    #[serde(rename = "ShutdownDrainTimeout")]
+    pub shutdown_drain_timeout: Option<String>,
+
+    #[serde(rename = "ApiKeys")]
+    pub api_keys: Option<Vec<ApiKeySettings>>,
+
+    #[serde(rename = "ApiKeysFile")]
+    pub api_keys_file: Option<String>,
+
+    #[serde(rename = "TopicAcl")]
+    pub topic_acl: Option<Vec<TopicAclSettings>>,
+
+    #[serde(rename = "AdminKeys")]
+    pub admin_keys: Option<Vec<AdminKeySettings>>,
+
+    #[serde(rename = "AdminAuditFile")]
+    pub admin_audit_file: Option<String>,
+
+    #[serde(rename = "AdminApiInsecure")]
+    pub admin_api_insecure: Option<bool>,
+
+    #[serde(rename = "PublishRateLimits")]
+    pub publish_rate_limits: Option<Vec<PublishRateLimitSettings>>,
+
+    #[serde(rename = "PublishRateLimitMaxDelay")]
+    pub publish_rate_limit_max_delay: Option<String>,
+
+    #[serde(rename = "MemoryBudget")]
+    pub memory_budget: Option<usize>,
+
+    #[serde(rename = "PrefetchDistance")]
+    pub prefetch_distance: Option<usize>,
+
+    #[serde(rename = "MaxConcurrentPageLoads")]
+    pub max_concurrent_page_loads: Option<usize>,
+
+    #[serde(rename = "OpenTelemetry")]
+    pub open_telemetry: Option<OpenTelemetrySettings>,
+
+    #[serde(rename = "LogsBufferSize")]
+    pub logs_buffer_size: Option<usize>,
+
+    #[serde(rename = "Webhooks")]
+    pub webhooks: Option<Vec<WebhookSettings>>,
+
+    #[serde(rename = "WebhooksQueueSize")]
+    pub webhooks_queue_size: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TcpListenerSettings {
+    #[serde(rename = "Name")]
+    pub name: String,
+
+    #[serde(rename = "Address")]
+    pub address: SocketAddr,
+
+    #[serde(rename = "Tls")]
+    pub tls: Option<TcpListenerTlsSettings>,
+
+    // Authentication is required by default if api keys are configured
+    #[serde(rename = "RequireAuth")]
+    pub require_auth: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TcpListenerTlsSettings {
+    #[serde(rename = "CertFile")]
+    pub cert_file: String,
+
+    #[serde(rename = "KeyFile")]
+    pub key_file: String,
+
+    // Client certificates are required and verified against this CA if specified
+    #[serde(rename = "ClientCaFile")]
+    pub client_ca_file: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ApiKeySettings {
+    // Replaces the name application reports about itself
+    #[serde(rename = "Identity")]
+    pub identity: String,
+
+    #[serde(rename = "Key")]
+    pub key: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TopicAclSettings {
+    #[serde(rename = "Identity")]
+    pub identity: String,
+
+    // Topic patterns. * matches any characters
+    #[serde(rename = "Publish")]
+    pub publish: Option<Vec<String>>,
+
+    #[serde(rename = "Subscribe")]
+    pub subscribe: Option<Vec<String>>,
+
+    // Queue patterns of the queues identity may create. Any queue if not specified
+    #[serde(rename = "CreateQueues")]
+    pub create_queues: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdminKeySettings {
+    #[serde(rename = "Identity")]
+    pub identity: String,
+
+    #[serde(rename = "Key")]
+    pub key: String,
+
+    // ReadOnly or Admin
+    #[serde(rename = "Scope")]
+    pub scope: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublishRateLimitSettings {
+    // App name pattern. Any application if not specified
+    #[serde(rename = "AppName")]
+    pub app_name: Option<String>,
+
+    // Topic pattern. Any topic if not specified
+    #[serde(rename = "TopicId")]
+    pub topic_id: Option<String>,
+
+    // Session or Application. Session if not specified
+    #[serde(rename = "Scope")]
+    pub scope: Option<String>,
+
+    #[serde(rename = "MessagesPerSecond")]
+    pub messages_per_second: Option<u64>,
+
+    #[serde(rename = "BytesPerSecond")]
+    pub bytes_per_second: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OpenTelemetrySettings {
+    // OTLP grpc endpoint of the collector
+    #[serde(rename = "Endpoint")]
+    pub endpoint: String,
+
+    #[serde(rename = "ServiceName")]
+    pub service_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookSettings {
+    #[serde(rename = "Url")]
+    pub url: String,
+
+    // Event types to send. All events if not specified
+    #[serde(rename = "Events")]
+    pub events: Option<Vec<String>>,
+
+    // Body is signed with HMAC-SHA256 if specified
+    #[serde(rename = "Secret")]
+    pub secret: Option<String>,
+}
+
+pub struct AuthSettings {
+    pub api_keys: Vec<ApiKeySettings>,
+    pub api_keys_file: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClusterNodeSettings {
+    #[serde(rename = "Id")]
+    pub id: String,
+
+    #[serde(rename = "TcpAddress")]
+    pub tcp_address: String,
+
+    #[serde(rename = "HttpUrl")]
+    pub http_url: String,
+}
+
+pub struct ClusterSettings {
+    pub node_id: String,
+    pub nodes: Vec<ClusterNodeSettings>,
+    pub assignment_table_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalSnapshotMode {
+    Primary,
+    Mirror,
+}
+
+impl LocalSnapshotMode {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src.to_lowercase().as_str() {
+            "primary" => Some(Self::Primary),
+            "mirror" => Some(Self::Mirror),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AdminScope {
+    ReadOnly,
+    Admin,
+}
+
+impl AdminScope {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src.to_lowercase().as_str() {
+            "readonly" => Some(Self::ReadOnly),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AdminKey {
+    pub identity: String,
+    pub key: String,
+    pub scope: AdminScope,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishRateLimitScope {
+    // Every session has its own limit
+    Session,
+    // Sessions with the same app name share the limit
+    Application,
+}
+
+impl PublishRateLimitScope {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src.to_lowercase().as_str() {
+            "session" => Some(Self::Session),
+            "application" => Some(Self::Application),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PublishRateLimit {
+    pub app_name: String,
+    pub topic_id: String,
+    pub scope: PublishRateLimitScope,
+    pub messages_per_second: Option<u64>,
+    pub bytes_per_second: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEventType {
+    TopicCreated,
+    TopicDeleted,
+    QueueCreated,
+    QueueDeleted,
+    SubscriberKicked,
+    SessionDisconnected,
+    PersistenceDegraded,
+    PersistenceRecovered,
+}
+
+impl WebhookEventType {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src.to_lowercase().as_str() {
+            "topiccreated" => Some(Self::TopicCreated),
+            "topicdeleted" => Some(Self::TopicDeleted),
+            "queuecreated" => Some(Self::QueueCreated),
+            "queuedeleted" => Some(Self::QueueDeleted),
+            "subscriberkicked" => Some(Self::SubscriberKicked),
+            "sessiondisconnected" => Some(Self::SessionDisconnected),
+            "persistencedegraded" => Some(Self::PersistenceDegraded),
+            "persistencerecovered" => Some(Self::PersistenceRecovered),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    pub url: String,
+    // All events if None
+    pub events: Option<Vec<WebhookEventType>>,
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplicationRole {
+    Active,
+    Passive,
+}
+
+impl ReplicationRole {
+    pub fn parse(src: &str) -> Option<Self> {
+        match src.to_lowercase().as_str() {
+            "active" => Some(Self::Active),
+            "passive" => Some(Self::Passive),
+            _ => None,
+        }
+    }
 }
 
 pub struct SettingsModel {
@@ -54,38 +445,124 @@ pub struct SettingsModel {
     pub auto_create_topic_on_subscribe: bool,
     pub persist_timer_interval: Duration,
     pub persist_compressed: bool,
+    pub tcp_listeners: Vec<TcpListenerSettings>,
+    pub http_address: SocketAddr,
+    pub web_socket_address: Option<SocketAddr>,
+    pub grpc_server_address: Option<SocketAddr>,
+    pub mqtt_address: Option<SocketAddr>,
+    pub local_storage_path: Option<String>,
+    // Directory of local topics and queues snapshot. Falls back to LocalStoragePath
+    pub local_snapshot_path: Option<String>,
+    pub local_snapshot_mode: Option<LocalSnapshotMode>,
+    pub wal_path: Option<String>,
+    pub replication_role: Option<ReplicationRole>,
+    pub replication_address: Option<SocketAddr>,
+    pub replication_active_node: Option<String>,
+    pub replication_max_lag: Duration,
+    pub replication_lease_timeout: Option<Duration>,
+    // Old active node has to be fenced externally, there is no promotion epoch
+    pub replication_auto_promote: bool,
+    // Nodes prove each other they know the secret before anything is replicated
+    pub replication_secret: Option<String>,
+    pub cluster: Option<ClusterSettings>,
+    pub shutdown_drain_timeout: Duration,
+    pub auth: Option<AuthSettings>,
+    pub topic_acl: Option<Vec<TopicAclSettings>>,
+    pub admin_keys: Option<Vec<AdminKey>>,
+    pub admin_audit_file: Option<String>,
+    pub admin_api_insecure: bool,
+    pub publish_rate_limits: Vec<PublishRateLimit>,
+    pub publish_rate_limit_max_delay: Duration,
+    pub memory_budget: Option<usize>,
+    pub prefetch_distance: usize,
+    pub max_concurrent_page_loads: usize,
+    pub open_telemetry: Option<OpenTelemetrySettings>,
+    pub logs_buffer_size: usize,
+    pub webhooks: Vec<Webhook>,
+    pub webhooks_queue_size: usize,
+    // Effective settings at startup. Settings which differ from them on reload require restart
+    pub yaml: Option<serde_yaml::Value>,
 }
 
-impl SettingsModel {
-    pub async fn read() -> Self {
-        let filename = get_settings_filename();
+impl SettingsModelYaml {
+    // Settings file, then MY_SB_* environment variables, then command line flags
+    pub async fn read() -> Result<Self, String> {
+        let sources = SettingsSources::read()?;
+
+        let file_content = read_settings_file(&sources).await?;
+
+        for settings_override in &sources.overrides {
+            println!(
+                "Setting {} is overridden by {:?}",
+                settings_override.key, settings_override.source
+            );
+        }
 
-        println!("Reading settings file {}", filename);
+        crate::settings_layers::compose(file_content.as_deref(), &sources.overrides)
+    }
+}
 
-        let file = File::open(&filename).await;
+async fn read_settings_file(sources: &SettingsSources) -> Result<Option<Vec<u8>>, String> {
+    let filename = sources.file_path.as_str();
 
-        if let Err(err) = file {
-            panic!(
+    println!("Reading settings file {}", filename);
+
+    let mut file = match File::open(filename).await {
+        Ok(file) => file,
+        Err(err) => {
+            if !sources.file_path_is_explicit && err.kind() == std::io::ErrorKind::NotFound {
+                println!(
+                    "Settings file {} is not found. Only environment variables and command line flags are used",
+                    filename
+                );
+                return Ok(None);
+            }
+
+            return Err(format!(
                 "Can not open settings file: {}. The reason is: {:?}",
                 filename, err
-            );
+            ));
         }
+    };
 
-        let mut file = file.unwrap();
+    let mut file_content: Vec<u8> = Vec::new();
 
-        let mut file_content: Vec<u8> = Vec::new();
+    loop {
+        let res = file.read_buf(&mut file_content).await.map_err(|err| {
+            format!(
+                "Can not read settings file: {}. The reason is: {:?}",
+                filename, err
+            )
+        })?;
+
+        if res == 0 {
+            break;
+        }
+    }
+
+    Ok(Some(file_content))
+}
 
-        loop {
-            let res = file.read_buf(&mut file_content).await.unwrap();
+impl SettingsModel {
+    pub async fn read() -> Self {
+        let result: Self = match SettingsModelYaml::read().await {
+            Ok(result) => result.into(),
+            Err(err) => panic!("{}", err),
+        };
 
-            if res == 0 {
-                break;
+        if let Some(effective_settings) = result.get_effective_settings() {
+            if let Ok(content) = serde_yaml::to_string(&effective_settings) {
+                println!("Effective settings:\n{}", content);
             }
         }
 
-        let result: SettingsModelYaml = serde_yaml::from_slice(&file_content).unwrap();
+        result
+    }
 
-        result.into()
+    // Settings as they are applied at startup with secrets masked
+    pub fn get_effective_settings(&self) -> Option<serde_yaml::Value> {
+        let yaml = self.yaml.as_ref()?;
+        Some(crate::settings_layers::mask_secrets(yaml))
     }
 
     #[cfg(test)]
@@ -99,6 +576,44 @@ impl SettingsModel {
             auto_create_topic_on_subscribe: true,
             persist_timer_interval: Duration::from_secs(1),
             persist_compressed: false,
+            tcp_listeners: vec![TcpListenerSettings {
+                name: DEFAULT_TCP_LISTENER_NAME.to_string(),
+                address: SocketAddr::from(([0, 0, 0, 0], DEFAULT_TCP_PORT)),
+                tls: None,
+                require_auth: None,
+            }],
+            http_address: SocketAddr::from(([0, 0, 0, 0], DEFAULT_HTTP_PORT)),
+            web_socket_address: None,
+            grpc_server_address: None,
+            mqtt_address: None,
+            local_storage_path: None,
+            local_snapshot_path: None,
+            local_snapshot_mode: None,
+            wal_path: None,
+            replication_role: None,
+            replication_address: None,
+            replication_active_node: None,
+            replication_max_lag: Duration::from_secs(1),
+            replication_lease_timeout: None,
+            replication_auto_promote: false,
+            replication_secret: None,
+            cluster: None,
+            shutdown_drain_timeout: Duration::from_secs(1),
+            auth: None,
+            topic_acl: None,
+            admin_keys: None,
+            admin_audit_file: None,
+            admin_api_insecure: false,
+            publish_rate_limits: vec![],
+            publish_rate_limit_max_delay: DEFAULT_PUBLISH_RATE_LIMIT_MAX_DELAY,
+            memory_budget: None,
+            prefetch_distance: 0,
+            max_concurrent_page_loads: DEFAULT_MAX_CONCURRENT_PAGE_LOADS,
+            open_telemetry: None,
+            logs_buffer_size: DEFAULT_LOGS_BUFFER_SIZE,
+            webhooks: vec![],
+            webhooks_queue_size: DEFAULT_WEBHOOKS_QUEUE_SIZE,
+            yaml: None,
         }
     }
 
@@ -121,22 +636,10 @@ impl SettingsModel {
     }
 }
 
-#[cfg(target_os = "windows")]
-fn get_settings_filename() -> String {
-    let home_path = env!("HOME");
-    let filename = format!("{}\\{}", home_path, ".myservicebus");
-    filename
-}
-
-#[cfg(not(target_os = "windows"))]
-fn get_settings_filename() -> String {
-    let home_path = env!("HOME");
-    let filename = format!("{}/{}", home_path, ".myservicebus");
-    filename
-}
-
 impl Into<SettingsModel> for SettingsModelYaml {
     fn into(self) -> SettingsModel {
+        let yaml = serde_yaml::to_value(&self).ok();
+
         let queue_gc_timeout =
             rust_extensions::duration_utils::parse_duration(self.queue_gc_timeout.as_str())
                 .unwrap();
@@ -190,6 +693,509 @@ impl Into<SettingsModel> for SettingsModelYaml {
             false
         };
 
+        let web_socket_address = get_listener_address(
+            self.web_socket_address,
+            self.web_socket_port,
+            "WebSocketAddress",
+            "WebSocketPort",
+        );
+
+        if let Some(web_socket_address) = web_socket_address {
+            println!("WebSocket server is enabled at {}", web_socket_address);
+        } else {
+            println!(
+                "WebSocket server is disabled. To enable please add parameter WebSocketPort: 6124"
+            );
+        }
+
+        let grpc_server_address = get_listener_address(
+            self.grpc_server_address,
+            self.grpc_server_port,
+            "GrpcServerAddress",
+            "GrpcServerPort",
+        );
+
+        if let Some(grpc_server_address) = grpc_server_address {
+            println!("Grpc server is enabled at {}", grpc_server_address);
+        } else {
+            println!(
+                "Grpc server is disabled. To enable please add parameter GrpcServerPort: 6125"
+            );
+        }
+
+        let mqtt_address =
+            get_listener_address(self.mqtt_address, self.mqtt_port, "MqttAddress", "MqttPort");
+
+        if let Some(mqtt_address) = mqtt_address {
+            println!("MQTT gateway is enabled at {}", mqtt_address);
+        } else {
+            println!("MQTT gateway is disabled. To enable please add parameter MqttPort: 1883");
+        }
+
+        if let Some(local_storage_path) = &self.local_storage_path {
+            println!(
+                "Messages are persisted to local disk at {}. GrpcUrl is not used for messages",
+                local_storage_path
+            );
+        }
+
+        let local_snapshot_path = self
+            .local_snapshot_path
+            .clone()
+            .or_else(|| self.local_storage_path.clone());
+
+        let local_snapshot_mode = match (&local_snapshot_path, &self.local_snapshot_mode) {
+            (None, None) => None,
+            (None, Some(_)) => {
+                panic!(
+                    "LocalSnapshotMode requires LocalSnapshotPath or LocalStoragePath to be specified"
+                );
+            }
+            (Some(_), None) => Some(LocalSnapshotMode::Primary),
+            (Some(_), Some(mode)) => match LocalSnapshotMode::parse(mode) {
+                Some(mode) => Some(mode),
+                None => panic!(
+                    "Invalid LocalSnapshotMode: {}. Supported values: Primary, Mirror",
+                    mode
+                ),
+            },
+        };
+
+        if let (Some(local_snapshot_mode), Some(local_snapshot_path)) =
+            (local_snapshot_mode, &local_snapshot_path)
+        {
+            println!(
+                "Topics and queues snapshot local mode: {:?} at {}",
+                local_snapshot_mode, local_snapshot_path
+            );
+        }
+
+        if let Some(wal_path) = &self.wal_path {
+            println!(
+                "Write-ahead spool of not persisted messages is enabled at {}",
+                wal_path
+            );
+        } else {
+            println!("Write-ahead spool of not persisted messages is disabled. To enable please add parameter WalPath: /var/lib/myservicebus/wal");
+        }
+
+        let replication_role = match &self.replication_role {
+            Some(role) => match ReplicationRole::parse(role) {
+                Some(role) => Some(role),
+                None => panic!(
+                    "Invalid ReplicationRole: {}. Supported values: Active, Passive",
+                    role
+                ),
+            },
+            None => None,
+        };
+
+        let replication_address = get_listener_address(
+            self.replication_address,
+            self.replication_port,
+            "ReplicationAddress",
+            "ReplicationPort",
+        );
+
+        match replication_role {
+            Some(ReplicationRole::Active) => match replication_address {
+                Some(replication_address) => {
+                    println!(
+                        "Replication role is Active. Passive nodes are accepted at {}",
+                        replication_address
+                    );
+                }
+                None => panic!(
+                    "ReplicationRole: Active requires ReplicationPort or ReplicationAddress to be specified"
+                ),
+            },
+            Some(ReplicationRole::Passive) => match &self.replication_active_node {
+                Some(replication_active_node) => {
+                    println!(
+                        "Replication role is Passive. Replicating from active node {}",
+                        replication_active_node
+                    );
+                }
+                None => panic!(
+                    "ReplicationRole: Passive requires ReplicationActiveNode to be specified"
+                ),
+            },
+            None => {
+                println!("Replication is disabled. To enable please add parameter ReplicationRole: Active or ReplicationRole: Passive");
+            }
+        }
+
+        if replication_role.is_some() {
+            match &self.replication_secret {
+                Some(secret) if !secret.is_empty() => {}
+                _ => panic!("ReplicationRole requires ReplicationSecret to be specified"),
+            }
+        }
+
+        let replication_max_lag = match &self.replication_max_lag {
+            Some(src) => match rust_extensions::duration_utils::parse_duration(src.as_str()) {
+                Ok(result) => result,
+                Err(err) => panic!(
+                    "Can not parse ReplicationMaxLag value '{}'. Reason: {:?}",
+                    src, err
+                ),
+            },
+            None => Duration::from_secs(1),
+        };
+
+        let replication_lease_timeout = match &self.replication_lease_timeout {
+            Some(src) => match rust_extensions::duration_utils::parse_duration(src.as_str()) {
+                Ok(result) => Some(result),
+                Err(err) => panic!(
+                    "Can not parse ReplicationLeaseTimeout value '{}'. Reason: {:?}",
+                    src, err
+                ),
+            },
+            None => None,
+        };
+
+        let replication_auto_promote = self.replication_auto_promote.unwrap_or(false);
+
+        match (replication_lease_timeout, replication_auto_promote) {
+            (Some(lease_timeout), true) => {
+                println!(
+                    "Passive node is promoted automatically if active node is silent for {:?}. Active node must be fenced externally",
+                    lease_timeout
+                );
+            }
+            (None, true) => {
+                panic!("ReplicationAutoPromote requires ReplicationLeaseTimeout to be specified");
+            }
+            (Some(lease_timeout), false) => {
+                println!(
+                    "Expiration of the {:?} lease is reported only. Passive node is promoted manually",
+                    lease_timeout
+                );
+            }
+            (None, false) => {}
+        }
+
+        let cluster = match (self.cluster_node_id, self.cluster_nodes) {
+            (None, None) => {
+                println!("Cluster mode is disabled. To enable please add parameters ClusterNodeId and ClusterNodes");
+                None
+            }
+            (Some(node_id), Some(nodes)) => {
+                if !nodes.iter().any(|itm| itm.id == node_id) {
+                    panic!("ClusterNodeId {} is not found in ClusterNodes", node_id);
+                }
+
+                println!(
+                    "Cluster mode is enabled. Node {} of {} nodes",
+                    node_id,
+                    nodes.len()
+                );
+
+                Some(ClusterSettings {
+                    node_id,
+                    nodes,
+                    assignment_table_path: self.cluster_assignment_table,
+                })
+            }
+            _ => {
+                panic!("Cluster mode requires both ClusterNodeId and ClusterNodes to be specified")
+            }
+        };
+
+        let tcp_listeners = match self.tcp_listeners {
+            Some(tcp_listeners) => {
+                check_tcp_listeners(&tcp_listeners);
+
+                if self.tcp_port.is_some() {
+                    println!("TcpPort is ignored since TcpListeners are specified");
+                }
+
+                tcp_listeners
+            }
+            None => vec![TcpListenerSettings {
+                name: DEFAULT_TCP_LISTENER_NAME.to_string(),
+                address: SocketAddr::from((
+                    [0, 0, 0, 0],
+                    self.tcp_port.unwrap_or(DEFAULT_TCP_PORT),
+                )),
+                tls: None,
+                require_auth: None,
+            }],
+        };
+
+        let http_address = match self.http_address {
+            Some(http_address) => {
+                if self.http_port.is_some() {
+                    println!("HttpPort is ignored since HttpAddress is specified");
+                }
+
+                http_address
+            }
+            None => SocketAddr::from(([0, 0, 0, 0], self.http_port.unwrap_or(DEFAULT_HTTP_PORT))),
+        };
+
+        let shutdown_drain_timeout = match &self.shutdown_drain_timeout {
+            Some(src) => match rust_extensions::duration_utils::parse_duration(src.as_str()) {
+                Ok(result) => result,
+                Err(err) => panic!(
+                    "Can not parse ShutdownDrainTimeout value '{}'. Reason: {:?}",
+                    src, err
+                ),
+            },
+            None => DEFAULT_SHUTDOWN_DRAIN_TIMEOUT,
+        };
+
+        let auth = match (self.api_keys, self.api_keys_file) {
+            (None, None) => {
+                println!("Authentication is disabled. To enable please add parameter ApiKeys or ApiKeysFile");
+                None
+            }
+            (api_keys, api_keys_file) => {
+                let api_keys = api_keys.unwrap_or_default();
+
+                if let Err(err) = crate::auth::check_api_keys(&api_keys) {
+                    panic!("ApiKeys are not valid. {}", err);
+                }
+
+                println!(
+                    "Authentication is enabled with {} api keys in settings",
+                    api_keys.len()
+                );
+
+                if let Some(api_keys_file) = &api_keys_file {
+                    println!("Api keys are also read from {}", api_keys_file);
+                }
+
+                Some(AuthSettings {
+                    api_keys,
+                    api_keys_file,
+                })
+            }
+        };
+
+        if let Some(topic_acl) = &self.topic_acl {
+            if auth.is_none() {
+                panic!("TopicAcl requires ApiKeys or ApiKeysFile to be specified");
+            }
+
+            for (index, item) in topic_acl.iter().enumerate() {
+                if topic_acl[..index]
+                    .iter()
+                    .any(|itm| itm.identity == item.identity)
+                {
+                    panic!("TopicAcl of identity {} is duplicated", item.identity);
+                }
+            }
+
+            println!("Topic ACL is enabled for {} identities", topic_acl.len());
+        }
+
+        let admin_keys = match self.admin_keys {
+            Some(admin_keys) => {
+                let mut result = Vec::with_capacity(admin_keys.len());
+
+                for admin_key in admin_keys {
+                    let scope = match AdminScope::parse(admin_key.scope.as_str()) {
+                        Some(scope) => scope,
+                        None => panic!(
+                            "Invalid Scope {} of admin key {}. Supported values: ReadOnly, Admin",
+                            admin_key.scope, admin_key.identity
+                        ),
+                    };
+
+                    if admin_key.key.is_empty() {
+                        panic!("Key of admin key {} is empty", admin_key.identity);
+                    }
+
+                    if result.iter().any(|itm: &AdminKey| itm.key == admin_key.key) {
+                        panic!("Key of admin key {} is duplicated", admin_key.identity);
+                    }
+
+                    result.push(AdminKey {
+                        identity: admin_key.identity,
+                        key: admin_key.key,
+                        scope,
+                    });
+                }
+
+                println!("Admin api is protected with {} admin keys", result.len());
+
+                if self.admin_api_insecure.unwrap_or(false) {
+                    println!("AdminApiInsecure is ignored since AdminKeys are specified");
+                }
+
+                Some(result)
+            }
+            None => {
+                if self.admin_api_insecure.unwrap_or(false) {
+                    println!("WARNING: Admin api is not protected since AdminApiInsecure is true. Anyone who reaches http port can manage the service bus");
+                } else {
+                    println!("Admin api is closed since AdminKeys are not specified. Please add AdminKeys or set AdminApiInsecure: true");
+                }
+
+                None
+            }
+        };
+
+        if let Some(admin_audit_file) = &self.admin_audit_file {
+            println!("Admin calls are audited to {}", admin_audit_file);
+        }
+
+        let mut publish_rate_limits = Vec::new();
+
+        for item in self.publish_rate_limits.unwrap_or_default() {
+            let app_name = item.app_name.unwrap_or_else(|| "*".to_string());
+            let topic_id = item.topic_id.unwrap_or_else(|| "*".to_string());
+
+            let scope = match &item.scope {
+                Some(scope) => match PublishRateLimitScope::parse(scope) {
+                    Some(scope) => scope,
+                    None => panic!(
+                        "Invalid Scope {} of publish rate limit {}/{}. Supported values: Session, Application",
+                        scope, app_name, topic_id
+                    ),
+                },
+                None => PublishRateLimitScope::Session,
+            };
+
+            match (item.messages_per_second, item.bytes_per_second) {
+                (None, None) => panic!(
+                    "Publish rate limit {}/{} requires MessagesPerSecond or BytesPerSecond",
+                    app_name, topic_id
+                ),
+                (Some(0), _) | (_, Some(0)) => panic!(
+                    "Publish rate limit {}/{} must be greater than zero",
+                    app_name, topic_id
+                ),
+                _ => {}
+            }
+
+            publish_rate_limits.push(PublishRateLimit {
+                app_name,
+                topic_id,
+                scope,
+                messages_per_second: item.messages_per_second,
+                bytes_per_second: item.bytes_per_second,
+            });
+        }
+
+        let publish_rate_limit_max_delay = match &self.publish_rate_limit_max_delay {
+            Some(src) => match rust_extensions::duration_utils::parse_duration(src.as_str()) {
+                Ok(result) => result,
+                Err(err) => panic!(
+                    "Can not parse PublishRateLimitMaxDelay value '{}'. Reason: {:?}",
+                    src, err
+                ),
+            },
+            None => DEFAULT_PUBLISH_RATE_LIMIT_MAX_DELAY,
+        };
+
+        if publish_rate_limits.is_empty() {
+            println!("Publish rate limits are disabled. To enable please add parameter PublishRateLimits");
+        } else {
+            println!(
+                "{} publish rate limits are enabled. Publishers are delayed up to {:?}",
+                publish_rate_limits.len(),
+                publish_rate_limit_max_delay
+            );
+        }
+
+        match self.memory_budget {
+            Some(0) => panic!("MemoryBudget must be greater than 0"),
+            Some(memory_budget) => println!(
+                "Memory budget is {} bytes. Persisted sub pages are evicted when it is exceeded",
+                memory_budget
+            ),
+            None => {
+                println!("Memory budget is disabled. To enable please add parameter MemoryBudget")
+            }
+        }
+
+        let prefetch_distance = self.prefetch_distance.unwrap_or(DEFAULT_PREFETCH_DISTANCE);
+
+        if prefetch_distance == 0 {
+            println!("Prefetch of the next sub page is disabled");
+        } else {
+            println!(
+                "Next sub page is prefetched when queue is {} messages before the end of sub page",
+                prefetch_distance
+            );
+        }
+
+        let max_concurrent_page_loads = self
+            .max_concurrent_page_loads
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_PAGE_LOADS);
+
+        if max_concurrent_page_loads == 0 {
+            panic!("MaxConcurrentPageLoads must be greater than 0");
+        }
+
+        let logs_buffer_size = self.logs_buffer_size.unwrap_or(DEFAULT_LOGS_BUFFER_SIZE);
+
+        if logs_buffer_size == 0 {
+            panic!("LogsBufferSize must be greater than 0");
+        }
+
+        let mut webhooks = Vec::new();
+
+        for item in self.webhooks.unwrap_or_default() {
+            if !item.url.starts_with("http://") && !item.url.starts_with("https://") {
+                panic!(
+                    "Url of webhook {} must start with http:// or https://",
+                    item.url
+                );
+            }
+
+            let events = match item.events {
+                Some(events) => {
+                    let mut result = Vec::with_capacity(events.len());
+
+                    for event in events {
+                        match WebhookEventType::parse(event.as_str()) {
+                            Some(event_type) => result.push(event_type),
+                            None => panic!(
+                                "Invalid event {} of webhook {}. Supported values: TopicCreated, TopicDeleted, QueueCreated, QueueDeleted, SubscriberKicked, SessionDisconnected, PersistenceDegraded, PersistenceRecovered",
+                                event, item.url
+                            ),
+                        }
+                    }
+
+                    Some(result)
+                }
+                None => None,
+            };
+
+            webhooks.push(Webhook {
+                url: item.url,
+                events,
+                secret: item.secret,
+            });
+        }
+
+        let webhooks_queue_size = self
+            .webhooks_queue_size
+            .unwrap_or(DEFAULT_WEBHOOKS_QUEUE_SIZE);
+
+        if webhooks_queue_size == 0 {
+            panic!("WebhooksQueueSize must be greater than 0");
+        }
+
+        if webhooks.is_empty() {
+            println!("Webhooks are disabled. To enable please add parameter Webhooks");
+        } else {
+            println!("Events are sent to {} webhooks", webhooks.len());
+        }
+
+        match &self.open_telemetry {
+            Some(open_telemetry) => println!(
+                "OpenTelemetry spans are exported to {}",
+                open_telemetry.endpoint
+            ),
+            None => {
+                println!("OpenTelemetry is disabled. To enable please add parameter OpenTelemetry")
+            }
+        }
+
         SettingsModel {
             persistence_grpc_url: self.persistence_grpc_url,
             queue_gc_timeout,
@@ -199,20 +1205,156 @@ impl Into<SettingsModel> for SettingsModelYaml {
             auto_create_topic_on_subscribe,
             persist_timer_interval: Duration::from_str(&self.persist_timer_interval).unwrap(),
             persist_compressed: self.persist_compressed,
+            tcp_listeners,
+            http_address,
+            web_socket_address,
+            grpc_server_address,
+            mqtt_address,
+            local_storage_path: self.local_storage_path,
+            local_snapshot_path,
+            local_snapshot_mode,
+            wal_path: self.wal_path,
+            replication_role,
+            replication_address,
+            replication_active_node: self.replication_active_node,
+            replication_max_lag,
+            replication_lease_timeout,
+            replication_auto_promote,
+            replication_secret: self.replication_secret,
+            cluster,
+            shutdown_drain_timeout,
+            auth,
+            topic_acl: self.topic_acl,
+            admin_keys,
+            admin_audit_file: self.admin_audit_file,
+            admin_api_insecure: self.admin_api_insecure.unwrap_or(false),
+            publish_rate_limits,
+            publish_rate_limit_max_delay,
+            memory_budget: self.memory_budget,
+            prefetch_distance,
+            max_concurrent_page_loads,
+            open_telemetry: self.open_telemetry,
+            logs_buffer_size,
+            webhooks,
+            webhooks_queue_size,
+            yaml,
+        }
+    }
+}
+
+fn get_listener_address(
+    address: Option<SocketAddr>,
+    port: Option<u16>,
+    address_name: &str,
+    port_name: &str,
+) -> Option<SocketAddr> {
+    match address {
+        Some(address) => {
+            if port.is_some() {
+                println!(
+                    "{} is ignored since {} is specified",
+                    port_name, address_name
+                );
+            }
+
+            Some(address)
+        }
+        None => port.map(|port| SocketAddr::from(([0, 0, 0, 0], port))),
+    }
+}
+
+fn check_tcp_listeners(tcp_listeners: &[TcpListenerSettings]) {
+    if tcp_listeners.is_empty() {
+        panic!("TcpListeners must contain at least one listener");
+    }
+
+    for (index, listener) in tcp_listeners.iter().enumerate() {
+        if tcp_listeners[..index]
+            .iter()
+            .any(|itm| itm.name == listener.name || itm.address == listener.address)
+        {
+            panic!(
+                "Tcp listener {} at {} is duplicated",
+                listener.name, listener.address
+            );
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{net::SocketAddr, time::Duration};
 
     use rust_extensions::duration_utils::DurationExtensions;
 
+    use super::TcpListenerSettings;
+
+    fn create_listener(name: &str, address: &str) -> TcpListenerSettings {
+        TcpListenerSettings {
+            name: name.to_string(),
+            address: address.parse().unwrap(),
+            tls: None,
+            require_auth: None,
+        }
+    }
+
     #[test]
     fn test() {
         let diration = Duration::from_str("100ms").unwrap();
 
         println!("{:?}", diration);
     }
+
+    #[test]
+    fn test_tcp_listeners_with_different_names_and_addresses() {
+        super::check_tcp_listeners(&[
+            create_listener("internal", "10.0.0.5:6421"),
+            create_listener("external", "[::]:6421"),
+            create_listener("local", "127.0.0.1:6422"),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "TcpListeners must contain at least one listener")]
+    fn test_empty_tcp_listeners() {
+        super::check_tcp_listeners(&[]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tcp listener internal at [::]:6422 is duplicated")]
+    fn test_tcp_listeners_with_duplicated_name() {
+        super::check_tcp_listeners(&[
+            create_listener("internal", "[::]:6421"),
+            create_listener("internal", "[::]:6422"),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Tcp listener external at 0.0.0.0:6421 is duplicated")]
+    fn test_tcp_listeners_with_duplicated_address() {
+        super::check_tcp_listeners(&[
+            create_listener("internal", "0.0.0.0:6421"),
+            create_listener("external", "0.0.0.0:6421"),
+        ]);
+    }
+
+    #[test]
+    fn test_listener_address_replaces_port() {
+        let address: SocketAddr = "[::1]:6124".parse().unwrap();
+
+        assert_eq!(
+            Some(address),
+            super::get_listener_address(Some(address), Some(6125), "Address", "Port")
+        );
+
+        assert_eq!(
+            Some(SocketAddr::from(([0, 0, 0, 0], 6125))),
+            super::get_listener_address(None, Some(6125), "Address", "Port")
+        );
+
+        assert_eq!(
+            None,
+            super::get_listener_address(None, None, "Address", "Port")
+        );
+    }
 }
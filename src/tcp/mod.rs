@@ -1,2 +1,3 @@
 mod error;
 pub mod socket_events;
+pub mod tls;
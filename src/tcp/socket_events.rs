@@ -8,17 +8,48 @@ use my_service_bus::{
     tcp_contracts::{MySbSerializerState, MySbTcpConnection, MySbTcpContract, MySbTcpSerializer},
 };
 
-use crate::{app::AppContext, operations};
+use crate::{
+    app::{AppContext, ListenerStatus},
+    operations::{self, OperationFailResult},
+    sessions::SessionId,
+};
 
-use super::error::MySbSocketError;
+use super::{error::MySbSocketError, tls::TlsPeersList};
 
 pub struct TcpServerEvents {
     app: Arc<AppContext>,
+    listener: Arc<ListenerStatus>,
+    // Some if listener accepts connections only through TLS proxy
+    tls_peers: Option<Arc<TlsPeersList>>,
 }
 
 impl TcpServerEvents {
-    pub fn new(app: Arc<AppContext>) -> Self {
-        Self { app }
+    pub fn new(
+        app: Arc<AppContext>,
+        listener: Arc<ListenerStatus>,
+        tls_peers: Option<Arc<TlsPeersList>>,
+    ) -> Self {
+        Self {
+            app,
+            listener,
+            tls_peers,
+        }
+    }
+
+    // Connection which did not send greeting has no session, so it can not confirm deliveries
+    async fn get_session_id(
+        &self,
+        connection: &Arc<MySbTcpConnection>,
+    ) -> Result<SessionId, MySbSocketError> {
+        match self
+            .app
+            .sessions
+            .get_session_id_by_tcp_connection_id(connection.id)
+            .await
+        {
+            Some(session_id) => Ok(session_id),
+            None => Err(OperationFailResult::SessionIsDisconnected.into()),
+        }
     }
 
     pub async fn handle_incoming_packet(
@@ -38,28 +69,87 @@ impl TcpServerEvents {
             } => {
                 println!(
                     "New tcp connection [{}] with name: {} and protocol_version {}",
-                    connection.id, name, protocol_version
+                    connection.id,
+                    name.split(';').take(2).collect::<Vec<_>>().join(";"),
+                    protocol_version
                 );
+
+                if self.app.states.is_shutting_down() {
+                    reject_and_disconnect(
+                        connection,
+                        format!("{:?}", operations::OperationFailResult::ShuttingDown),
+                    )
+                    .await;
+                    return Ok(());
+                }
+
+                let tls_peer = match &self.tls_peers {
+                    Some(tls_peers) => {
+                        let tls_peer = connection.addr.and_then(|addr| tls_peers.get(&addr));
+
+                        if tls_peer.is_none() {
+                            reject_and_disconnect(
+                                connection,
+                                "Connection is not established through TLS".to_string(),
+                            )
+                            .await;
+                            return Ok(());
+                        }
+
+                        tls_peer
+                    }
+                    None => None,
+                };
+
+                // Name is AppName;Version;Credential where Credential is api key or hmac token
                 let mut connection_name = None;
                 let mut version = None;
+                let mut credential = None;
 
                 let mut no = 0;
                 for itm in name.split(";") {
                     match no {
                         0 => connection_name = Some(itm.to_string()),
                         1 => version = Some(itm.to_string()),
+                        2 => credential = Some(itm),
                         _ => {}
                     }
                     no += 1;
                 }
 
+                let ip = match &tls_peer {
+                    Some(tls_peer) => tls_peer.client_addr.to_string(),
+                    None => connection
+                        .addr
+                        .map(|addr| addr.to_string())
+                        .unwrap_or_default(),
+                };
+
+                let identity = match operations::authenticate_session(
+                    self.app.as_ref(),
+                    self.listener.as_ref(),
+                    connection_name.as_deref().unwrap_or_default(),
+                    credential,
+                    ip.as_str(),
+                )
+                .await
+                {
+                    Ok(identity) => identity,
+                    Err(err) => {
+                        reject_and_disconnect(connection, format!("Unauthorized. {:?}", err)).await;
+                        return Ok(());
+                    }
+                };
+
                 self.app
                     .sessions
                     .add_tcp(
                         connection.clone(),
-                        connection_name.unwrap(),
+                        identity.clone().or(connection_name).unwrap_or_default(),
                         version,
                         protocol_version,
+                        tls_peer,
+                        identity,
                     )
                     .await;
 
@@ -71,31 +161,41 @@ impl TcpServerEvents {
                 persist_immediately,
                 data_to_publish,
             } => {
-                if let Some(session_id) = self
+                if let Some(session) = self
                     .app
                     .sessions
-                    .get_session_id_by_tcp_connection_id(connection.id)
+                    .get_tcp_session_by_connection_id(connection.id)
                     .await
                 {
-                    let result = operations::publisher::publish(
+                    let result = operations::publisher::publish_with_rate_limit(
                         &self.app,
+                        session.session_id,
+                        session.name.as_str(),
                         topic_id.as_str(),
                         data_to_publish,
                         persist_immediately,
-                        session_id,
                     )
                     .await;
 
-                    if let Err(err) = result {
-                        connection
-                            .send(&MySbTcpContract::Reject {
-                                message: format!("{:?}", err),
-                            })
-                            .await;
-                    } else {
-                        connection
-                            .send(&MySbTcpContract::PublishResponse { request_id })
-                            .await;
+                    match result {
+                        Ok(delay) => {
+                            session
+                                .send_response(
+                                    MySbTcpContract::PublishResponse { request_id },
+                                    delay,
+                                )
+                                .await;
+                        }
+                        Err(err) => {
+                            session
+                                .send_response(
+                                    MySbTcpContract::Reject {
+                                        message: format!("{:?}", err),
+                                    },
+                                    None,
+                                )
+                                .await;
+                        }
                     }
                 }
 
@@ -141,11 +241,14 @@ impl TcpServerEvents {
                 queue_id,
                 confirmation_id,
             } => {
+                let session_id = self.get_session_id(connection).await?;
+
                 operations::delivery_confirmation::all_confirmed(
                     &self.app,
                     topic_id.as_str(),
                     queue_id.as_str(),
                     confirmation_id.into(),
+                    session_id,
                 )
                 .await?;
 
@@ -175,11 +278,14 @@ impl TcpServerEvents {
                 confirmation_id,
                 delivered,
             } => {
+                let session_id = self.get_session_id(connection).await?;
+
                 operations::delivery_confirmation::intermediary_confirm(
                     &self.app,
                     topic_id.as_str(),
                     queue_id.as_str(),
                     confirmation_id.into(),
+                    session_id,
                     QueueWithIntervals::restore(delivered),
                 )
                 .await?;
@@ -212,11 +318,14 @@ impl TcpServerEvents {
                 confirmation_id,
             } => {
                 println!("Confirm all fail packet");
+                let session_id = self.get_session_id(connection).await?;
+
                 operations::delivery_confirmation::all_fail(
                     &self.app,
                     topic_id.as_str(),
                     queue_id.as_str(),
                     confirmation_id.into(),
+                    session_id,
                 )
                 .await?;
                 Ok(())
@@ -229,11 +338,14 @@ impl TcpServerEvents {
                 confirmation_id,
                 delivered,
             } => {
+                let session_id = self.get_session_id(connection).await?;
+
                 operations::delivery_confirmation::some_messages_are_confirmed(
                     &self.app,
                     topic_id.as_str(),
                     queue_id.as_str(),
                     confirmation_id.into(),
+                    session_id,
                     QueueWithIntervals::restore(delivered),
                 )
                 .await?;
@@ -260,10 +372,12 @@ impl SocketEventCallback<MySbTcpContract, MySbTcpSerializer, MySbSerializerState
 {
     async fn connected(&self, _connection: Arc<MySbTcpConnection>) {
         self.app.prometheus.mark_new_tcp_connection();
+        self.listener.connected();
     }
 
     async fn disconnected(&self, connection: Arc<MySbTcpConnection>) {
         self.app.prometheus.mark_new_tcp_disconnection();
+        self.listener.disconnected();
         if let Some(session) = self.app.sessions.remove_tcp(connection.id).await {
             crate::operations::sessions::disconnect(self.app.as_ref(), session).await;
         }
@@ -280,3 +394,8 @@ impl SocketEventCallback<MySbTcpContract, MySbTcpSerializer, MySbSerializerState
         }
     }
 }
+
+async fn reject_and_disconnect(connection: &Arc<MySbTcpConnection>, message: String) {
+    connection.send(&MySbTcpContract::Reject { message }).await;
+    connection.disconnect().await;
+}
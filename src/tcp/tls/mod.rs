@@ -0,0 +1,7 @@
+mod tls_peers;
+mod tls_proxy;
+mod tls_server_config;
+
+pub use tls_peers::*;
+pub use tls_proxy::*;
+pub use tls_server_config::*;
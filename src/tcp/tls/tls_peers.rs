@@ -0,0 +1,33 @@
+use std::{collections::HashMap, net::SocketAddr, sync::Mutex};
+
+#[derive(Debug, Clone)]
+pub struct TlsPeer {
+    pub client_addr: SocketAddr,
+    pub client_certificate_subject: Option<String>,
+}
+
+// TLS connections are decrypted and proxied to internal plain tcp listener.
+// Key is the address the proxied connection comes from to the internal listener
+pub struct TlsPeersList {
+    items: Mutex<HashMap<SocketAddr, TlsPeer>>,
+}
+
+impl TlsPeersList {
+    pub fn new() -> Self {
+        Self {
+            items: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn add(&self, proxy_addr: SocketAddr, peer: TlsPeer) {
+        self.items.lock().unwrap().insert(proxy_addr, peer);
+    }
+
+    pub fn remove(&self, proxy_addr: &SocketAddr) {
+        self.items.lock().unwrap().remove(proxy_addr);
+    }
+
+    pub fn get(&self, proxy_addr: &SocketAddr) -> Option<TlsPeer> {
+        self.items.lock().unwrap().get(proxy_addr).cloned()
+    }
+}
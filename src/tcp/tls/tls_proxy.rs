@@ -0,0 +1,139 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use my_logger::LogEventCtx;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
+
+use crate::app::AppContext;
+
+use super::{TlsPeer, TlsPeersList, TlsServerConfig};
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+// Terminates TLS at public address and forwards decrypted stream to internal plain tcp listener
+pub struct TlsProxy {
+    pub public_addr: SocketAddr,
+    pub internal_addr: SocketAddr,
+    pub config: Arc<TlsServerConfig>,
+    pub peers: Arc<TlsPeersList>,
+}
+
+impl TlsProxy {
+    pub fn start(self, app: Arc<AppContext>) {
+        tokio::spawn(accept_loop(app, Arc::new(self)));
+    }
+}
+
+// Free loopback port for internal listener which only TLS proxy connects to.
+// Port is released before internal listener binds it, so binding has to be retried if port is taken meanwhile
+pub fn get_internal_addr() -> SocketAddr {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0")
+        .expect("Can not allocate loopback port for TLS listener");
+
+    listener.local_addr().unwrap()
+}
+
+async fn accept_loop(app: Arc<AppContext>, proxy: Arc<TlsProxy>) {
+    let listener = match TcpListener::bind(proxy.public_addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            panic!(
+                "Can not start TLS listener {} at {}. Err: {:?}",
+                proxy.config.listener_name, proxy.public_addr, err
+            );
+        }
+    };
+
+    println!(
+        "TLS listener {} is started at {}",
+        proxy.config.listener_name, proxy.public_addr
+    );
+
+    while !app.states.is_shutting_down() {
+        match listener.accept().await {
+            Ok((stream, client_addr)) => {
+                tokio::spawn(handle_connection(proxy.clone(), stream, client_addr));
+            }
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "Tls Accept".to_string(),
+                    format!("{:?}", err),
+                    LogEventCtx::new().add("listener", proxy.config.listener_name.as_str()),
+                );
+
+                tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+            }
+        }
+    }
+}
+
+async fn handle_connection(proxy: Arc<TlsProxy>, stream: TcpStream, client_addr: SocketAddr) {
+    let acceptor = TlsAcceptor::from(proxy.config.get());
+
+    let mut tls_stream =
+        match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+            Ok(Ok(tls_stream)) => tls_stream,
+            Ok(Err(err)) => {
+                my_logger::LOGGER.write_error(
+                    "Tls Handshake".to_string(),
+                    format!("{:?}", err),
+                    LogEventCtx::new()
+                        .add("listener", proxy.config.listener_name.as_str())
+                        .add("ip", client_addr.to_string()),
+                );
+                return;
+            }
+            Err(_) => {
+                my_logger::LOGGER.write_error(
+                    "Tls Handshake".to_string(),
+                    format!("Handshake is not finished within {:?}", HANDSHAKE_TIMEOUT),
+                    LogEventCtx::new()
+                        .add("listener", proxy.config.listener_name.as_str())
+                        .add("ip", client_addr.to_string()),
+                );
+                return;
+            }
+        };
+
+    let client_certificate_subject = tls_stream
+        .get_ref()
+        .1
+        .peer_certificates()
+        .and_then(|certs| certs.first())
+        .and_then(|cert| get_subject(cert.as_ref()));
+
+    let mut internal_stream = match TcpStream::connect(proxy.internal_addr).await {
+        Ok(internal_stream) => internal_stream,
+        Err(err) => {
+            my_logger::LOGGER.write_error(
+                "Tls Proxy".to_string(),
+                format!("Can not connect to internal listener. Err: {:?}", err),
+                LogEventCtx::new().add("listener", proxy.config.listener_name.as_str()),
+            );
+            return;
+        }
+    };
+
+    let proxy_addr = match internal_stream.local_addr() {
+        Ok(proxy_addr) => proxy_addr,
+        Err(_) => return,
+    };
+
+    proxy.peers.add(
+        proxy_addr,
+        TlsPeer {
+            client_addr,
+            client_certificate_subject,
+        },
+    );
+
+    let _ = tokio::io::copy_bidirectional(&mut tls_stream, &mut internal_stream).await;
+
+    proxy.peers.remove(&proxy_addr);
+}
+
+fn get_subject(der: &[u8]) -> Option<String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(der).ok()?;
+    Some(cert.subject().to_string())
+}
@@ -0,0 +1,289 @@
+use std::{
+    sync::{Arc, RwLock},
+    time::SystemTime,
+};
+
+use my_logger::LogEventCtx;
+use tokio_rustls::rustls::{
+    pki_types::{CertificateDer, PrivateKeyDer},
+    server::WebPkiClientVerifier,
+    RootCertStore, ServerConfig,
+};
+
+use crate::settings::TcpListenerTlsSettings;
+
+// Certificate files are checked by timer and config is replaced if any of them is changed.
+// Connections which are already established keep the config they are accepted with
+pub struct TlsServerConfig {
+    pub listener_name: String,
+    settings: TcpListenerTlsSettings,
+    config: RwLock<Arc<ServerConfig>>,
+    files_modified: RwLock<Vec<Option<SystemTime>>>,
+}
+
+impl TlsServerConfig {
+    pub fn new(listener_name: String, settings: TcpListenerTlsSettings) -> Result<Self, String> {
+        let files_modified = get_files_modified(&settings);
+        let config = load_server_config(&settings)?;
+
+        Ok(Self {
+            listener_name,
+            settings,
+            config: RwLock::new(Arc::new(config)),
+            files_modified: RwLock::new(files_modified),
+        })
+    }
+
+    pub fn requires_client_certificate(&self) -> bool {
+        self.settings.client_ca_file.is_some()
+    }
+
+    pub fn get(&self) -> Arc<ServerConfig> {
+        self.config.read().unwrap().clone()
+    }
+
+    pub fn reload_if_changed(&self) {
+        let files_modified = get_files_modified(&self.settings);
+
+        if *self.files_modified.read().unwrap() == files_modified {
+            return;
+        }
+
+        *self.files_modified.write().unwrap() = files_modified;
+
+        match load_server_config(&self.settings) {
+            Ok(config) => {
+                *self.config.write().unwrap() = Arc::new(config);
+
+                my_logger::LOGGER.write_info(
+                    "Tls",
+                    "Certificates are reloaded".to_string(),
+                    LogEventCtx::new().add("listener", self.listener_name.as_str()),
+                );
+            }
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "Tls",
+                    format!(
+                        "Can not reload certificates. Previous ones are kept. {}",
+                        err
+                    ),
+                    LogEventCtx::new().add("listener", self.listener_name.as_str()),
+                );
+            }
+        }
+    }
+}
+
+fn get_files_modified(settings: &TcpListenerTlsSettings) -> Vec<Option<SystemTime>> {
+    let mut files = vec![settings.cert_file.as_str(), settings.key_file.as_str()];
+
+    if let Some(client_ca_file) = &settings.client_ca_file {
+        files.push(client_ca_file.as_str());
+    }
+
+    files
+        .into_iter()
+        .map(|file| {
+            std::fs::metadata(file)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+        .collect()
+}
+
+fn load_server_config(settings: &TcpListenerTlsSettings) -> Result<ServerConfig, String> {
+    let certs = load_certificates(settings.cert_file.as_str())?;
+    let key = load_private_key(settings.key_file.as_str())?;
+
+    let builder = ServerConfig::builder();
+
+    let builder = match &settings.client_ca_file {
+        Some(client_ca_file) => {
+            let mut roots = RootCertStore::empty();
+
+            for cert in load_certificates(client_ca_file.as_str())? {
+                roots.add(cert).map_err(|err| {
+                    format!(
+                        "Invalid CA certificate in {}. Err: {:?}",
+                        client_ca_file, err
+                    )
+                })?;
+            }
+
+            let verifier = WebPkiClientVerifier::builder(Arc::new(roots))
+                .build()
+                .map_err(|err| {
+                    format!("Can not create client certificate verifier. Err: {:?}", err)
+                })?;
+
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    builder
+        .with_single_cert(certs, key)
+        .map_err(|err| format!("Invalid certificate or key. Err: {:?}", err))
+}
+
+fn load_certificates(file_name: &str) -> Result<Vec<CertificateDer<'static>>, String> {
+    let content = std::fs::read(file_name)
+        .map_err(|err| format!("Can not read {}. Err: {:?}", file_name, err))?;
+
+    let certs = rustls_pemfile::certs(&mut content.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Can not parse certificates {}. Err: {:?}", file_name, err))?;
+
+    if certs.is_empty() {
+        return Err(format!("No certificates found in {}", file_name));
+    }
+
+    Ok(certs)
+}
+
+fn load_private_key(file_name: &str) -> Result<PrivateKeyDer<'static>, String> {
+    let content = std::fs::read(file_name)
+        .map_err(|err| format!("Can not read {}. Err: {:?}", file_name, err))?;
+
+    match rustls_pemfile::private_key(&mut content.as_slice()) {
+        Ok(Some(key)) => Ok(key),
+        Ok(None) => Err(format!("No private key found in {}", file_name)),
+        Err(err) => Err(format!(
+            "Can not parse private key {}. Err: {:?}",
+            file_name, err
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, sync::Arc, time::SystemTime};
+
+    use crate::settings::TcpListenerTlsSettings;
+
+    use super::TlsServerConfig;
+
+    fn get_test_file(file_name: &str) -> String {
+        format!("{}/test_data/tls/{}", env!("CARGO_MANIFEST_DIR"), file_name)
+    }
+
+    fn create_settings(cert_file: String, key_file: String) -> TcpListenerTlsSettings {
+        TcpListenerTlsSettings {
+            cert_file,
+            key_file,
+            client_ca_file: None,
+        }
+    }
+
+    fn replace_file(dest: &Path, content: &[u8], modified: SystemTime) {
+        std::fs::write(dest, content).unwrap();
+
+        std::fs::File::options()
+            .write(true)
+            .open(dest)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_certificates_are_loaded() {
+        let settings = create_settings(get_test_file("server.crt"), get_test_file("server.key"));
+
+        let config = TlsServerConfig::new("test".to_string(), settings).unwrap();
+
+        assert!(!config.requires_client_certificate());
+    }
+
+    #[test]
+    fn test_client_ca_is_loaded() {
+        let mut settings =
+            create_settings(get_test_file("server.crt"), get_test_file("server.key"));
+        settings.client_ca_file = Some(get_test_file("server2.crt"));
+
+        let config = TlsServerConfig::new("test".to_string(), settings).unwrap();
+
+        assert!(config.requires_client_certificate());
+    }
+
+    #[test]
+    fn test_key_file_without_private_key() {
+        let settings = create_settings(get_test_file("server.crt"), get_test_file("server.crt"));
+
+        assert!(TlsServerConfig::new("test".to_string(), settings).is_err());
+    }
+
+    #[test]
+    fn test_missing_files() {
+        let settings = create_settings(
+            get_test_file("not_existing.crt"),
+            get_test_file("server.key"),
+        );
+
+        assert!(TlsServerConfig::new("test".to_string(), settings).is_err());
+    }
+
+    #[test]
+    fn test_certificates_are_reloaded_when_changed() {
+        let root_path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&root_path).unwrap();
+
+        let cert_file = root_path.join("server.crt");
+        let key_file = root_path.join("server.key");
+
+        let first_modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000);
+
+        replace_file(
+            &cert_file,
+            &std::fs::read(get_test_file("server.crt")).unwrap(),
+            first_modified,
+        );
+        replace_file(
+            &key_file,
+            &std::fs::read(get_test_file("server.key")).unwrap(),
+            first_modified,
+        );
+
+        let config = TlsServerConfig::new(
+            "test".to_string(),
+            create_settings(
+                cert_file.to_str().unwrap().to_string(),
+                key_file.to_str().unwrap().to_string(),
+            ),
+        )
+        .unwrap();
+
+        let first_config = config.get();
+
+        // Nothing is changed
+        config.reload_if_changed();
+        assert!(Arc::ptr_eq(&first_config, &config.get()));
+
+        // Broken key keeps previous certificates
+        let second_modified = first_modified + std::time::Duration::from_secs(1);
+        replace_file(&key_file, b"broken", second_modified);
+
+        config.reload_if_changed();
+        assert!(Arc::ptr_eq(&first_config, &config.get()));
+
+        // New pair is loaded
+        let third_modified = second_modified + std::time::Duration::from_secs(1);
+
+        replace_file(
+            &cert_file,
+            &std::fs::read(get_test_file("server2.crt")).unwrap(),
+            third_modified,
+        );
+        replace_file(
+            &key_file,
+            &std::fs::read(get_test_file("server2.key")).unwrap(),
+            third_modified,
+        );
+
+        config.reload_if_changed();
+        assert!(!Arc::ptr_eq(&first_config, &config.get()));
+
+        std::fs::remove_dir_all(root_path).unwrap();
+    }
+}
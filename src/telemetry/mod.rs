@@ -0,0 +1,5 @@
+mod telemetry;
+mod traceparent;
+
+pub use telemetry::*;
+pub use traceparent::*;
@@ -0,0 +1,373 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, queue_with_intervals::QueueWithIntervals,
+};
+use my_service_bus::shared::protobuf_models::MessageProtobufModel;
+use opentelemetry::{
+    trace::{Span as _, SpanContext, SpanKind, Status, TraceContextExt, Tracer, TracerProvider},
+    Context, KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{
+    trace::{SdkTracer, SdkTracerProvider, Span},
+    Resource,
+};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use crate::{
+    messages_page::MySbMessageContent, queue_subscribers::SubscriberId,
+    settings::OpenTelemetrySettings, topics::TopicInner,
+};
+
+const TRACER_NAME: &str = "my-service-bus";
+const DEFAULT_SERVICE_NAME: &str = "my-service-bus";
+const MESSAGING_SYSTEM: &str = "myservicebus";
+
+struct TelemetryInner {
+    provider: SdkTracerProvider,
+    tracer: SdkTracer,
+}
+
+// Spans are emitted only for messages which have W3C traceparent header
+pub struct Telemetry {
+    inner: Option<TelemetryInner>,
+}
+
+pub struct PersistTraceParents {
+    started: SystemTime,
+    messages: Vec<(i64, SpanContext)>,
+}
+
+impl Telemetry {
+    pub fn new(settings: Option<&OpenTelemetrySettings>) -> Self {
+        let settings = match settings {
+            Some(settings) => settings,
+            None => return Self { inner: None },
+        };
+
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .with_endpoint(settings.endpoint.as_str())
+            .build()
+            .unwrap_or_else(|err| {
+                panic!(
+                    "Can not create OpenTelemetry exporter to {}. Err: {:?}",
+                    settings.endpoint, err
+                )
+            });
+
+        let service_name = settings
+            .service_name
+            .clone()
+            .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name(service_name).build())
+            .build();
+
+        Self::from_provider(provider)
+    }
+
+    fn from_provider(provider: SdkTracerProvider) -> Self {
+        let tracer = provider.tracer(TRACER_NAME);
+
+        Self {
+            inner: Some(TelemetryInner { provider, tracer }),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_exporter(exporter: opentelemetry_sdk::trace::InMemorySpanExporter) -> Self {
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter)
+            .build();
+
+        Self::from_provider(provider)
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.inner.is_some()
+    }
+
+    // Traceparent of the message is replaced with the publish span,
+    // so consumers continue the trace from the bus
+    pub fn start_publish_spans(
+        &self,
+        topic_id: &str,
+        messages: &mut [MessageToPublish],
+    ) -> Vec<Span> {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return vec![],
+        };
+
+        let mut result = Vec::new();
+
+        for message in messages.iter_mut() {
+            let parent = match get_parent_context(&message.headers) {
+                Some(parent) => parent,
+                None => continue,
+            };
+
+            let span = inner
+                .tracer
+                .span_builder(format!("publish {}", topic_id))
+                .with_kind(SpanKind::Producer)
+                .with_attributes(vec![
+                    KeyValue::new("messaging.system", MESSAGING_SYSTEM),
+                    KeyValue::new("messaging.destination.name", topic_id.to_string()),
+                    KeyValue::new("messaging.message.body.size", message.content.len() as i64),
+                ])
+                .start_with_context(&inner.tracer, &parent);
+
+            message.headers = super::set_traceparent(
+                &message.headers,
+                super::format_traceparent(span.span_context()),
+            );
+
+            result.push(span);
+        }
+
+        result
+    }
+
+    pub fn end_publish_spans(&self, spans: Vec<Span>, error: Option<String>) {
+        for mut span in spans {
+            if let Some(error) = &error {
+                span.set_status(Status::error(error.to_string()));
+            }
+
+            span.end();
+        }
+    }
+
+    pub fn get_persist_trace_parents(
+        &self,
+        messages: &[MessageProtobufModel],
+    ) -> Option<PersistTraceParents> {
+        if self.inner.is_none() {
+            return None;
+        }
+
+        let mut result = Vec::new();
+
+        for message in messages {
+            let traceparent = message
+                .headers
+                .iter()
+                .find(|itm| itm.key.eq_ignore_ascii_case(super::TRACEPARENT_HEADER));
+
+            if let Some(span_context) =
+                traceparent.and_then(|itm| super::parse_traceparent(itm.value.as_str()))
+            {
+                result.push((message.get_message_id().get_value(), span_context));
+            }
+        }
+
+        if result.is_empty() {
+            return None;
+        }
+
+        Some(PersistTraceParents {
+            started: SystemTime::now(),
+            messages: result,
+        })
+    }
+
+    pub fn record_persist_spans(
+        &self,
+        topic_id: &str,
+        trace_parents: Option<PersistTraceParents>,
+        error: Option<String>,
+    ) {
+        let (inner, trace_parents) = match (&self.inner, trace_parents) {
+            (Some(inner), Some(trace_parents)) => (inner, trace_parents),
+            _ => return,
+        };
+
+        let ended = SystemTime::now();
+
+        for (message_id, span_context) in trace_parents.messages {
+            let parent = Context::new().with_remote_span_context(span_context);
+
+            let mut span = inner
+                .tracer
+                .span_builder(format!("persist {}", topic_id))
+                .with_kind(SpanKind::Client)
+                .with_start_time(trace_parents.started)
+                .with_attributes(vec![
+                    KeyValue::new("messaging.system", MESSAGING_SYSTEM),
+                    KeyValue::new("messaging.destination.name", topic_id.to_string()),
+                    KeyValue::new("messaging.message.id", message_id.to_string()),
+                ])
+                .start_with_context(&inner.tracer, &parent);
+
+            if let Some(error) = &error {
+                span.set_status(Status::error(error.to_string()));
+            }
+
+            span.end_with_timestamp(ended);
+        }
+    }
+
+    // Span covers the time message waited in the queue
+    pub fn record_delivery_span(
+        &self,
+        topic_id: &str,
+        queue_id: &str,
+        subscriber_id: SubscriberId,
+        message: &MySbMessageContent,
+        attempt_no: i32,
+    ) {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return,
+        };
+
+        let parent = match get_parent_context(&message.headers) {
+            Some(parent) => parent,
+            None => return,
+        };
+
+        inner
+            .tracer
+            .span_builder(format!("deliver {}", topic_id))
+            .with_kind(SpanKind::Producer)
+            .with_start_time(to_system_time(message.time))
+            .with_attributes(vec![
+                KeyValue::new("messaging.system", MESSAGING_SYSTEM),
+                KeyValue::new("messaging.destination.name", topic_id.to_string()),
+                KeyValue::new("messaging.consumer.group.name", queue_id.to_string()),
+                KeyValue::new("messaging.message.id", message.id.get_value().to_string()),
+                KeyValue::new("subscriber.id", subscriber_id.get_value()),
+                KeyValue::new("delivery.attempt", attempt_no as i64),
+            ])
+            .start_with_context(&inner.tracer, &parent)
+            .end();
+    }
+
+    // Span covers the time from the start of delivery till the confirmation
+    pub fn record_confirmation_spans(
+        &self,
+        topic_data: &TopicInner,
+        queue_id: &str,
+        ids: &QueueWithIntervals,
+        delivery_started: DateTimeAsMicroseconds,
+        delivered: bool,
+    ) {
+        let inner = match &self.inner {
+            Some(inner) => inner,
+            None => return,
+        };
+
+        let messages = topic_data.get_messages(ids, |message| {
+            (message.id.get_value(), get_parent_context(&message.headers))
+        });
+
+        let ended = SystemTime::now();
+
+        for (message_id, parent) in messages {
+            let parent = match parent {
+                Some(parent) => parent,
+                None => continue,
+            };
+
+            let mut span = inner
+                .tracer
+                .span_builder(format!("confirm {}", topic_data.topic_id.as_str()))
+                .with_kind(SpanKind::Server)
+                .with_start_time(to_system_time(delivery_started))
+                .with_attributes(vec![
+                    KeyValue::new("messaging.system", MESSAGING_SYSTEM),
+                    KeyValue::new(
+                        "messaging.destination.name",
+                        topic_data.topic_id.to_string(),
+                    ),
+                    KeyValue::new("messaging.consumer.group.name", queue_id.to_string()),
+                    KeyValue::new("messaging.message.id", message_id.to_string()),
+                    KeyValue::new("delivered", delivered),
+                ])
+                .start_with_context(&inner.tracer, &parent);
+
+            if !delivered {
+                span.set_status(Status::error("Message is not delivered"));
+            }
+
+            span.end_with_timestamp(ended);
+        }
+    }
+
+    // Exports spans which are not exported yet
+    pub fn shutdown(&self) {
+        if let Some(inner) = &self.inner {
+            if let Err(err) = inner.provider.shutdown() {
+                println!("Can not shutdown OpenTelemetry exporter. Err: {:?}", err);
+            }
+        }
+    }
+}
+
+fn get_parent_context(headers: &my_service_bus::abstractions::SbMessageHeaders) -> Option<Context> {
+    let span_context = super::parse_traceparent(super::get_traceparent(headers)?)?;
+    Some(Context::new().with_remote_span_context(span_context))
+}
+
+fn to_system_time(src: DateTimeAsMicroseconds) -> SystemTime {
+    UNIX_EPOCH + Duration::from_micros(src.unix_microseconds.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus::abstractions::SbMessageHeaders;
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+
+    use super::super::TRACEPARENT_HEADER;
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn test_publish_span_continues_trace_of_the_message() {
+        // In memory exporter stands in for the OTLP collector
+        let exporter = InMemorySpanExporter::default();
+        let telemetry = Telemetry::with_exporter(exporter.clone());
+
+        let mut messages = vec![
+            MessageToPublish {
+                content: vec![0u8; 10],
+                headers: SbMessageHeaders::new()
+                    .add(TRACEPARENT_HEADER.to_string(), TRACEPARENT.to_string()),
+            },
+            MessageToPublish {
+                content: vec![0u8; 10],
+                headers: SbMessageHeaders::new(),
+            },
+        ];
+
+        let spans = telemetry.start_publish_spans("test-topic", &mut messages);
+        assert_eq!(spans.len(), 1);
+
+        telemetry.end_publish_spans(spans, None);
+
+        let finished_spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(finished_spans.len(), 1);
+
+        let parent = super::super::parse_traceparent(TRACEPARENT).unwrap();
+        let span = &finished_spans[0];
+
+        assert_eq!(span.name, "publish test-topic");
+        assert_eq!(span.parent_span_id, parent.span_id());
+        assert_eq!(span.span_context.trace_id(), parent.trace_id());
+
+        // Delivered message carries the publish span as a parent
+        let delivered_traceparent = super::super::get_traceparent(&messages[0].headers).unwrap();
+        assert_eq!(
+            delivered_traceparent,
+            super::super::format_traceparent(&span.span_context)
+        );
+
+        assert!(super::super::get_traceparent(&messages[1].headers).is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use my_service_bus::abstractions::SbMessageHeaders;
+use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceId, TraceState};
+
+// W3C trace context header: {version}-{trace-id}-{parent-id}-{trace-flags}
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+pub fn get_traceparent(headers: &SbMessageHeaders) -> Option<&str> {
+    headers
+        .iter()
+        .find(|itm| itm.0.eq_ignore_ascii_case(TRACEPARENT_HEADER))
+        .map(|itm| itm.1.as_str())
+}
+
+pub fn set_traceparent(headers: &SbMessageHeaders, traceparent: String) -> SbMessageHeaders {
+    let mut traceparent = Some(traceparent);
+
+    let mut items: Vec<(String, String)> = headers
+        .iter()
+        .map(|itm| {
+            if itm.0.eq_ignore_ascii_case(TRACEPARENT_HEADER) {
+                if let Some(traceparent) = traceparent.take() {
+                    return (itm.0.to_string(), traceparent);
+                }
+            }
+
+            (itm.0.to_string(), itm.1.to_string())
+        })
+        .collect();
+
+    if let Some(traceparent) = traceparent {
+        items.push((TRACEPARENT_HEADER.to_string(), traceparent));
+    }
+
+    SbMessageHeaders::from_iterator(items.len().into(), items.into_iter())
+}
+
+pub fn parse_traceparent(src: &str) -> Option<SpanContext> {
+    let mut parts = src.trim().split('-');
+
+    let version = parts.next()?;
+    let trace_id = parts.next()?;
+    let span_id = parts.next()?;
+    let flags = parts.next()?;
+
+    // Future versions may add fields, version 00 has exactly four
+    if version.len() != 2 || version == "ff" || (version == "00" && parts.next().is_some()) {
+        return None;
+    }
+
+    if trace_id.len() != 32 || span_id.len() != 16 || flags.len() != 2 {
+        return None;
+    }
+
+    let trace_id = TraceId::from_hex(trace_id).ok()?;
+    let span_id = SpanId::from_hex(span_id).ok()?;
+    let flags = u8::from_str_radix(flags, 16).ok()?;
+
+    if trace_id == TraceId::INVALID || span_id == SpanId::INVALID {
+        return None;
+    }
+
+    Some(SpanContext::new(
+        trace_id,
+        span_id,
+        TraceFlags::new(flags) & TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    ))
+}
+
+pub fn format_traceparent(span_context: &SpanContext) -> String {
+    format!(
+        "00-{}-{}-{:02x}",
+        span_context.trace_id(),
+        span_context.span_id(),
+        span_context.trace_flags().to_u8()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_traceparent() {
+        let src = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+        let span_context = parse_traceparent(src).unwrap();
+
+        assert!(span_context.is_remote());
+        assert!(span_context.is_sampled());
+        assert_eq!(format_traceparent(&span_context), src);
+
+        assert!(
+            parse_traceparent("00-00000000000000000000000000000000-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(
+            parse_traceparent("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7").is_none()
+        );
+        assert!(
+            parse_traceparent("ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").is_none()
+        );
+        assert!(parse_traceparent("not a traceparent").is_none());
+    }
+
+    #[test]
+    fn test_set_traceparent_replaces_existing_header() {
+        let headers = SbMessageHeaders::new()
+            .add("Traceparent".to_string(), "old".to_string())
+            .add("key".to_string(), "value".to_string());
+
+        let headers = set_traceparent(&headers, "new".to_string());
+
+        assert_eq!(headers.len(), 2);
+        assert_eq!(get_traceparent(&headers), Some("new"));
+
+        let headers = set_traceparent(&SbMessageHeaders::new(), "new".to_string());
+        assert_eq!(get_traceparent(&headers), Some("new"));
+    }
+}
@@ -102,6 +102,11 @@ impl Topic {
         write_access.mark_messages_as_persisted(bucket.sub_page_id, &bucket.ids);
     }
 
+    pub async fn get_min_not_persisted_message_id(&self) -> MessageId {
+        let read_access = self.get_access().await;
+        read_access.get_min_not_persisted_message_id()
+    }
+
     pub async fn get_topic_size_metrics(&self) -> SizeMetrics {
         let read_access = self.get_access().await;
         read_access.get_topic_size_metrics()
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use my_service_bus::abstractions::publisher::MessageToPublish;
@@ -8,7 +9,10 @@ use my_service_bus::shared::sub_page::SubPageId;
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 use crate::avg_value::AvgValue;
-use crate::messages_page::{ActiveSubPages, MessagesPageList, MySbMessageContent, SizeMetrics};
+use crate::messages_page::{
+    ActiveSubPages, GetMessageResult, MessagesPageList, MySbMessageContent, SizeMetrics,
+    SubPageEvictionCandidate,
+};
 use crate::queue_subscribers::QueueSubscriber;
 use crate::queues::{TopicQueue, TopicQueuesList};
 use crate::sessions::SessionId;
@@ -27,6 +31,8 @@ pub struct TopicInner {
     pub publishers: TopicPublishers,
     pub persist: bool,
     pub avg_size: AvgValue,
+    // First and last ids of messages which are being written to the write-ahead spool
+    reserved_messages: BTreeMap<i64, i64>,
 }
 
 impl TopicInner {
@@ -40,6 +46,7 @@ impl TopicInner {
             publishers: TopicPublishers::new(),
             persist,
             avg_size: AvgValue::new(),
+            reserved_messages: BTreeMap::new(),
         }
     }
 
@@ -48,19 +55,65 @@ impl TopicInner {
         self.publishers.add(session_id, BADGE_HIGHLIGHT_TIME_OUT);
     }
 
-    pub fn publish_messages(&mut self, session_id: SessionId, messages: Vec<MessageToPublish>) {
+    pub fn publish_messages(
+        &mut self,
+        session_id: SessionId,
+        messages: Vec<MessageToPublish>,
+    ) -> QueueWithIntervals {
+        let messages = self.prepare_messages(messages);
+        self.add_prepared_messages(session_id, messages)
+    }
+
+    // Gives ids to the messages. Messages are not visible until they are added to the topic
+    pub fn prepare_messages(&self, messages: Vec<MessageToPublish>) -> Vec<MySbMessageContent> {
+        let now = DateTimeAsMicroseconds::now();
+        let mut message_id = self.message_id.get_value();
+
+        messages
+            .into_iter()
+            .map(|msg| {
+                let message = MySbMessageContent {
+                    id: message_id.into(),
+                    content: msg.content,
+                    time: now,
+                    headers: msg.headers,
+                };
+
+                message_id += 1;
+                message
+            })
+            .collect()
+    }
+
+    // Ids of the prepared messages are not given to other messages while the topic lock is released.
+    // Reserved ids which are never added stay as a gap
+    pub fn reserve_prepared_messages(&mut self, messages: &[MySbMessageContent]) {
+        let (first, last) = match (messages.first(), messages.last()) {
+            (Some(first), Some(last)) => (first.id.get_value(), last.id.get_value()),
+            _ => return,
+        };
+
+        self.reserved_messages.insert(first, last);
+        self.message_id = (last + 1).into();
+    }
+
+    pub fn release_reserved_messages(&mut self, messages: &[MySbMessageContent]) {
+        if let Some(first) = messages.first() {
+            self.reserved_messages.remove(&first.id.get_value());
+        }
+    }
+
+    // Messages must be prepared by the same topic lock or reserved, so there are no other messages in between
+    pub fn add_prepared_messages(
+        &mut self,
+        session_id: SessionId,
+        messages: Vec<MySbMessageContent>,
+    ) -> QueueWithIntervals {
         self.set_publisher_as_active(session_id);
 
         let mut ids = QueueWithIntervals::new();
 
-        for msg in messages {
-            let message = MySbMessageContent {
-                id: self.message_id.into(),
-                content: msg.content,
-                time: DateTimeAsMicroseconds::now(),
-                headers: msg.headers,
-            };
-
+        for message in messages {
             self.avg_size.add(message.content.len());
 
             ids.enqueue(message.id.into());
@@ -71,12 +124,52 @@ impl TopicInner {
             page.update_last_accessed(message.time);
             page.add_message(message, self.persist);
 
-            self.message_id.increment();
+            if message.id.get_value() >= self.message_id.get_value() {
+                self.message_id = (message.id.get_value() + 1).into();
+            }
         }
 
         for topic_queue in self.queues.get_all_mut() {
             topic_queue.enqueue_messages(&ids);
         }
+
+        ids
+    }
+
+    pub fn get_messages<TResult>(
+        &self,
+        ids: impl IntoIterator<Item = i64>,
+        transform: impl Fn(&MySbMessageContent) -> TResult,
+    ) -> Vec<TResult> {
+        let mut result = Vec::new();
+
+        for message_id in ids {
+            let message_id = MessageId::new(message_id);
+            let sub_page_id: SubPageId = message_id.into();
+
+            if let Some(sub_page) = self.pages.get(sub_page_id) {
+                if let GetMessageResult::Message(msg) = sub_page.get_message(message_id) {
+                    result.push(transform(msg));
+                }
+            }
+        }
+
+        result
+    }
+
+    // All the messages with id below the value are persisted
+    pub fn get_min_not_persisted_message_id(&self) -> MessageId {
+        let result = match self.pages.get_min_message_id_to_persist() {
+            Some(message_id) => message_id,
+            None => self.message_id,
+        };
+
+        match self.reserved_messages.keys().next() {
+            Some(first_reserved) if *first_reserved < result.get_value() => {
+                (*first_reserved).into()
+            }
+            _ => result,
+        }
     }
 
     pub fn one_second_tick(&mut self) {
@@ -96,6 +189,7 @@ impl TopicInner {
         let mut min_message_id = MinMessageIdCalculator::new();
 
         min_message_id.add(Some(self.message_id.get_value()));
+        min_message_id.add(self.reserved_messages.keys().next().copied());
 
         for topic_queue in self.queues.get_all() {
             let min_id = topic_queue.queue.get_min_id();
@@ -124,6 +218,16 @@ impl TopicInner {
         result
     }
 
+    // Messages of not persisted topic can not be loaded back, so its sub pages are never evicted
+    pub fn get_eviction_candidates(&self) -> Vec<SubPageEvictionCandidate> {
+        if !self.persist {
+            return vec![];
+        }
+
+        let active_sub_pages = self.get_active_sub_pages();
+        self.pages.get_eviction_candidates(&active_sub_pages)
+    }
+
     pub fn gc_pages(&mut self) {
         if let Some(min_message_id) = self.get_min_message_id() {
             let active_sub_pages = self.get_active_sub_pages();
@@ -225,10 +329,16 @@ impl TopicInner {
 
 #[cfg(test)]
 mod tests {
-    use my_service_bus::abstractions::{
-        publisher::MessageToPublish, queue_with_intervals::QueueWithIntervals,
-        subscriber::TopicQueueType, SbMessageHeaders,
+    use my_service_bus::{
+        abstractions::{
+            publisher::MessageToPublish, queue_with_intervals::QueueWithIntervals,
+            subscriber::TopicQueueType, SbMessageHeaders,
+        },
+        shared::sub_page::SubPageId,
     };
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::messages_page::MySbMessageContent;
 
     #[test]
     fn test_we_deliver_then_persist_then_gc_message() {
@@ -273,4 +383,64 @@ mod tests {
 
         assert!(message_result.unwrap().is_garbage_collected());
     }
+
+    #[test]
+    fn test_sub_pages_of_not_persisted_topic_are_not_evicted() {
+        for persist in [true, false] {
+            let mut topic_inner = super::TopicInner::new("test".into(), 2000, persist);
+
+            // Message which does not need to be persisted: already persisted one, or one of not persisted topic
+            topic_inner
+                .pages
+                .get_or_create_mut(SubPageId::new(0))
+                .add_message(
+                    MySbMessageContent {
+                        id: 0.into(),
+                        content: vec![1, 2, 3],
+                        time: DateTimeAsMicroseconds::now(),
+                        headers: SbMessageHeaders::new(),
+                    },
+                    false,
+                );
+
+            let candidates = topic_inner.get_eviction_candidates();
+
+            if persist {
+                assert_eq!(1, candidates.len());
+                assert_eq!(0, candidates[0].sub_page_id.get_value());
+            } else {
+                assert!(candidates.is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_reserved_messages_keep_their_ids_and_are_not_persisted() {
+        let mut topic_inner = super::TopicInner::new("test".into(), 0, true);
+
+        let create_message = || MessageToPublish {
+            headers: SbMessageHeaders::new(),
+            content: vec![1, 2, 3],
+        };
+
+        let reserved = topic_inner.prepare_messages(vec![create_message(), create_message()]);
+        topic_inner.reserve_prepared_messages(&reserved);
+
+        topic_inner.publish_messages(10.into(), vec![create_message()]);
+
+        assert_eq!(topic_inner.message_id.get_value(), 3);
+        assert_eq!(
+            topic_inner.get_min_not_persisted_message_id().get_value(),
+            0
+        );
+
+        topic_inner.release_reserved_messages(&reserved);
+        topic_inner.add_prepared_messages(10.into(), reserved);
+
+        assert_eq!(topic_inner.message_id.get_value(), 3);
+        assert_eq!(
+            topic_inner.get_min_not_persisted_message_id().get_value(),
+            0
+        );
+    }
 }
@@ -54,7 +54,11 @@ impl TopicsList {
         (read_access.get_snapshot_id(), read_access.get_all())
     }
 
-    pub async fn add_if_not_exists(&self, topic_id: &str) -> Result<Arc<Topic>, InvalidTopicName> {
+    // Returns true if topic is created
+    pub async fn add_if_not_exists(
+        &self,
+        topic_id: &str,
+    ) -> Result<(Arc<Topic>, bool), InvalidTopicName> {
         let mut write_access = self.data.write().await;
 
         let result = write_access.add_if_not_exists(topic_id, true);
@@ -44,13 +44,14 @@ impl TopicListInner {
         self.snapshot_id
     }
 
+    // Returns true if topic is created
     pub fn add_if_not_exists(
         &mut self,
         topic_id: &str,
         persist: bool,
-    ) -> Result<Arc<Topic>, InvalidTopicName> {
+    ) -> Result<(Arc<Topic>, bool), InvalidTopicName> {
         match self.topics.get_or_create(topic_id) {
-            rust_extensions::sorted_vec::GetOrCreateEntry::Get(item) => Ok(item.clone()),
+            rust_extensions::sorted_vec::GetOrCreateEntry::Get(item) => Ok((item.clone(), false)),
             rust_extensions::sorted_vec::GetOrCreateEntry::Create(entry) => {
                 my_service_bus::shared::validators::validate_topic_name(topic_id)?;
 
@@ -58,7 +59,7 @@ impl TopicListInner {
                 let topic = Arc::new(topic);
                 let result = entry.insert_and_get_value(topic);
                 self.snapshot_id += 1;
-                return Ok(result.clone());
+                return Ok((result.clone(), true));
             }
         }
     }
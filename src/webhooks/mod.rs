@@ -0,0 +1,5 @@
+mod webhook_event;
+mod webhook_publisher;
+
+pub use webhook_event::*;
+pub use webhook_publisher::*;
@@ -0,0 +1,108 @@
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde::Serialize;
+
+use crate::settings::WebhookEventType;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum WebhookEvent {
+    TopicCreated {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+    },
+    TopicDeleted {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+    },
+    QueueCreated {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+    },
+    QueueDeleted {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+    },
+    // Subscriber did not confirm delivery in time and its session is disconnected
+    SubscriberKicked {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+        #[serde(rename = "subscriberId")]
+        subscriber_id: i64,
+        #[serde(rename = "sessionId")]
+        session_id: i64,
+    },
+    SessionDisconnected {
+        #[serde(rename = "sessionId")]
+        session_id: i64,
+        name: String,
+        ip: String,
+        #[serde(rename = "sessionType")]
+        session_type: String,
+    },
+    PersistenceDegraded {
+        error: String,
+    },
+    PersistenceRecovered {},
+}
+
+impl WebhookEvent {
+    pub fn get_event_type(&self) -> WebhookEventType {
+        match self {
+            WebhookEvent::TopicCreated { .. } => WebhookEventType::TopicCreated,
+            WebhookEvent::TopicDeleted { .. } => WebhookEventType::TopicDeleted,
+            WebhookEvent::QueueCreated { .. } => WebhookEventType::QueueCreated,
+            WebhookEvent::QueueDeleted { .. } => WebhookEventType::QueueDeleted,
+            WebhookEvent::SubscriberKicked { .. } => WebhookEventType::SubscriberKicked,
+            WebhookEvent::SessionDisconnected { .. } => WebhookEventType::SessionDisconnected,
+            WebhookEvent::PersistenceDegraded { .. } => WebhookEventType::PersistenceDegraded,
+            WebhookEvent::PersistenceRecovered { .. } => WebhookEventType::PersistenceRecovered,
+        }
+    }
+
+    pub fn to_json(&self, now: DateTimeAsMicroseconds) -> Vec<u8> {
+        let json_model = WebhookEventJsonModel {
+            date_time: now.to_rfc3339(),
+            event: self,
+        };
+
+        serde_json::to_vec(&json_model).unwrap()
+    }
+}
+
+#[derive(Serialize)]
+struct WebhookEventJsonModel<'s> {
+    #[serde(rename = "dateTime")]
+    date_time: String,
+    #[serde(flatten)]
+    event: &'s WebhookEvent,
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::WebhookEvent;
+
+    #[test]
+    fn test_event_is_serialized_with_type() {
+        let event = WebhookEvent::QueueDeleted {
+            topic_id: "test-topic".to_string(),
+            queue_id: "test-queue".to_string(),
+        };
+
+        let json = event.to_json(DateTimeAsMicroseconds::new(0));
+
+        let json: serde_json::Value = serde_json::from_slice(json.as_slice()).unwrap();
+
+        assert_eq!(json["type"], "QueueDeleted");
+        assert_eq!(json["topicId"], "test-topic");
+        assert_eq!(json["queueId"], "test-queue");
+        assert!(json["dateTime"].is_string());
+    }
+}
@@ -0,0 +1,270 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use hmac::{Hmac, Mac};
+use my_logger::LogEventCtx;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use sha2::Sha256;
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+
+use crate::settings::{SettingsModel, Webhook};
+
+use super::WebhookEvent;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const WEBHOOK_ATTEMPTS: usize = 5;
+const WEBHOOK_FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+const WEBHOOK_MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+const WEBHOOK_SIGNATURE_HEADER: &str = "X-MyServiceBus-Signature";
+
+struct WebhookEndpoint {
+    webhook: Arc<Webhook>,
+    sender: Sender<Arc<Vec<u8>>>,
+    receiver: Mutex<Option<Receiver<Arc<Vec<u8>>>>>,
+}
+
+// Every webhook has its own bounded queue, so slow endpoint does not delay others.
+// Events are dropped if the queue is full - publish never blocks the caller
+pub struct WebhookPublisher {
+    endpoints: Vec<WebhookEndpoint>,
+}
+
+impl WebhookPublisher {
+    pub fn new(settings: &SettingsModel) -> Self {
+        let endpoints = settings
+            .webhooks
+            .iter()
+            .map(|webhook| {
+                let (sender, receiver) = mpsc::channel(settings.webhooks_queue_size);
+                WebhookEndpoint {
+                    webhook: Arc::new(webhook.clone()),
+                    sender,
+                    receiver: Mutex::new(Some(receiver)),
+                }
+            })
+            .collect();
+
+        Self { endpoints }
+    }
+
+    pub fn start(&self) {
+        for endpoint in &self.endpoints {
+            if let Some(receiver) = endpoint.receiver.lock().unwrap().take() {
+                tokio::spawn(send_events(endpoint.webhook.clone(), receiver));
+            }
+        }
+    }
+
+    pub fn publish(&self, event: WebhookEvent) {
+        if self.endpoints.is_empty() {
+            return;
+        }
+
+        let event_type = event.get_event_type();
+
+        let mut body = None;
+
+        for endpoint in &self.endpoints {
+            if let Some(events) = &endpoint.webhook.events {
+                if !events.contains(&event_type) {
+                    continue;
+                }
+            }
+
+            let body = body
+                .get_or_insert_with(|| Arc::new(event.to_json(DateTimeAsMicroseconds::now())))
+                .clone();
+
+            if let Err(err) = endpoint.sender.try_send(body) {
+                let reason = match err {
+                    TrySendError::Full(_) => "queue is full",
+                    TrySendError::Closed(_) => "queue is closed",
+                };
+
+                my_logger::LOGGER.write_error(
+                    "Webhooks",
+                    format!("Event {:?} is dropped: {}", event_type, reason),
+                    LogEventCtx::new().add("url", endpoint.webhook.url.as_str()),
+                );
+            }
+        }
+    }
+}
+
+async fn send_events(webhook: Arc<Webhook>, mut receiver: Receiver<Arc<Vec<u8>>>) {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .unwrap();
+
+    while let Some(body) = receiver.recv().await {
+        send_with_retries(&client, webhook.as_ref(), body).await;
+    }
+}
+
+async fn send_with_retries(client: &reqwest::Client, webhook: &Webhook, body: Arc<Vec<u8>>) {
+    let signature = webhook
+        .secret
+        .as_ref()
+        .map(|secret| get_signature(secret.as_str(), body.as_slice()));
+
+    let mut attempt_no = 0;
+
+    loop {
+        attempt_no += 1;
+
+        let mut request = client
+            .post(webhook.url.as_str())
+            .header("Content-Type", "application/json")
+            .body(body.as_ref().clone());
+
+        if let Some(signature) = &signature {
+            request = request.header(WEBHOOK_SIGNATURE_HEADER, signature.as_str());
+        }
+
+        let err = match request.send().await {
+            Ok(response) => {
+                let status = response.status();
+
+                if status.is_success() {
+                    return;
+                }
+
+                // Endpoint rejected the event. Retrying is not going to help
+                if status.is_client_error()
+                    && status != reqwest::StatusCode::REQUEST_TIMEOUT
+                    && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+                {
+                    format!("Status code: {}", status)
+                } else if attempt_no < WEBHOOK_ATTEMPTS {
+                    retry_after(webhook, attempt_no, format!("Status code: {}", status)).await;
+                    continue;
+                } else {
+                    format!("Status code: {}", status)
+                }
+            }
+            Err(err) => {
+                if attempt_no < WEBHOOK_ATTEMPTS {
+                    retry_after(webhook, attempt_no, format!("{:?}", err)).await;
+                    continue;
+                }
+
+                format!("{:?}", err)
+            }
+        };
+
+        my_logger::LOGGER.write_error(
+            "Webhooks",
+            format!("Can not send event. Event is dropped. Err: {}", err),
+            LogEventCtx::new()
+                .add("url", webhook.url.as_str())
+                .add("attemptNo", attempt_no.to_string()),
+        );
+
+        return;
+    }
+}
+
+async fn retry_after(webhook: &Webhook, attempt_no: usize, err: String) {
+    let delay = get_retry_delay(attempt_no);
+
+    my_logger::LOGGER.write_info(
+        "Webhooks",
+        format!("Can not send event. Retrying in {:?}. Err: {}", delay, err),
+        LogEventCtx::new()
+            .add("url", webhook.url.as_str())
+            .add("attemptNo", attempt_no.to_string()),
+    );
+
+    tokio::time::sleep(delay).await;
+}
+
+fn get_retry_delay(attempt_no: usize) -> Duration {
+    let delay = WEBHOOK_FIRST_RETRY_DELAY * 2u32.pow(attempt_no.saturating_sub(1).min(16) as u32);
+
+    if delay > WEBHOOK_MAX_RETRY_DELAY {
+        return WEBHOOK_MAX_RETRY_DELAY;
+    }
+
+    delay
+}
+
+// sha256=<hex encoded HMAC-SHA256 of the body>
+fn get_signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        HmacSha256::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+
+    let mut result = String::from("sha256=");
+
+    for b in mac.finalize().into_bytes() {
+        result.push_str(&format!("{:02x}", b));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::settings::WebhookEventType;
+
+    use super::*;
+
+    #[test]
+    fn test_events_are_filtered_and_dropped_when_queue_is_full() {
+        let mut settings = SettingsModel::create_test_settings(16);
+        settings.webhooks_queue_size = 1;
+        settings.webhooks = vec![
+            Webhook {
+                url: "http://localhost/all".to_string(),
+                events: None,
+                secret: None,
+            },
+            Webhook {
+                url: "http://localhost/topics".to_string(),
+                events: Some(vec![WebhookEventType::TopicCreated]),
+                secret: None,
+            },
+        ];
+
+        let publisher = WebhookPublisher::new(&settings);
+
+        publisher.publish(WebhookEvent::QueueCreated {
+            topic_id: "test-topic".to_string(),
+            queue_id: "test-queue".to_string(),
+        });
+
+        assert_eq!(publisher.endpoints[0].sender.capacity(), 0);
+        assert_eq!(publisher.endpoints[1].sender.capacity(), 1);
+
+        // Queue of the first webhook is full. Publish must not block
+        publisher.publish(WebhookEvent::TopicCreated {
+            topic_id: "test-topic".to_string(),
+        });
+
+        assert_eq!(publisher.endpoints[1].sender.capacity(), 0);
+
+        let mut receiver = publisher.endpoints[0]
+            .receiver
+            .lock()
+            .unwrap()
+            .take()
+            .unwrap();
+        let body = receiver.try_recv().unwrap();
+        assert!(String::from_utf8_lossy(body.as_slice()).contains("QueueCreated"));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_signature() {
+        assert_eq!(
+            get_signature("key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
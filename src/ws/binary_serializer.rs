@@ -0,0 +1,349 @@
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, queue_with_intervals::QueueIndexRange, SbMessageHeaders,
+};
+
+use super::{contracts::queue_type_from_u8, MySbWsError, WsContract};
+
+pub const PING: u8 = 0;
+pub const PONG: u8 = 1;
+pub const GREETING: u8 = 2;
+pub const PUBLISH: u8 = 3;
+pub const PUBLISH_RESPONSE: u8 = 4;
+pub const SUBSCRIBE: u8 = 5;
+pub const SUBSCRIBE_RESPONSE: u8 = 6;
+pub const NEW_MESSAGES: u8 = 7;
+pub const ALL_MESSAGES_CONFIRMED_AS_OK: u8 = 8;
+pub const CREATE_TOPIC_IF_NOT_EXISTS: u8 = 9;
+pub const REJECT: u8 = 12;
+pub const ALL_MESSAGES_CONFIRMED_AS_FAIL: u8 = 13;
+pub const CONFIRM_SOME_MESSAGES_AS_OK: u8 = 14;
+
+struct BinaryReader<'s> {
+    data: &'s [u8],
+    pos: usize,
+}
+
+impl<'s> BinaryReader<'s> {
+    fn new(data: &'s [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn read_slice(&mut self, len: usize) -> Result<&'s [u8], MySbWsError> {
+        if self.pos + len > self.data.len() {
+            return Err(MySbWsError::InvalidPayload(format!(
+                "Binary frame is too short. Need {} bytes at position {}. Frame size: {}",
+                len,
+                self.pos,
+                self.data.len()
+            )));
+        }
+
+        let result = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(result)
+    }
+
+    fn has_more(&self) -> bool {
+        self.pos < self.data.len()
+    }
+
+    fn read_u8(&mut self) -> Result<u8, MySbWsError> {
+        Ok(self.read_slice(1)?[0])
+    }
+
+    fn read_bool(&mut self) -> Result<bool, MySbWsError> {
+        Ok(self.read_u8()? > 0)
+    }
+
+    fn read_i32(&mut self) -> Result<i32, MySbWsError> {
+        let mut result = [0u8; 4];
+        result.copy_from_slice(self.read_slice(4)?);
+        Ok(i32::from_le_bytes(result))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, MySbWsError> {
+        let mut result = [0u8; 8];
+        result.copy_from_slice(self.read_slice(8)?);
+        Ok(i64::from_le_bytes(result))
+    }
+
+    fn read_len(&mut self) -> Result<usize, MySbWsError> {
+        let len = self.read_i32()?;
+
+        if len < 0 {
+            return Err(MySbWsError::InvalidPayload(format!(
+                "Negative length {} at position {}",
+                len, self.pos
+            )));
+        }
+
+        Ok(len as usize)
+    }
+
+    fn read_bytes(&mut self) -> Result<Vec<u8>, MySbWsError> {
+        let len = self.read_len()?;
+        Ok(self.read_slice(len)?.to_vec())
+    }
+
+    fn read_string(&mut self) -> Result<String, MySbWsError> {
+        let bytes = self.read_bytes()?;
+        match String::from_utf8(bytes) {
+            Ok(result) => Ok(result),
+            Err(err) => Err(MySbWsError::InvalidPayload(format!(
+                "Invalid utf8 string. Err: {}",
+                err
+            ))),
+        }
+    }
+}
+
+fn write_i32(dest: &mut Vec<u8>, value: i32) {
+    dest.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_i64(dest: &mut Vec<u8>, value: i64) {
+    dest.extend_from_slice(&value.to_le_bytes());
+}
+
+fn write_bytes(dest: &mut Vec<u8>, value: &[u8]) {
+    write_i32(dest, value.len() as i32);
+    dest.extend_from_slice(value);
+}
+
+fn write_string(dest: &mut Vec<u8>, value: &str) {
+    write_bytes(dest, value.as_bytes());
+}
+
+pub fn deserialize(src: &[u8]) -> Result<WsContract, MySbWsError> {
+    let mut reader = BinaryReader::new(src);
+
+    let packet_no = reader.read_u8()?;
+
+    let result = match packet_no {
+        PING => WsContract::Ping,
+        PONG => WsContract::Pong,
+        GREETING => {
+            let name = reader.read_string()?;
+            let version = reader.read_string()?;
+
+            // Api key is optional trailing string, so greeting of old clients is still valid
+            let api_key = if reader.has_more() {
+                Some(reader.read_string()?)
+            } else {
+                None
+            };
+
+            WsContract::Greeting {
+                name,
+                version: if version.is_empty() {
+                    None
+                } else {
+                    Some(version)
+                },
+                api_key,
+            }
+        }
+        PUBLISH => {
+            let request_id = reader.read_i64()?;
+            let topic_id = reader.read_string()?;
+            let persist_immediately = reader.read_bool()?;
+            let messages_count = reader.read_len()?;
+
+            let mut messages = Vec::with_capacity(messages_count);
+
+            for _ in 0..messages_count {
+                let headers_count = reader.read_u8()?;
+                let mut headers = SbMessageHeaders::with_capacity(headers_count as usize);
+
+                for _ in 0..headers_count {
+                    let key = reader.read_string()?;
+                    let value = reader.read_string()?;
+                    headers = headers.add(key, value);
+                }
+
+                let content = reader.read_bytes()?;
+                messages.push(MessageToPublish { headers, content });
+            }
+
+            WsContract::Publish {
+                request_id,
+                topic_id,
+                persist_immediately,
+                messages,
+            }
+        }
+        SUBSCRIBE => {
+            let topic_id = reader.read_string()?;
+            let queue_id = reader.read_string()?;
+            let queue_type = queue_type_from_u8(reader.read_u8()?)?;
+            WsContract::Subscribe {
+                topic_id,
+                queue_id,
+                queue_type,
+            }
+        }
+        ALL_MESSAGES_CONFIRMED_AS_OK => WsContract::NewMessagesConfirmation {
+            topic_id: reader.read_string()?,
+            queue_id: reader.read_string()?,
+            confirmation_id: reader.read_i64()?,
+        },
+        CREATE_TOPIC_IF_NOT_EXISTS => WsContract::CreateTopicIfNotExists {
+            topic_id: reader.read_string()?,
+        },
+        ALL_MESSAGES_CONFIRMED_AS_FAIL => WsContract::AllMessagesConfirmedAsFail {
+            topic_id: reader.read_string()?,
+            queue_id: reader.read_string()?,
+            confirmation_id: reader.read_i64()?,
+        },
+        CONFIRM_SOME_MESSAGES_AS_OK => {
+            let topic_id = reader.read_string()?;
+            let queue_id = reader.read_string()?;
+            let confirmation_id = reader.read_i64()?;
+            let intervals_count = reader.read_len()?;
+
+            let mut delivered = Vec::with_capacity(intervals_count);
+
+            for _ in 0..intervals_count {
+                delivered.push(QueueIndexRange {
+                    from_id: reader.read_i64()?,
+                    to_id: reader.read_i64()?,
+                });
+            }
+
+            WsContract::ConfirmSomeMessagesAsOk {
+                topic_id,
+                queue_id,
+                confirmation_id,
+                delivered,
+            }
+        }
+        _ => {
+            return Err(MySbWsError::InvalidPayload(format!(
+                "Unsupported binary packet: {}",
+                packet_no
+            )));
+        }
+    };
+
+    Ok(result)
+}
+
+pub fn serialize(src: &WsContract) -> Vec<u8> {
+    let mut result = Vec::new();
+
+    match src {
+        WsContract::Ping => {
+            result.push(PING);
+        }
+        WsContract::Pong => {
+            result.push(PONG);
+        }
+        WsContract::PublishResponse { request_id } => {
+            result.push(PUBLISH_RESPONSE);
+            write_i64(&mut result, *request_id);
+        }
+        WsContract::SubscribeResponse { topic_id, queue_id } => {
+            result.push(SUBSCRIBE_RESPONSE);
+            write_string(&mut result, topic_id);
+            write_string(&mut result, queue_id);
+        }
+        WsContract::NewMessages {
+            topic_id,
+            queue_id,
+            confirmation_id,
+            messages,
+        } => {
+            result.push(NEW_MESSAGES);
+            write_string(&mut result, topic_id);
+            write_string(&mut result, queue_id);
+            write_i64(&mut result, *confirmation_id);
+            write_i32(&mut result, messages.len() as i32);
+
+            for msg in messages {
+                write_i64(&mut result, msg.id);
+                write_i32(&mut result, msg.attempt_no);
+                result.push(msg.headers.len() as u8);
+                for (key, value) in &msg.headers {
+                    write_string(&mut result, key);
+                    write_string(&mut result, value);
+                }
+                write_bytes(&mut result, &msg.content);
+            }
+        }
+        WsContract::Reject { message } => {
+            result.push(REJECT);
+            write_string(&mut result, message);
+        }
+        _ => {
+            panic!("Contract is not supposed to be sent by server");
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_publish() {
+        let mut payload = vec![PUBLISH];
+        write_i64(&mut payload, 15);
+        write_string(&mut payload, "test-topic");
+        payload.push(1);
+        write_i32(&mut payload, 1);
+        payload.push(1);
+        write_string(&mut payload, "key");
+        write_string(&mut payload, "value");
+        write_bytes(&mut payload, &[1, 2, 3]);
+
+        match deserialize(&payload).unwrap() {
+            WsContract::Publish {
+                request_id,
+                topic_id,
+                persist_immediately,
+                messages,
+            } => {
+                assert_eq!(15, request_id);
+                assert_eq!("test-topic", topic_id);
+                assert!(persist_immediately);
+                assert_eq!(1, messages.len());
+                assert_eq!(vec![1u8, 2, 3], messages[0].content);
+            }
+            _ => panic!("Publish contract is expected"),
+        }
+    }
+
+    #[test]
+    fn test_deserialize_greeting_with_and_without_api_key() {
+        let mut payload = vec![GREETING];
+        write_string(&mut payload, "test-app");
+        write_string(&mut payload, "1.0.0");
+
+        match deserialize(&payload).unwrap() {
+            WsContract::Greeting { name, api_key, .. } => {
+                assert_eq!("test-app", name);
+                assert_eq!(None, api_key);
+            }
+            _ => panic!("Greeting contract is expected"),
+        }
+
+        write_string(&mut payload, "secret-key");
+
+        match deserialize(&payload).unwrap() {
+            WsContract::Greeting { api_key, .. } => {
+                assert_eq!(Some("secret-key".to_string()), api_key);
+            }
+            _ => panic!("Greeting contract is expected"),
+        }
+    }
+
+    #[test]
+    fn test_truncated_payload_is_rejected() {
+        let mut payload = vec![SUBSCRIBE];
+        write_string(&mut payload, "test-topic");
+
+        assert!(deserialize(&payload).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, queue_with_intervals::QueueIndexRange, subscriber::TopicQueueType,
+};
+use tokio_tungstenite::tungstenite::Message;
+
+use super::MySbWsError;
+
+#[derive(Debug, Clone, Copy)]
+pub enum WsFrameType {
+    Text,
+    Binary,
+}
+
+pub struct WsMessageToDeliver {
+    pub id: i64,
+    pub attempt_no: i32,
+    pub headers: Vec<(String, String)>,
+    pub content: Vec<u8>,
+}
+
+pub enum WsContract {
+    Ping,
+    Pong,
+    Greeting {
+        name: String,
+        version: Option<String>,
+        api_key: Option<String>,
+    },
+    Publish {
+        request_id: i64,
+        topic_id: String,
+        persist_immediately: bool,
+        messages: Vec<MessageToPublish>,
+    },
+    PublishResponse {
+        request_id: i64,
+    },
+    Subscribe {
+        topic_id: String,
+        queue_id: String,
+        queue_type: TopicQueueType,
+    },
+    SubscribeResponse {
+        topic_id: String,
+        queue_id: String,
+    },
+    NewMessages {
+        topic_id: String,
+        queue_id: String,
+        confirmation_id: i64,
+        messages: Vec<WsMessageToDeliver>,
+    },
+    NewMessagesConfirmation {
+        topic_id: String,
+        queue_id: String,
+        confirmation_id: i64,
+    },
+    CreateTopicIfNotExists {
+        topic_id: String,
+    },
+    Reject {
+        message: String,
+    },
+    AllMessagesConfirmedAsFail {
+        topic_id: String,
+        queue_id: String,
+        confirmation_id: i64,
+    },
+    ConfirmSomeMessagesAsOk {
+        topic_id: String,
+        queue_id: String,
+        confirmation_id: i64,
+        delivered: Vec<QueueIndexRange>,
+    },
+}
+
+impl WsContract {
+    pub fn deserialize(message: &Message) -> Result<Option<(Self, WsFrameType)>, MySbWsError> {
+        match message {
+            Message::Text(text) => {
+                let contract = super::json_serializer::deserialize(text.as_str())?;
+                Ok(Some((contract, WsFrameType::Text)))
+            }
+            Message::Binary(data) => {
+                let contract = super::binary_serializer::deserialize(&data[..])?;
+                Ok(Some((contract, WsFrameType::Binary)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub fn serialize(&self, frame_type: WsFrameType) -> Message {
+        match frame_type {
+            WsFrameType::Text => Message::text(super::json_serializer::serialize(self)),
+            WsFrameType::Binary => Message::binary(super::binary_serializer::serialize(self)),
+        }
+    }
+}
+
+pub fn queue_type_from_u8(src: u8) -> Result<TopicQueueType, MySbWsError> {
+    match src {
+        0 => Ok(TopicQueueType::Permanent),
+        1 => Ok(TopicQueueType::DeleteOnDisconnect),
+        2 => Ok(TopicQueueType::PermanentWithSingleConnection),
+        _ => Err(MySbWsError::InvalidPayload(format!(
+            "Invalid queue type: {}",
+            src
+        ))),
+    }
+}
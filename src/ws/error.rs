@@ -0,0 +1,16 @@
+use crate::operations::OperationFailResult;
+
+#[derive(Debug)]
+pub enum MySbWsError {
+    InvalidPayload(String),
+    GreetingIsNotDone,
+    GreetingIsAlreadyDone,
+    Unauthorized(String),
+    OperationFailResult(OperationFailResult),
+}
+
+impl From<OperationFailResult> for MySbWsError {
+    fn from(src: OperationFailResult) -> Self {
+        Self::OperationFailResult(src)
+    }
+}
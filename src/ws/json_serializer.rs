@@ -0,0 +1,283 @@
+use my_service_bus::abstractions::{
+    publisher::MessageToPublish, queue_with_intervals::QueueIndexRange, SbMessageHeaders,
+};
+use rust_extensions::base64::{FromBase64, IntoBase64};
+use serde::{Deserialize, Serialize};
+
+use crate::http::controllers::{MessageKeyValueJsonModel, MessageToDeliverHttpContract};
+
+use super::{contracts::queue_type_from_u8, MySbWsError, WsContract};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WsMessageToPublishJsonModel {
+    pub headers: Option<Vec<MessageKeyValueJsonModel>>,
+    #[serde(rename = "base64Message")]
+    pub base64_message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WsQueueIntervalJsonModel {
+    #[serde(rename = "fromId")]
+    pub from_id: i64,
+    #[serde(rename = "toId")]
+    pub to_id: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "type")]
+pub enum WsJsonContract {
+    Ping,
+    Pong,
+    Greeting {
+        name: String,
+        version: Option<String>,
+        #[serde(rename = "apiKey")]
+        api_key: Option<String>,
+    },
+    Publish {
+        #[serde(rename = "requestId")]
+        request_id: i64,
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "persistImmediately")]
+        persist_immediately: Option<bool>,
+        messages: Vec<WsMessageToPublishJsonModel>,
+    },
+    PublishResponse {
+        #[serde(rename = "requestId")]
+        request_id: i64,
+    },
+    Subscribe {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+        #[serde(rename = "queueType")]
+        queue_type: u8,
+    },
+    SubscribeResponse {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+    },
+    NewMessages {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+        #[serde(rename = "confirmationId")]
+        confirmation_id: i64,
+        messages: Vec<MessageToDeliverHttpContract>,
+    },
+    NewMessagesConfirmation {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+        #[serde(rename = "confirmationId")]
+        confirmation_id: i64,
+    },
+    CreateTopicIfNotExists {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+    },
+    Reject {
+        message: String,
+    },
+    AllMessagesConfirmedAsFail {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+        #[serde(rename = "confirmationId")]
+        confirmation_id: i64,
+    },
+    ConfirmSomeMessagesAsOk {
+        #[serde(rename = "topicId")]
+        topic_id: String,
+        #[serde(rename = "queueId")]
+        queue_id: String,
+        #[serde(rename = "confirmationId")]
+        confirmation_id: i64,
+        delivered: Vec<WsQueueIntervalJsonModel>,
+    },
+}
+
+pub fn deserialize(src: &str) -> Result<WsContract, MySbWsError> {
+    let contract: WsJsonContract = match serde_json::from_str(src) {
+        Ok(result) => result,
+        Err(err) => {
+            return Err(MySbWsError::InvalidPayload(format!(
+                "Can not deserialize json contract. Err: {}",
+                err
+            )))
+        }
+    };
+
+    let result = match contract {
+        WsJsonContract::Ping => WsContract::Ping,
+        WsJsonContract::Pong => WsContract::Pong,
+        WsJsonContract::Greeting {
+            name,
+            version,
+            api_key,
+        } => WsContract::Greeting {
+            name,
+            version,
+            api_key,
+        },
+        WsJsonContract::Publish {
+            request_id,
+            topic_id,
+            persist_immediately,
+            messages,
+        } => {
+            let mut messages_to_publish = Vec::with_capacity(messages.len());
+
+            for msg in messages {
+                messages_to_publish.push(to_message_to_publish(msg)?);
+            }
+
+            WsContract::Publish {
+                request_id,
+                topic_id,
+                persist_immediately: persist_immediately.unwrap_or(false),
+                messages: messages_to_publish,
+            }
+        }
+        WsJsonContract::PublishResponse { request_id } => {
+            WsContract::PublishResponse { request_id }
+        }
+        WsJsonContract::Subscribe {
+            topic_id,
+            queue_id,
+            queue_type,
+        } => WsContract::Subscribe {
+            topic_id,
+            queue_id,
+            queue_type: queue_type_from_u8(queue_type)?,
+        },
+        WsJsonContract::SubscribeResponse { topic_id, queue_id } => {
+            WsContract::SubscribeResponse { topic_id, queue_id }
+        }
+        WsJsonContract::NewMessages { .. } => {
+            return Err(MySbWsError::InvalidPayload(
+                "NewMessages is a server side contract".to_string(),
+            ));
+        }
+        WsJsonContract::NewMessagesConfirmation {
+            topic_id,
+            queue_id,
+            confirmation_id,
+        } => WsContract::NewMessagesConfirmation {
+            topic_id,
+            queue_id,
+            confirmation_id,
+        },
+        WsJsonContract::CreateTopicIfNotExists { topic_id } => {
+            WsContract::CreateTopicIfNotExists { topic_id }
+        }
+        WsJsonContract::Reject { message } => WsContract::Reject { message },
+        WsJsonContract::AllMessagesConfirmedAsFail {
+            topic_id,
+            queue_id,
+            confirmation_id,
+        } => WsContract::AllMessagesConfirmedAsFail {
+            topic_id,
+            queue_id,
+            confirmation_id,
+        },
+        WsJsonContract::ConfirmSomeMessagesAsOk {
+            topic_id,
+            queue_id,
+            confirmation_id,
+            delivered,
+        } => WsContract::ConfirmSomeMessagesAsOk {
+            topic_id,
+            queue_id,
+            confirmation_id,
+            delivered: delivered
+                .into_iter()
+                .map(|itm| QueueIndexRange {
+                    from_id: itm.from_id,
+                    to_id: itm.to_id,
+                })
+                .collect(),
+        },
+    };
+
+    Ok(result)
+}
+
+pub fn serialize(src: &WsContract) -> String {
+    let contract = match src {
+        WsContract::Ping => WsJsonContract::Ping,
+        WsContract::Pong => WsJsonContract::Pong,
+        WsContract::PublishResponse { request_id } => WsJsonContract::PublishResponse {
+            request_id: *request_id,
+        },
+        WsContract::SubscribeResponse { topic_id, queue_id } => WsJsonContract::SubscribeResponse {
+            topic_id: topic_id.to_string(),
+            queue_id: queue_id.to_string(),
+        },
+        WsContract::NewMessages {
+            topic_id,
+            queue_id,
+            confirmation_id,
+            messages,
+        } => WsJsonContract::NewMessages {
+            topic_id: topic_id.to_string(),
+            queue_id: queue_id.to_string(),
+            confirmation_id: *confirmation_id,
+            messages: messages
+                .iter()
+                .map(|msg| MessageToDeliverHttpContract {
+                    id: msg.id,
+                    attempt_no: msg.attempt_no,
+                    headers: msg
+                        .headers
+                        .iter()
+                        .map(|(key, value)| MessageKeyValueJsonModel {
+                            key: key.to_string(),
+                            value: value.to_string(),
+                        })
+                        .collect(),
+                    content: msg.content.into_base64(),
+                })
+                .collect(),
+        },
+        WsContract::Reject { message } => WsJsonContract::Reject {
+            message: message.to_string(),
+        },
+        _ => {
+            panic!("Contract is not supposed to be sent by server");
+        }
+    };
+
+    serde_json::to_string(&contract).unwrap()
+}
+
+fn to_message_to_publish(
+    src: WsMessageToPublishJsonModel,
+) -> Result<MessageToPublish, MySbWsError> {
+    let content = match src.base64_message.from_base64() {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            return Err(MySbWsError::InvalidPayload(format!(
+                "Can not convert content from Base64. Err: {}",
+                err
+            )))
+        }
+    };
+
+    let mut headers = SbMessageHeaders::new();
+
+    if let Some(src_headers) = src.headers {
+        for itm in src_headers {
+            headers = headers.add(itm.key, itm.value);
+        }
+    }
+
+    Ok(MessageToPublish { headers, content })
+}
@@ -0,0 +1,10 @@
+pub mod binary_serializer;
+mod contracts;
+pub use contracts::*;
+mod error;
+pub use error::*;
+pub mod json_serializer;
+mod ws_connection;
+pub use ws_connection::*;
+mod ws_server;
+pub use ws_server::start;
@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use my_service_bus::abstractions::queue_with_intervals::QueueWithIntervals;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    app::{AppContext, ListenerStatus},
+    operations,
+    sessions::{ws::MyServiceBusWsSession, MyServiceBusSession},
+};
+
+use super::{MySbWsError, WsContract, WsFrameType};
+
+pub struct WsConnection {
+    app: Arc<AppContext>,
+    listener: Arc<ListenerStatus>,
+    pub ip: String,
+    sender: UnboundedSender<Message>,
+    pub session: Option<Arc<MyServiceBusWsSession>>,
+}
+
+impl WsConnection {
+    pub fn new(
+        app: Arc<AppContext>,
+        listener: Arc<ListenerStatus>,
+        ip: String,
+        sender: UnboundedSender<Message>,
+    ) -> Self {
+        Self {
+            app,
+            listener,
+            ip,
+            sender,
+            session: None,
+        }
+    }
+
+    pub fn send(&self, contract: &WsContract, frame_type: WsFrameType) {
+        if let Some(session) = &self.session {
+            session.send(contract);
+            return;
+        }
+
+        let _ = self.sender.send(contract.serialize(frame_type));
+    }
+
+    fn get_session(&self) -> Result<Arc<MyServiceBusWsSession>, MySbWsError> {
+        match &self.session {
+            Some(session) => Ok(session.clone()),
+            None => Err(MySbWsError::GreetingIsNotDone),
+        }
+    }
+
+    pub async fn handle_incoming_packet(
+        &mut self,
+        contract: WsContract,
+        frame_type: WsFrameType,
+    ) -> Result<(), MySbWsError> {
+        match contract {
+            WsContract::Ping => {
+                self.send(&WsContract::Pong, frame_type);
+                Ok(())
+            }
+            WsContract::Pong => Ok(()),
+            WsContract::Greeting {
+                name,
+                version,
+                api_key,
+            } => {
+                if self.session.is_some() {
+                    return Err(MySbWsError::GreetingIsAlreadyDone);
+                }
+
+                println!(
+                    "New websocket connection from {} with name: {} and version {:?}",
+                    self.ip, name, version
+                );
+
+                if self.app.states.is_shutting_down() {
+                    return Err(operations::OperationFailResult::ShuttingDown.into());
+                }
+
+                let identity = operations::authenticate_session(
+                    self.app.as_ref(),
+                    self.listener.as_ref(),
+                    name.as_str(),
+                    api_key.as_deref(),
+                    self.ip.as_str(),
+                )
+                .await
+                .map_err(|err| MySbWsError::Unauthorized(format!("{:?}", err)))?;
+
+                let session = self
+                    .app
+                    .sessions
+                    .add_ws(
+                        identity.clone().unwrap_or(name),
+                        version,
+                        self.ip.to_string(),
+                        frame_type,
+                        self.sender.clone(),
+                        identity,
+                    )
+                    .await;
+
+                self.session = Some(session);
+                Ok(())
+            }
+            WsContract::Publish {
+                request_id,
+                topic_id,
+                persist_immediately,
+                messages,
+            } => {
+                let session = self.get_session()?;
+
+                let result = operations::publisher::publish_with_rate_limit(
+                    &self.app,
+                    session.session_id,
+                    session.name.as_str(),
+                    topic_id.as_str(),
+                    messages,
+                    persist_immediately,
+                )
+                .await;
+
+                match result {
+                    Ok(None) => session.send(&WsContract::PublishResponse { request_id }),
+                    Ok(Some(delay)) => {
+                        // Responses carry request_id, so only the response of throttled publisher is delayed
+                        tokio::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            session.send(&WsContract::PublishResponse { request_id });
+                        });
+                    }
+                    Err(err) => session.send(&WsContract::Reject {
+                        message: format!("{:?}", err),
+                    }),
+                }
+
+                Ok(())
+            }
+            WsContract::Subscribe {
+                topic_id,
+                queue_id,
+                queue_type,
+            } => {
+                let session = self.get_session()?;
+
+                operations::subscriber::subscribe_to_queue(
+                    &self.app,
+                    topic_id.to_string(),
+                    queue_id.to_string(),
+                    queue_type,
+                    session.clone(),
+                )
+                .await?;
+
+                session.send(&WsContract::SubscribeResponse { topic_id, queue_id });
+
+                Ok(())
+            }
+            WsContract::CreateTopicIfNotExists { topic_id } => {
+                let session = self.get_session()?;
+
+                operations::publisher::create_topic_if_not_exists(
+                    &self.app,
+                    Some(session.get_session_id()),
+                    topic_id.as_str(),
+                )
+                .await?;
+
+                Ok(())
+            }
+            WsContract::NewMessagesConfirmation {
+                topic_id,
+                queue_id,
+                confirmation_id,
+            } => {
+                let session = self.get_session()?;
+
+                operations::delivery_confirmation::all_confirmed(
+                    &self.app,
+                    topic_id.as_str(),
+                    queue_id.as_str(),
+                    confirmation_id.into(),
+                    session.session_id,
+                )
+                .await?;
+
+                Ok(())
+            }
+            WsContract::AllMessagesConfirmedAsFail {
+                topic_id,
+                queue_id,
+                confirmation_id,
+            } => {
+                let session = self.get_session()?;
+
+                operations::delivery_confirmation::all_fail(
+                    &self.app,
+                    topic_id.as_str(),
+                    queue_id.as_str(),
+                    confirmation_id.into(),
+                    session.session_id,
+                )
+                .await?;
+
+                Ok(())
+            }
+            WsContract::ConfirmSomeMessagesAsOk {
+                topic_id,
+                queue_id,
+                confirmation_id,
+                delivered,
+            } => {
+                let session = self.get_session()?;
+
+                operations::delivery_confirmation::some_messages_are_confirmed(
+                    &self.app,
+                    topic_id.as_str(),
+                    queue_id.as_str(),
+                    confirmation_id.into(),
+                    session.session_id,
+                    QueueWithIntervals::restore(delivered),
+                )
+                .await?;
+
+                Ok(())
+            }
+            WsContract::PublishResponse { request_id: _ } => {
+                //This is a client packet
+                Ok(())
+            }
+            WsContract::SubscribeResponse {
+                topic_id: _,
+                queue_id: _,
+            } => {
+                //This is a client packet
+                Ok(())
+            }
+            WsContract::NewMessages { .. } => {
+                //This is a client packet
+                Ok(())
+            }
+            WsContract::Reject { message: _ } => {
+                //This is a client packet
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use futures_util::{SinkExt, StreamExt};
+use my_logger::LogEventCtx;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::{
+    app::{AppContext, ListenerStatus},
+    sessions::MyServiceBusSession,
+};
+
+use super::{MySbWsError, WsConnection, WsContract};
+
+const READ_TIMEOUT: Duration = Duration::from_secs(60);
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+pub fn start(app: Arc<AppContext>, listener_status: Arc<ListenerStatus>) {
+    tokio::spawn(accept_loop(app, listener_status));
+}
+
+async fn accept_loop(app: Arc<AppContext>, listener_status: Arc<ListenerStatus>) {
+    let addr = listener_status.address;
+
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            panic!("Can not start WebSocket server at {}. Err: {:?}", addr, err);
+        }
+    };
+
+    println!("WebSocket server is started at {}", addr);
+
+    while !app.states.is_shutting_down() {
+        match listener.accept().await {
+            Ok((stream, peer_addr)) => {
+                let app = app.clone();
+                let listener_status = listener_status.clone();
+
+                tokio::spawn(async move {
+                    listener_status.connected();
+                    handle_connection(app, listener_status.clone(), stream, peer_addr).await;
+                    listener_status.disconnected();
+                });
+            }
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "WebSocket Accept".to_string(),
+                    format!("{:?}", err),
+                    LogEventCtx::new(),
+                );
+
+                tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+            }
+        }
+    }
+}
+
+async fn handle_connection(
+    app: Arc<AppContext>,
+    listener_status: Arc<ListenerStatus>,
+    stream: TcpStream,
+    peer_addr: SocketAddr,
+) {
+    let ws_stream = match tokio_tungstenite::accept_async(stream).await {
+        Ok(ws_stream) => ws_stream,
+        Err(err) => {
+            my_logger::LOGGER.write_error(
+                "WebSocket Handshake".to_string(),
+                format!("{:?}", err),
+                LogEventCtx::new().add("ip", peer_addr.to_string()),
+            );
+            return;
+        }
+    };
+
+    let (mut write, mut read) = ws_stream.split();
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<Message>();
+
+    tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            let is_close = matches!(message, Message::Close(_));
+
+            if write.send(message).await.is_err() {
+                break;
+            }
+
+            if is_close {
+                break;
+            }
+        }
+    });
+
+    let mut connection =
+        WsConnection::new(app.clone(), listener_status, peer_addr.to_string(), sender);
+
+    loop {
+        let message = match tokio::time::timeout(READ_TIMEOUT, read.next()).await {
+            Ok(Some(Ok(message))) => message,
+            Ok(Some(Err(err))) => {
+                println!("WebSocket connection {} read error: {:?}", peer_addr, err);
+                break;
+            }
+            Ok(None) => break,
+            Err(_) => {
+                println!(
+                    "WebSocket connection {} has no incoming traffic for {:?}. Disconnecting",
+                    peer_addr, READ_TIMEOUT
+                );
+                break;
+            }
+        };
+
+        if let Message::Close(_) = &message {
+            break;
+        }
+
+        if let Some(session) = &connection.session {
+            session.update_read_amount(message.len());
+        }
+
+        let (contract, frame_type) = match WsContract::deserialize(&message) {
+            Ok(Some(result)) => result,
+            Ok(None) => continue,
+            Err(err) => {
+                my_logger::LOGGER.write_error(
+                    "Handle WebSocket Payload".to_string(),
+                    format!("{:?}", err),
+                    LogEventCtx::new().add("ip", peer_addr.to_string()),
+                );
+                break;
+            }
+        };
+
+        if let Err(err) = connection
+            .handle_incoming_packet(contract, frame_type)
+            .await
+        {
+            my_logger::LOGGER.write_error(
+                "Handle WebSocket Payload".to_string(),
+                format!("{:?}", err),
+                LogEventCtx::new().add("ip", peer_addr.to_string()),
+            );
+
+            let unauthorized = matches!(err, MySbWsError::Unauthorized(_));
+
+            connection.send(
+                &WsContract::Reject {
+                    message: format!("{:?}", err),
+                },
+                frame_type,
+            );
+
+            // Unauthenticated connection is disconnected the same way tcp one is
+            if unauthorized {
+                break;
+            }
+        }
+    }
+
+    if let Some(session) = connection.session.take() {
+        session.disconnect().await;
+
+        if let Some(session) = app.sessions.remove_by_session_id(session.session_id).await {
+            crate::operations::sessions::disconnect(app.as_ref(), session).await;
+        }
+    }
+}
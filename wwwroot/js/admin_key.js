@@ -0,0 +1,28 @@
+// Admin api requires admin key. It is kept in the cookie, so logs pages which are opened by links get it too.
+// Server accepts the cookie only for reads
+var AdminKey = (function () {
+    var cookieName = 'AdminKey';
+    var asked = false;
+
+    function setKey(key) {
+        document.cookie = cookieName + '=' + encodeURIComponent(key) + '; path=/; SameSite=Strict';
+    }
+
+    $(document).ajaxError(function (event, xhr) {
+        if (asked || (xhr.status != 401 && xhr.status != 403))
+            return;
+
+        asked = true;
+
+        var key = window.prompt(xhr.status == 401
+            ? 'Admin key is required'
+            : 'Admin key has no permission. Please enter other admin key');
+
+        if (key) {
+            setKey(key);
+            asked = false;
+        }
+    });
+
+    return { setKey: setKey };
+}());